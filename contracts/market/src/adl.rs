@@ -0,0 +1,140 @@
+//! # Auto-Deleveraging
+//!
+//! Ranking and sizing helpers for forced reductions of winning positions
+//! when the vault can no longer comfortably cover trader profits.
+
+use noether_common::{Position, BASIS_POINTS};
+
+/// Calculate vault coverage of net trader PnL in basis points.
+/// Returns i128::MAX when traders are not net profitable.
+pub fn calculate_coverage_bps(vault_usdc: i128, trader_pnl: i128) -> i128 {
+    if trader_pnl <= 0 {
+        return i128::MAX;
+    }
+    vault_usdc * (BASIS_POINTS as i128) / trader_pnl
+}
+
+/// Calculate the ADL ranking score of a position.
+///
+/// # Formula
+/// score = (pnl / collateral) × (size / collateral)
+///
+/// Profit as a share of collateral times effective leverage, so the most
+/// profitable and most leveraged positions are reduced first.
+/// Positions that are not in profit score 0 and are never deleveraged.
+pub fn calculate_adl_score(position: &Position, pnl: i128) -> i128 {
+    if pnl <= 0 || position.collateral <= 0 {
+        return 0;
+    }
+
+    let pnl_bps = pnl * (BASIS_POINTS as i128) / position.collateral;
+    pnl_bps * position.size / position.collateral
+}
+
+/// Calculate the profit that must be realized to bring coverage back to target.
+///
+/// # Formula
+/// Realizing profit R pays it out of the vault and removes it from trader PnL:
+/// ```
+/// (V - R) / (P - R) = target  =>  R = (target·P - V) / (target - 1)
+/// ```
+///
+/// # Returns
+/// Profit to realize (0 if already at target, capped at `trader_pnl`)
+pub fn calculate_adl_profit_to_realize(
+    vault_usdc: i128,
+    trader_pnl: i128,
+    target_coverage_bps: u32,
+) -> i128 {
+    let bps = BASIS_POINTS as i128;
+    let target = target_coverage_bps as i128;
+    if trader_pnl <= 0 || target <= bps {
+        return 0;
+    }
+
+    let shortfall = target * trader_pnl - vault_usdc * bps;
+    if shortfall <= 0 {
+        return 0;
+    }
+
+    // Round up so the reduction is not one unit short of target
+    let required = (shortfall + (target - bps) - 1) / (target - bps);
+    required.min(trader_pnl)
+}
+
+/// Calculate the fraction of a position to close to realize `profit_needed`.
+///
+/// # Returns
+/// Fraction in basis points (rounded up); BASIS_POINTS closes the position
+pub fn calculate_adl_close_bps(position_pnl: i128, profit_needed: i128) -> u32 {
+    let bps = BASIS_POINTS as i128;
+    if position_pnl <= 0 || profit_needed >= position_pnl {
+        return BASIS_POINTS;
+    }
+    if profit_needed <= 0 {
+        return 0;
+    }
+
+    let close_bps = (profit_needed * bps + position_pnl - 1) / position_pnl;
+    close_bps.clamp(1, bps) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use noether_common::{Direction, PRECISION};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+
+    fn create_position(env: &Env, collateral: i128, size: i128) -> Position {
+        Position {
+            id: 1,
+            trader: Address::generate(env),
+            asset: Symbol::new(env, "XLM"),
+            collateral,
+            size,
+            entry_price: PRECISION,
+            direction: Direction::Long,
+            leverage: (size / collateral) as u32,
+            liquidation_price: 0,
+            timestamp: 1000000,
+            funding_index: 0,
+            borrow_index: 0,
+            accumulated_funding: 0,
+            accrued_borrow_fee: 0,
+        }
+    }
+
+    #[test]
+    fn test_adl_score_ranks_leverage_and_profit() {
+        let env = Env::default();
+        let low_leverage = create_position(&env, 500 * PRECISION, 1000 * PRECISION);
+        let high_leverage = create_position(&env, 100 * PRECISION, 1000 * PRECISION);
+
+        // Same $100 profit; the 10x position ranks above the 2x position
+        let low = calculate_adl_score(&low_leverage, 100 * PRECISION);
+        let high = calculate_adl_score(&high_leverage, 100 * PRECISION);
+        assert!(high > low);
+
+        // Losing positions are never selected
+        assert_eq!(calculate_adl_score(&high_leverage, -PRECISION), 0);
+    }
+
+    #[test]
+    fn test_adl_profit_restores_target_coverage() {
+        // Vault $1100 against $1000 trader PnL (110%), target 150%
+        let vault = 1100 * PRECISION;
+        let pnl = 1000 * PRECISION;
+        let realize = calculate_adl_profit_to_realize(vault, pnl, 15_000);
+
+        // R = (1.5 × 1000 - 1100) / 0.5 = 800
+        assert_eq!(realize, 800 * PRECISION);
+        assert_eq!(calculate_coverage_bps(vault - realize, pnl - realize), 15_000);
+    }
+
+    #[test]
+    fn test_adl_close_bps() {
+        assert_eq!(calculate_adl_close_bps(100 * PRECISION, 25 * PRECISION), 2500);
+        assert_eq!(calculate_adl_close_bps(100 * PRECISION, 200 * PRECISION), BASIS_POINTS);
+        assert_eq!(calculate_adl_close_bps(100 * PRECISION, 0), 0);
+    }
+}
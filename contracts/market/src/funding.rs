@@ -19,9 +19,11 @@
 //!
 //! ## Application
 //!
-//! Funding is applied hourly. The payment per position is:
+//! Funding accrues continuously into a per-asset cumulative index, updated on
+//! every open, close and funding tick. Each position stores the index it last
+//! settled at, so the payment is:
 //! ```
-//! funding_payment = position_size * funding_rate * hours_elapsed
+//! funding_payment = position_size * (index_now - index_entry)
 //! ```
 
 use noether_common::{Direction, BASIS_POINTS};
@@ -136,6 +138,7 @@ pub fn time_until_next_funding(last_funding_time: u64, current_time: u64, interv
 #[cfg(test)]
 mod tests {
     use super::*;
+    use noether_common::PRECISION;
 
     #[test]
     fn test_balanced_market() {
@@ -3,11 +3,12 @@
 //! The core trading engine for Noether PerpDex.
 //!
 //! ## Features
-//! - Open leveraged long/short positions (1-10x)
-//! - Close positions and settle PnL
+//! - Open leveraged long/short positions (1-10x) and add size to them
+//! - Close positions (fully or partially) and settle PnL
 //! - Liquidation mechanism for underwater positions
 //! - Funding rate to balance long/short interest
-//! - Position management (add collateral)
+//! - Position management (add/remove collateral)
+//! - Asset registry with per-asset market parameters
 //!
 //! ## Architecture
 //! - Uses Oracle Adapter for price feeds
@@ -35,13 +36,15 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, token, Address, Env, Symbol, Vec, IntoVal};
+use soroban_sdk::{contract, contractimpl, token, Address, Env, Map, Symbol, Vec, IntoVal};
 use noether_common::{
-    NoetherError, Position, Direction, MarketConfig, MarketStats,
-    Order, OrderType, OrderStatus, TriggerCondition, KeeperFeeConfig,
+    NoetherError, Position, Direction, MarketConfig, MarketStats, AssetInfo, BASIS_POINTS, MAX_POSITION_ORDERS, MAX_PAGE_SIZE,
+    Order, OrderType, OrderStatus, TriggerCondition, ModifyOrderParams, KeeperFeeConfig, KeeperInfo, BatchResult,
+    PositionHealth, AccountHealth,
     calculate_position_size, calculate_liquidation_price, calculate_pnl,
-    calculate_trading_fee, calculate_funding_rate, calculate_funding_payment,
-    calculate_keeper_reward, should_liquidate,
+    calculate_trading_fee, calculate_funding_rate, calculate_funding_index_delta,
+    calculate_accrued_funding, calculate_borrow_rate, calculate_borrow_index_delta, calculate_accrued_borrow_fee,
+    calculate_average_entry_price, calculate_position_quantity, calculate_execution_price,
 };
 
 mod storage;
@@ -49,8 +52,43 @@ mod position;
 mod trading;
 mod liquidation;
 mod funding;
+mod adl;
 
 use storage::*;
+use trading::{
+    calculate_close_size, calculate_effective_leverage, calculate_partial_close, calculate_partial_close_size,
+    calculate_trailing_stop_price, is_entry_price_acceptable, is_exit_price_acceptable, is_stop_loss_trigger_valid,
+    is_take_profit_trigger_valid, ratchet_trailing_mark,
+};
+use liquidation::{
+    PartialLiquidation, calculate_current_margin, calculate_distance_to_liquidation_pct,
+    calculate_liquidation_payout, calculate_liquidation_price_from_collateral, calculate_margin_ratio_bps,
+    calculate_partial_liquidation, should_liquidate,
+};
+use position::calculate_total_unrealized_pnl;
+use adl::{
+    calculate_adl_close_bps, calculate_adl_profit_to_realize, calculate_adl_score,
+    calculate_coverage_bps,
+};
+
+/// Keeper jobs tracked by the keeper registry
+enum KeeperJob {
+    Liquidation,
+    Deleverage,
+    OrderExecution,
+    OrderExpiry,
+    FundingTick,
+    TrailingUpdate,
+    PnlSync,
+}
+
+/// Where a close order attached to a position triggers
+enum CloseTrigger {
+    /// Fixed stop-loss or take-profit price
+    Price(i128),
+    /// Trailing stop `distance_bps` behind the best price seen
+    Trailing { distance_bps: u32, mark: i128 },
+}
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Contract Definition
@@ -88,9 +126,7 @@ impl MarketContract {
         admin.require_auth();
 
         // Validate config
-        if config.max_leverage < 1 || config.max_leverage > 100 {
-            return Err(NoetherError::InvalidParameter);
-        }
+        Self::validate_config(&config)?;
 
         // Store addresses
         set_admin(&env, &admin);
@@ -102,11 +138,8 @@ impl MarketContract {
         set_config(&env, &config);
 
         // Initialize state
+        // Open interest and funding state are tracked per asset and start at zero
         set_position_counter(&env, 0);
-        set_total_long_size(&env, 0);
-        set_total_short_size(&env, 0);
-        set_last_funding_time(&env, env.ledger().timestamp());
-        init_position_index(&env);
 
         set_initialized(&env, true);
         set_paused(&env, false);
@@ -133,6 +166,7 @@ impl MarketContract {
     /// * `collateral` - USDC collateral amount (7 decimals)
     /// * `leverage` - Leverage multiplier (1-10)
     /// * `direction` - Long or Short
+    /// * `acceptable_price` - Maximum entry price for longs, minimum for shorts (0 = no limit)
     ///
     /// # Returns
     /// The created Position
@@ -140,7 +174,7 @@ impl MarketContract {
     /// # Flow
     /// 1. Validate parameters
     /// 2. Check Vault liquidity for potential payout
-    /// 3. Fetch price from oracle
+    /// 3. Fetch price from oracle, apply skew-based price impact and check it against `acceptable_price`
     /// 4. Calculate position size and liquidation price
     /// 5. Transfer collateral from trader
     /// 6. Deduct trading fee
@@ -152,13 +186,14 @@ impl MarketContract {
         collateral: i128,
         leverage: u32,
         direction: Direction,
+        acceptable_price: i128,
     ) -> Result<Position, NoetherError> {
         require_initialized(&env)?;
         require_not_paused(&env)?;
 
         trader.require_auth();
 
-        let config = get_config(&env);
+        let config = Self::get_listed_asset_config(&env, &asset)?;
 
         // Validate parameters
         if collateral < config.min_collateral {
@@ -174,13 +209,20 @@ impl MarketContract {
             return Err(NoetherError::PositionTooLarge);
         }
 
-        // Check Vault has enough liquidity for potential payout
+        Self::check_open_interest_cap(&env, &asset, &config, direction, size)?;
+
+        // Reserve Vault liquidity for potential payout
         // Maximum potential payout is the position size (100% gain)
         let vault_address = get_vault(&env);
-        Self::check_vault_liquidity(&env, &vault_address, size)?;
-
-        // Fetch current price
-        let entry_price = Self::get_oracle_price(&env, &asset)?;
+        Self::reserve_vault_liquidity(&env, &vault_address, size)?;
+
+        // Fetch current price and apply skew-based price impact
+        let oracle_price = Self::get_oracle_price(&env, &asset)?;
+        let (entry_price, price_impact) =
+            Self::get_execution_price(&env, &asset, &config, oracle_price, direction, size, true);
+        if !is_entry_price_acceptable(direction, entry_price, acceptable_price) {
+            return Err(NoetherError::SlippageExceeded);
+        }
 
         // Calculate liquidation price
         let liquidation_price = calculate_liquidation_price(
@@ -199,6 +241,10 @@ impl MarketContract {
         let token_client = token::Client::new(&env, &usdc_token);
         token_client.transfer(&trader, &env.current_contract_address(), &collateral);
 
+        // Bring the funding and borrow indexes up to date; the position accrues from here
+        let funding_index = Self::update_funding_index(&env, &asset);
+        let borrow_index = Self::update_borrow_index(&env, &asset);
+
         // Generate position ID
         let position_id = next_position_id(&env);
 
@@ -214,32 +260,181 @@ impl MarketContract {
             leverage,
             liquidation_price,
             timestamp: env.ledger().timestamp(),
-            last_funding_time: env.ledger().timestamp(),
+            funding_index,
+            borrow_index,
             accumulated_funding: 0,
+            accrued_borrow_fee: 0,
         };
 
         // Store position
-        save_position(&env, &position);
+        save_new_position(&env, &position);
 
         // Update market stats
-        match direction {
-            Direction::Long => {
-                let total = get_total_long_size(&env);
-                set_total_long_size(&env, total + size);
-            }
-            Direction::Short => {
-                let total = get_total_short_size(&env);
-                set_total_short_size(&env, total + size);
-            }
-        }
+        Self::update_open_interest(
+            &env,
+            &asset,
+            direction,
+            size,
+            calculate_position_quantity(size, entry_price),
+        );
 
-        // Transfer fee to vault
-        token_client.transfer(&env.current_contract_address(), &vault_address, &fee);
+        // Transfer fee to vault, with the insurance fund's share
+        Self::pay_trading_fee(&env, &vault_address, &token_client, fee, config.insurance_fee_bps)?;
 
         // Emit event
         env.events().publish(
             (Symbol::new(&env, "position_opened"),),
-            (position.id, trader, asset, size, direction, leverage, entry_price),
+            (position.id, trader, asset, size, direction, leverage, entry_price, price_impact),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(position)
+    }
+
+    /// Add size to an existing position.
+    ///
+    /// # Arguments
+    /// * `trader` - Address of the trader (must own position)
+    /// * `position_id` - ID of the position to increase
+    /// * `extra_collateral` - Additional USDC collateral (7 decimals)
+    /// * `leverage` - Leverage applied to the extra collateral (1-10)
+    ///
+    /// # Returns
+    /// The updated Position
+    ///
+    /// # Flow
+    /// 1. Settle pending funding
+    /// 2. Check size limits and reserve Vault liquidity for the added size
+    /// 3. Compute the volume-weighted entry price
+    /// 4. Charge the trading fee on the added size
+    /// 5. Recompute leverage (rounded up) and the liquidation price from the combined collateral
+    pub fn increase_position(
+        env: Env,
+        trader: Address,
+        position_id: u64,
+        extra_collateral: i128,
+        leverage: u32,
+    ) -> Result<Position, NoetherError> {
+        require_initialized(&env)?;
+        require_not_paused(&env)?;
+
+        trader.require_auth();
+
+        if extra_collateral <= 0 {
+            return Err(NoetherError::InvalidAmount);
+        }
+
+        // Get position
+        let mut position = get_position(&env, position_id)
+            .ok_or(NoetherError::PositionNotFound)?;
+
+        // Verify ownership
+        if position.trader != trader {
+            return Err(NoetherError::NotPositionOwner);
+        }
+
+        // Adding exposure requires the asset to still be listed
+        let config = Self::get_listed_asset_config(&env, &position.asset)?;
+        if leverage < 1 || leverage > config.max_leverage {
+            return Err(NoetherError::InvalidLeverage);
+        }
+
+        // Calculate added and combined size
+        let added_size = calculate_position_size(extra_collateral, leverage);
+        let new_size = position.size + added_size;
+        if new_size > config.max_position_size {
+            return Err(NoetherError::PositionTooLarge);
+        }
+
+        Self::check_open_interest_cap(&env, &position.asset, &config, position.direction, added_size)?;
+
+        // Reserve Vault liquidity for the added size
+        let vault_address = get_vault(&env);
+        Self::reserve_vault_liquidity(&env, &vault_address, added_size)?;
+
+        // Settle funding on the existing size before it changes
+        Self::apply_funding_to_position(&env, &mut position)?;
+
+        // Fetch current price and apply skew-based price impact to the added size
+        let current_price = Self::get_oracle_price(&env, &position.asset)?;
+        let (execution_price, price_impact) = Self::get_execution_price(
+            &env,
+            &position.asset,
+            &config,
+            current_price,
+            position.direction,
+            added_size,
+            true,
+        );
+
+        // Calculate and deduct trading fee on the added size
+        let fee = calculate_trading_fee(added_size, config.trading_fee_bps);
+        let net_collateral = extra_collateral - fee;
+        if net_collateral <= 0 {
+            return Err(NoetherError::InsufficientCollateral);
+        }
+
+        // Update position
+        let old_quantity = calculate_position_quantity(position.size, position.entry_price);
+        position.entry_price = calculate_average_entry_price(
+            position.size,
+            position.entry_price,
+            added_size,
+            execution_price,
+        )?;
+        position.size = new_size;
+        position.collateral += net_collateral;
+        position.leverage = calculate_effective_leverage(position.size, position.collateral)
+            .min(config.max_leverage);
+        position.liquidation_price = calculate_liquidation_price_from_collateral(
+            position.entry_price,
+            position.size,
+            position.collateral,
+            position.direction,
+            config.maintenance_margin_bps,
+        );
+
+        // The combined position must not be immediately liquidatable
+        if should_liquidate(&position, current_price, config.maintenance_margin_bps) {
+            return Err(NoetherError::InsufficientMargin);
+        }
+
+        // Transfer extra collateral from trader to market contract
+        let usdc_token = get_usdc_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&trader, &env.current_contract_address(), &extra_collateral);
+
+        // Store position
+        save_position(&env, &position);
+
+        // Update market stats
+        let new_quantity = calculate_position_quantity(position.size, position.entry_price);
+        Self::update_open_interest(
+            &env,
+            &position.asset,
+            position.direction,
+            added_size,
+            new_quantity - old_quantity,
+        );
+
+        // Transfer fee to vault, with the insurance fund's share
+        Self::pay_trading_fee(&env, &vault_address, &token_client, fee, config.insurance_fee_bps)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "position_increased"),),
+            (
+                position_id,
+                trader,
+                position.asset.clone(),
+                position.direction,
+                added_size,
+                position.size,
+                execution_price,
+                position.entry_price,
+                fee,
+                price_impact,
+            ),
         );
 
         extend_instance_ttl(&env);
@@ -252,6 +447,7 @@ impl MarketContract {
     /// # Arguments
     /// * `trader` - Address of the trader (must own position)
     /// * `position_id` - ID of position to close
+    /// * `acceptable_price` - Minimum exit price for longs, maximum for shorts (0 = no limit)
     ///
     /// # Returns
     /// Final PnL amount (positive = profit, negative = loss)
@@ -259,7 +455,7 @@ impl MarketContract {
     /// # Flow
     /// 1. Verify ownership
     /// 2. Apply any pending funding
-    /// 3. Calculate PnL at current price
+    /// 3. Calculate PnL at the oracle price adjusted for price impact, checked against `acceptable_price`
     /// 4. Settle with vault (handles fund transfer for wins)
     /// 5. Transfer loss to vault if trader lost
     /// 6. Return collateral +/- PnL to trader
@@ -267,6 +463,7 @@ impl MarketContract {
         env: Env,
         trader: Address,
         position_id: u64,
+        acceptable_price: i128,
     ) -> Result<i128, NoetherError> {
         require_initialized(&env)?;
         require_not_paused(&env)?;
@@ -285,19 +482,33 @@ impl MarketContract {
         // Apply pending funding
         Self::apply_funding_to_position(&env, &mut position)?;
 
-        // Get current price
-        let current_price = Self::get_oracle_price(&env, &position.asset)?;
+        // Get current price and apply skew-based price impact
+        let config = Self::get_asset_config_or_default(&env, &position.asset);
+        let oracle_price = Self::get_oracle_price(&env, &position.asset)?;
+        let (current_price, price_impact) = Self::get_execution_price(
+            &env,
+            &position.asset,
+            &config,
+            oracle_price,
+            position.direction,
+            position.size,
+            false,
+        );
+        if !is_exit_price_acceptable(position.direction, current_price, acceptable_price) {
+            return Err(NoetherError::SlippageExceeded);
+        }
 
         // Calculate PnL
         let pnl = calculate_pnl(&position, current_price)?;
 
         // Calculate amount to return to trader
-        let to_trader = position.collateral + pnl - position.accumulated_funding;
+        let to_trader = position.collateral + pnl - position.accumulated_funding - position.accrued_borrow_fee;
 
-        // Settle with vault
+        // Release the position's reserved liquidity, then settle with vault
         // - If pnl > 0: Vault transfers profit to Market
         // - If pnl < 0: Vault just updates accounting
         let vault_address = get_vault(&env);
+        Self::release_vault_liquidity(&env, &vault_address, position.size)?;
         Self::settle_with_vault(&env, &vault_address, pnl)?;
 
         // Get token client for transfers
@@ -320,24 +531,25 @@ impl MarketContract {
             );
         }
 
+        // Pay accrued borrow fees to the vault
+        Self::pay_fees_to_vault(&env, &vault_address, &token_client, position.accrued_borrow_fee, 0)?;
+
         // Transfer to trader (if positive)
         if to_trader > 0 {
             token_client.transfer(&env.current_contract_address(), &trader, &to_trader);
         }
 
         // Update market stats
-        match position.direction {
-            Direction::Long => {
-                let total = get_total_long_size(&env);
-                set_total_long_size(&env, total - position.size);
-            }
-            Direction::Short => {
-                let total = get_total_short_size(&env);
-                set_total_short_size(&env, total - position.size);
-            }
-        }
+        Self::update_open_interest(
+            &env,
+            &position.asset,
+            position.direction,
+            -position.size,
+            -calculate_position_quantity(position.size, position.entry_price),
+        );
 
-        // Delete position
+        // Cancel attached close orders, then delete position
+        Self::cancel_position_orders(&env, position_id, Symbol::new(&env, "position_closed"));
         delete_position(&env, position_id, &trader);
 
         // Emit comprehensive event with full trade data for frontend history
@@ -353,6 +565,7 @@ impl MarketContract {
                 current_price,  // exit_price
                 pnl,
                 position.accumulated_funding,
+                price_impact,
             ),
         );
 
@@ -361,6 +574,68 @@ impl MarketContract {
         Ok(pnl)
     }
 
+    /// Partially close an existing position.
+    ///
+    /// # Arguments
+    /// * `trader` - Address of the trader (must own position)
+    /// * `position_id` - ID of position to decrease
+    /// * `close_bps` - Fraction of the position to close in basis points (1-9999)
+    /// * `acceptable_price` - Minimum exit price for longs, maximum for shorts (0 = no limit)
+    ///
+    /// # Returns
+    /// Realized PnL of the closed portion (positive = profit, negative = loss)
+    ///
+    /// # Flow
+    /// 1. Verify ownership and exit price
+    /// 2. Apply any pending funding
+    /// 3. Split collateral, size and funding proportionally
+    /// 4. Settle the closed portion's PnL with the vault
+    /// 5. Keep the remainder open with a recomputed liquidation price
+    pub fn decrease_position(
+        env: Env,
+        trader: Address,
+        position_id: u64,
+        close_bps: u32,
+        acceptable_price: i128,
+    ) -> Result<i128, NoetherError> {
+        require_initialized(&env)?;
+        require_not_paused(&env)?;
+
+        trader.require_auth();
+
+        // Full closes go through close_position
+        if close_bps == 0 || close_bps >= BASIS_POINTS {
+            return Err(NoetherError::InvalidParameter);
+        }
+
+        // Get position
+        let mut position = get_position(&env, position_id)
+            .ok_or(NoetherError::PositionNotFound)?;
+
+        // Verify ownership
+        if position.trader != trader {
+            return Err(NoetherError::NotPositionOwner);
+        }
+
+        // Apply pending funding
+        Self::apply_funding_to_position(&env, &mut position)?;
+
+        // The remainder must still meet the minimum collateral
+        let config = Self::get_asset_config_or_default(&env, &position.asset);
+        let (_, close_size, remaining_collateral, _) = calculate_partial_close(&position, close_bps);
+        if remaining_collateral < config.min_collateral {
+            return Err(NoetherError::InsufficientCollateral);
+        }
+
+        // Close the portion at the execution price, checked against the trader's limit
+        let oracle_price = Self::get_oracle_price(&env, &position.asset)?;
+        let pnl = Self::reduce_position(&env, position, close_size, oracle_price, acceptable_price)?;
+
+        extend_instance_ttl(&env);
+
+        Ok(pnl)
+    }
+
     /// Add collateral to an existing position.
     /// Reduces liquidation risk.
     pub fn add_collateral(
@@ -394,15 +669,13 @@ impl MarketContract {
         // Update position
         position.collateral += amount;
 
-        // Recalculate liquidation price with new effective leverage
-        let config = get_config(&env);
-        let new_leverage = (position.size / position.collateral) as u32;
-        let effective_leverage = new_leverage.max(1).min(config.max_leverage);
-
-        position.liquidation_price = calculate_liquidation_price(
+        // Recalculate liquidation price from the new collateral
+        let config = Self::get_asset_config_or_default(&env, &position.asset);
+        position.liquidation_price = calculate_liquidation_price_from_collateral(
             position.entry_price,
-            effective_leverage,
-            position.direction.clone(),
+            position.size,
+            position.collateral,
+            position.direction,
             config.maintenance_margin_bps,
         );
 
@@ -417,6 +690,88 @@ impl MarketContract {
         Ok(())
     }
 
+    /// Withdraw excess collateral from an existing position.
+    ///
+    /// The position must stay above maintenance margin plus the configured
+    /// withdrawal buffer at the current oracle price, including accrued funding.
+    ///
+    /// # Arguments
+    /// * `trader` - Address of the trader (must own position)
+    /// * `position_id` - ID of the position
+    /// * `amount` - USDC collateral to withdraw (7 decimals)
+    pub fn remove_collateral(
+        env: Env,
+        trader: Address,
+        position_id: u64,
+        amount: i128,
+    ) -> Result<(), NoetherError> {
+        require_initialized(&env)?;
+        require_not_paused(&env)?;
+
+        if amount <= 0 {
+            return Err(NoetherError::InvalidAmount);
+        }
+
+        trader.require_auth();
+
+        // Get position
+        let mut position = get_position(&env, position_id)
+            .ok_or(NoetherError::PositionNotFound)?;
+
+        if position.trader != trader {
+            return Err(NoetherError::NotPositionOwner);
+        }
+
+        let config = Self::get_asset_config_or_default(&env, &position.asset);
+        if position.collateral - amount < config.min_collateral {
+            return Err(NoetherError::InsufficientCollateral);
+        }
+
+        // Apply pending funding so the margin check includes it
+        Self::apply_funding_to_position(&env, &mut position)?;
+        let current_price = Self::get_oracle_price(&env, &position.asset)?;
+
+        // Update position
+        position.collateral -= amount;
+
+        // Leverage on remaining collateral must stay within limits
+        if position.size > position.collateral * (config.max_leverage as i128) {
+            return Err(NoetherError::InsufficientMargin);
+        }
+
+        // Margin after withdrawal must clear maintenance plus buffer
+        let required_margin_bps =
+            (config.maintenance_margin_bps + config.withdraw_margin_buffer_bps) as i128;
+        if calculate_margin_ratio_bps(&position, current_price) < required_margin_bps {
+            return Err(NoetherError::InsufficientMargin);
+        }
+
+        // Recalculate liquidation price from the remaining collateral; integer
+        // leverage would truncate e.g. 9.9x to 9x and understate the risk
+        position.liquidation_price = calculate_liquidation_price_from_collateral(
+            position.entry_price,
+            position.size,
+            position.collateral,
+            position.direction,
+            config.maintenance_margin_bps,
+        );
+
+        // Save updated position
+        save_position(&env, &position);
+
+        // Return collateral to trader
+        let usdc_token = get_usdc_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &trader, &amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "collateral_removed"),),
+            (position_id, amount, position.collateral),
+        );
+
+        Ok(())
+    }
+
     // ═══════════════════════════════════════════════════════════════════════
     // Liquidation Functions
     // ═══════════════════════════════════════════════════════════════════════
@@ -424,6 +779,10 @@ impl MarketContract {
     /// Liquidate an underwater position.
     /// Callable by anyone (keeper). Keeper receives liquidation reward.
     ///
+    /// Positions still above `full_liquidation_margin_bps` are only reduced
+    /// until they are back at `partial_liquidation_target_bps`; the keeper is
+    /// paid the liquidation fee on the closed share of equity.
+    ///
     /// # Arguments
     /// * `keeper` - Address executing the liquidation (receives reward)
     /// * `position_id` - ID of position to liquidate
@@ -433,10 +792,11 @@ impl MarketContract {
     ///
     /// # Flow
     /// 1. Verify position is liquidatable
-    /// 2. Calculate remaining equity and keeper reward
-    /// 3. Pay keeper their reward
+    /// 2. Calculate remaining equity, liquidation fee and bad debt
+    /// 3. Credit keeper their reward (liquidation fee less the insurance cut)
     /// 4. Transfer remaining collateral to Vault
     /// 5. Update Vault accounting
+    /// 6. Record bad debt, absorbed by the insurance fund first
     pub fn liquidate(
         env: Env,
         keeper: Address,
@@ -446,6 +806,7 @@ impl MarketContract {
         // Note: Liquidations should work even when paused for safety
 
         keeper.require_auth();
+        require_keeper(&env, &keeper)?;
 
         // Get position
         let position = get_position(&env, position_id)
@@ -455,91 +816,80 @@ impl MarketContract {
         let current_price = Self::get_oracle_price(&env, &position.asset)?;
 
         // Check if liquidatable
-        if !should_liquidate(&position, current_price) {
+        if !Self::is_position_liquidatable(&env, &position, current_price) {
             return Err(NoetherError::NotLiquidatable);
         }
 
-        let config = get_config(&env);
-
-        // Calculate PnL
-        let pnl = calculate_pnl(&position, current_price)?;
+        Self::liquidate_position(&env, &keeper, position, current_price)
+    }
 
-        // Calculate remaining collateral after PnL and funding
-        let remaining = position.collateral + pnl - position.accumulated_funding;
+    /// Liquidate several positions in one transaction.
+    /// Each asset's oracle price is read once. IDs that are unknown, healthy,
+    /// whose price is unavailable or whose liquidation fails are skipped rather
+    /// than reverting the batch.
+    ///
+    /// # Arguments
+    /// * `keeper` - Address executing the liquidations (credited rewards)
+    /// * `position_ids` - IDs of positions to liquidate
+    ///
+    /// # Returns
+    /// One result per input ID, in input order
+    pub fn liquidate_batch(
+        env: Env,
+        keeper: Address,
+        position_ids: Vec<u64>,
+    ) -> Result<Vec<BatchResult>, NoetherError> {
+        require_initialized(&env)?;
+        // Note: Liquidations should work even when paused for safety
 
-        // Calculate keeper reward (only from remaining equity, if positive)
-        let keeper_reward = if remaining > 0 {
-            calculate_keeper_reward(remaining, config.liquidation_fee_bps)
-        } else {
-            0
-        };
+        keeper.require_auth();
+        require_keeper(&env, &keeper)?;
+
+        let mut prices: Map<Symbol, i128> = Map::new(&env);
+        let mut results = Vec::new(&env);
+        let mut liquidated: u32 = 0;
+        let mut total_reward: i128 = 0;
+
+        for position_id in position_ids.iter() {
+            let position = match get_position(&env, position_id) {
+                Some(position) => position,
+                None => {
+                    results.push_back(Self::skipped(position_id, NoetherError::PositionNotFound));
+                    continue;
+                }
+            };
 
-        // Ensure keeper_reward doesn't exceed position collateral
-        let actual_keeper_reward = if keeper_reward > position.collateral {
-            position.collateral / 10 // Cap at 10% of collateral as safety
-        } else {
-            keeper_reward
-        };
+            let current_price = match Self::get_cached_oracle_price(&env, &mut prices, &position.asset) {
+                Ok(price) => price,
+                Err(e) => {
+                    results.push_back(Self::skipped(position_id, e));
+                    continue;
+                }
+            };
 
-        // Calculate what goes to Vault (everything except keeper reward)
-        let vault_receives = if position.collateral > actual_keeper_reward {
-            position.collateral - actual_keeper_reward
-        } else {
-            0
-        };
+            if !Self::is_position_liquidatable(&env, &position, current_price) {
+                results.push_back(Self::skipped(position_id, NoetherError::NotLiquidatable));
+                continue;
+            }
 
-        // Get addresses and token client
-        let vault_address = get_vault(&env);
-        let usdc_token = get_usdc_token(&env);
-        let token_client = token::Client::new(&env, &usdc_token);
+            match Self::liquidate_position(&env, &keeper, position, current_price) {
+                Ok(reward) => {
+                    liquidated += 1;
+                    total_reward += reward;
+                    results.push_back(BatchResult { id: position_id, success: true, reward, error_code: 0 });
+                }
+                Err(e) => results.push_back(Self::skipped(position_id, e)),
+            }
+        }
 
-        // Settle with vault - pass the amount Vault is receiving (as negative pnl)
-        // This ensures Vault's total_usdc accounting matches actual token receipt
-        if vault_receives > 0 {
-            Self::settle_with_vault(&env, &vault_address, -vault_receives)?;
-            token_client.transfer(&env.current_contract_address(), &vault_address, &vault_receives);
-        }
-
-        // Pay keeper reward
-        if actual_keeper_reward > 0 {
-            token_client.transfer(&env.current_contract_address(), &keeper, &actual_keeper_reward);
-        }
-
-        // Update market stats
-        match position.direction {
-            Direction::Long => {
-                let total = get_total_long_size(&env);
-                set_total_long_size(&env, total - position.size);
-            }
-            Direction::Short => {
-                let total = get_total_short_size(&env);
-                set_total_short_size(&env, total - position.size);
-            }
-        }
-
-        // Delete position
-        delete_position(&env, position_id, &position.trader);
-
-        // Emit comprehensive event with full trade data for frontend history
         env.events().publish(
-            (Symbol::new(&env, "position_liquidated"),),
-            (
-                position_id,
-                position.trader,
-                position.asset,
-                position.direction,
-                position.size,
-                position.entry_price,
-                current_price,
-                pnl,
-                keeper.clone(),
-                actual_keeper_reward,
-            ),
+            (Symbol::new(&env, "liquidation_batch"),),
+            (keeper, position_ids.len(), liquidated, total_reward),
         );
 
         extend_instance_ttl(&env);
 
-        Ok(actual_keeper_reward)
+        Ok(results)
     }
 
     /// Check if a position can be liquidated.
@@ -549,86 +899,318 @@ impl MarketContract {
 
         let current_price = Self::get_oracle_price(&env, &position.asset)?;
 
-        Ok(should_liquidate(&position, current_price))
+        Ok(Self::is_position_liquidatable(&env, &position, current_price))
     }
 
-    /// Get all liquidatable positions (for keeper).
-    /// Returns list of position IDs that can be liquidated.
-    pub fn get_liquidatable_positions(env: Env, asset: Symbol) -> Result<Vec<u64>, NoetherError> {
+    /// Get liquidatable positions in an asset (for keeper).
+    /// Scans one page of up to `limit` of the asset's positions after `start_after`.
+    ///
+    /// # Returns
+    /// IDs of the liquidatable positions in the page, and the cursor to pass as
+    /// `start_after` for the next page (0 once the asset's positions are exhausted)
+    pub fn get_liquidatable_positions(
+        env: Env,
+        asset: Symbol,
+        start_after: u64,
+        limit: u32,
+    ) -> Result<(Vec<u64>, u64), NoetherError> {
         require_initialized(&env)?;
 
         let current_price = Self::get_oracle_price(&env, &asset)?;
-        let all_positions = get_all_position_ids(&env);
+        let page = index_page(&env, &IndexKey::AssetPositions(asset), start_after, limit)?;
         let mut liquidatable = Vec::new(&env);
 
-        for i in 0..all_positions.len() {
-            let pos_id = all_positions.get(i).unwrap();
+        for pos_id in page.iter() {
             if let Some(position) = get_position(&env, pos_id) {
-                if position.asset == asset && should_liquidate(&position, current_price) {
+                if Self::is_position_liquidatable(&env, &position, current_price) {
                     liquidatable.push_back(pos_id);
                 }
             }
         }
 
-        Ok(liquidatable)
+        let next_cursor = if page.len() == limit.min(MAX_PAGE_SIZE) {
+            page.last().unwrap_or(0)
+        } else {
+            0
+        };
+
+        Ok((liquidatable, next_cursor))
     }
 
     // ═══════════════════════════════════════════════════════════════════════
-    // Funding Rate Functions
+    // Auto-Deleveraging
     // ═══════════════════════════════════════════════════════════════════════
 
-    /// Apply funding to all positions (can be called periodically).
-    /// Funding balances long/short interest:
-    /// - If more longs than shorts: longs pay shorts
-    /// - If more shorts than longs: shorts pay longs
-    pub fn apply_funding(env: Env) -> Result<(), NoetherError> {
+    /// Force-reduce winning positions when the Vault can no longer comfortably
+    /// cover net trader PnL. Callable by any keeper (registered keepers only
+    /// while the allowlist is on); no reward is paid.
+    ///
+    /// # Arguments
+    /// * `keeper` - Address triggering ADL (recorded in the keeper registry)
+    /// * `position_ids` - Candidate positions, at most `MAX_PAGE_SIZE`; they are
+    ///   ranked on-chain, and candidates whose price or reduction fails are skipped
+    ///
+    /// # Returns
+    /// IDs of the positions that were reduced, in the order they were reduced
+    ///
+    /// # Flow
+    /// 1. Check coverage = Vault USDC / net unrealized trader PnL is below
+    ///    `adl_trigger_coverage_bps`
+    /// 2. Rank profitable candidates by (pnl / collateral) × leverage
+    /// 3. Reduce them in order at the oracle price until coverage is back at
+    ///    `adl_target_coverage_bps`
+    pub fn auto_deleverage(
+        env: Env,
+        keeper: Address,
+        position_ids: Vec<u64>,
+    ) -> Result<Vec<u64>, NoetherError> {
         require_initialized(&env)?;
+        // Note: ADL should work even when paused for safety
 
-        let current_time = env.ledger().timestamp();
-        let last_funding = get_last_funding_time(&env);
-
-        // Require at least 1 hour between funding applications
-        if current_time < last_funding + 3600 {
-            return Err(NoetherError::FundingIntervalNotElapsed);
-        }
+        keeper.require_auth();
+        require_keeper(&env, &keeper)?;
 
-        let hours_elapsed = (current_time - last_funding) / 3600;
-        if hours_elapsed == 0 {
-            return Ok(());
+        if position_ids.len() > MAX_PAGE_SIZE {
+            return Err(NoetherError::InvalidParameter);
         }
 
         let config = get_config(&env);
-        let total_long = get_total_long_size(&env);
-        let total_short = get_total_short_size(&env);
+        let vault_address = get_vault(&env);
 
-        // Calculate funding rate
-        let funding_rate = calculate_funding_rate(
-            total_long,
-            total_short,
-            config.base_funding_rate_bps,
+        // Check coverage
+        let trader_pnl = calculate_total_unrealized_pnl(&env, Self::get_oracle_price)?;
+        let vault_usdc = Self::get_vault_total_usdc(&env, &vault_address);
+        if calculate_coverage_bps(vault_usdc, trader_pnl) >= config.adl_trigger_coverage_bps as i128 {
+            return Err(NoetherError::AdlNotRequired);
+        }
+
+        let mut profit_needed = calculate_adl_profit_to_realize(
+            vault_usdc,
+            trader_pnl,
+            config.adl_target_coverage_bps,
         );
 
-        // Store for reference
-        set_current_funding_rate(&env, funding_rate);
-        set_last_funding_time(&env, current_time);
+        // Score candidates; only profitable positions are eligible
+        let mut prices: Map<Symbol, i128> = Map::new(&env);
+        let mut candidates: Vec<(i128, u64, i128)> = Vec::new(&env);
+        for position_id in position_ids.iter() {
+            let position = match get_position(&env, position_id) {
+                Some(position) => position,
+                None => continue,
+            };
+            let current_price = match Self::get_cached_oracle_price(&env, &mut prices, &position.asset) {
+                Ok(price) => price,
+                Err(_) => continue,
+            };
+            let pnl = match calculate_pnl(&position, current_price) {
+                Ok(pnl) => pnl,
+                Err(_) => continue,
+            };
+            let score = calculate_adl_score(&position, pnl);
+            if score > 0 {
+                candidates.push_back((score, position_id, current_price));
+            }
+        }
+
+        // Reduce the highest-ranked position until enough profit is realized
+        let mut reduced = Vec::new(&env);
+        while profit_needed > 0 && !candidates.is_empty() {
+            let mut best = 0;
+            for i in 1..candidates.len() {
+                if candidates.get(i).unwrap().0 > candidates.get(best).unwrap().0 {
+                    best = i;
+                }
+            }
+
+            let (_, position_id, current_price) = candidates.get(best).unwrap();
+            candidates.remove(best);
+
+            if get_position(&env, position_id).is_none() {
+                continue; // Duplicate ID already closed
+            }
+
+            // A candidate whose reduction fails is skipped
+            if let Ok(realized) = Self::execute_adl(&env, &vault_address, position_id, current_price, profit_needed) {
+                profit_needed -= realized;
+                reduced.push_back(position_id);
+            }
+        }
+
+        if !reduced.is_empty() {
+            Self::record_keeper_jobs(&env, &keeper, KeeperJob::Deleverage, reduced.len() as u64, 0);
+        }
+
+        extend_instance_ttl(&env);
+
+        Ok(reduced)
+    }
+
+    /// Get Vault coverage of net unrealized trader PnL in basis points.
+    /// ADL is allowed while this is below `adl_trigger_coverage_bps`.
+    pub fn get_vault_coverage(env: Env) -> Result<i128, NoetherError> {
+        require_initialized(&env)?;
+
+        let trader_pnl = calculate_total_unrealized_pnl(&env, Self::get_oracle_price)?;
+        let vault_usdc = Self::get_vault_total_usdc(&env, &get_vault(&env));
+
+        Ok(calculate_coverage_bps(vault_usdc, trader_pnl))
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Funding Rate Functions
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Checkpoint funding for an asset (can be called periodically).
+    /// Funding accrues continuously through the asset's cumulative funding index,
+    /// which is also updated on every open and close. This tick keeps the index
+    /// and the stored rate fresh for quiet markets.
+    ///
+    /// Funding balances long/short interest of that asset only:
+    /// - If more longs than shorts: longs pay shorts
+    /// - If more shorts than longs: shorts pay longs
+    ///
+    /// The asset's borrow index is checkpointed too, and its rate re-read
+    /// from current vault utilization.
+    pub fn apply_funding(env: Env, keeper: Address, asset: Symbol) -> Result<(), NoetherError> {
+        require_initialized(&env)?;
+
+        keeper.require_auth();
+        require_keeper(&env, &keeper)?;
+
+        let funding_index = Self::update_funding_index(&env, &asset);
+        Self::refresh_funding_rate(&env, &asset);
+        Self::update_borrow_index(&env, &asset);
+        Self::refresh_borrow_rate(&env, &asset);
 
         env.events().publish(
             (Symbol::new(&env, "funding_applied"),),
-            (funding_rate, hours_elapsed),
+            (asset.clone(), get_current_funding_rate(&env, &asset), funding_index),
         );
 
+        Self::record_keeper_jobs(&env, &keeper, KeeperJob::FundingTick, 1, 0);
+
         Ok(())
     }
 
-    /// Get current funding rate.
-    pub fn get_funding_rate(env: Env) -> i128 {
-        let config = get_config(&env);
-        let total_long = get_total_long_size(&env);
-        let total_short = get_total_short_size(&env);
+    /// Get current funding rate for an asset.
+    pub fn get_funding_rate(env: Env, asset: Symbol) -> i128 {
+        let config = Self::get_asset_config_or_default(&env, &asset);
+        let total_long = get_total_long_size(&env, &asset);
+        let total_short = get_total_short_size(&env, &asset);
 
         calculate_funding_rate(total_long, total_short, config.base_funding_rate_bps)
     }
 
+    /// Get current hourly borrow rate for an asset (basis points, 7 decimals).
+    /// Both sides pay this on position size; it scales with vault utilization.
+    pub fn get_borrow_rate(env: Env, asset: Symbol) -> i128 {
+        let config = Self::get_asset_config_or_default(&env, &asset);
+        let utilization = Self::get_vault_utilization(&env, &get_vault(&env));
+
+        calculate_borrow_rate(utilization, config.max_borrow_rate_bps)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Pool PnL Sync
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Push aggregate unrealized PnL of all open positions to the Vault.
+    /// Callable by any keeper (registered keepers only while the allowlist is on)
+    /// so the NOE price tracks open trader PnL.
+    ///
+    /// # Arguments
+    /// * `keeper` - Address pushing the sync (recorded in the keeper registry)
+    ///
+    /// # Returns
+    /// Total unrealized PnL (positive = traders winning)
+    pub fn sync_pool_pnl(env: Env, keeper: Address) -> Result<i128, NoetherError> {
+        require_initialized(&env)?;
+
+        keeper.require_auth();
+        require_keeper(&env, &keeper)?;
+
+        let total_pnl = calculate_total_unrealized_pnl(&env, Self::get_oracle_price)?;
+
+        let vault_address = get_vault(&env);
+        let args: Vec<soroban_sdk::Val> = (total_pnl,).into_val(&env);
+        let _: () = env.invoke_contract(
+            &vault_address,
+            &Symbol::new(&env, "update_unrealized_pnl"),
+            args,
+        );
+
+        Self::record_keeper_jobs(&env, &keeper, KeeperJob::PnlSync, 1, 0);
+
+        env.events().publish(
+            (Symbol::new(&env, "pool_pnl_synced"),),
+            (total_pnl,),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(total_pnl)
+    }
+
+    /// Get aggregate unrealized PnL of all open positions at current prices.
+    pub fn get_total_unrealized_pnl(env: Env) -> Result<i128, NoetherError> {
+        calculate_total_unrealized_pnl(&env, Self::get_oracle_price)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Keeper Registry
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Withdraw a keeper's accrued rewards.
+    /// Works for removed keepers too, so earned rewards are never stranded.
+    ///
+    /// # Returns
+    /// Amount of USDC transferred to the keeper
+    pub fn claim_keeper_rewards(env: Env, keeper: Address) -> Result<i128, NoetherError> {
+        require_initialized(&env)?;
+
+        keeper.require_auth();
+
+        let mut info = get_keeper_info(&env, &keeper);
+        let amount = info.accrued_rewards;
+        if amount <= 0 {
+            return Err(NoetherError::NoKeeperRewards);
+        }
+
+        info.accrued_rewards = 0;
+        info.total_claimed += amount;
+        save_keeper_info(&env, &keeper, &info);
+
+        let usdc_token = get_usdc_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &keeper, &amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "keeper_rewards_claimed"),),
+            (keeper, amount),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(amount)
+    }
+
+    /// Get a keeper's registry entry (defaults if the keeper has never been seen).
+    pub fn get_keeper_info(env: Env, keeper: Address) -> KeeperInfo {
+        get_keeper_info(&env, &keeper)
+    }
+
+    /// Get a page of keepers registered on the allowlist, in registration order.
+    /// Pass `start_after` = None for the first page and the last returned keeper
+    /// for the next; `limit` is capped at `MAX_PAGE_SIZE`.
+    pub fn get_keepers(env: Env, start_after: Option<Address>, limit: u32) -> Vec<Address> {
+        get_keepers(&env, start_after, limit)
+    }
+
+    /// Check if only registered keepers may run keeper jobs.
+    pub fn is_keeper_allowlist_enabled(env: Env) -> bool {
+        is_keeper_allowlist_enabled(&env)
+    }
+
     // ═══════════════════════════════════════════════════════════════════════
     // View Functions
     // ═══════════════════════════════════════════════════════════════════════
@@ -638,9 +1220,12 @@ impl MarketContract {
         get_position(&env, position_id)
     }
 
-    /// Get all positions for a trader.
-    pub fn get_positions(env: Env, trader: Address) -> Vec<Position> {
-        get_trader_positions(&env, &trader)
+    /// Get a page of a trader's open positions, ordered by ID.
+    /// Pass `start_after` = 0 for the first page and the last returned ID for the next;
+    /// `limit` is capped at `MAX_PAGE_SIZE`. Fails with `InvalidCursor` if the IDs
+    /// around the cursor were all removed since; restart from 0 then.
+    pub fn get_positions(env: Env, trader: Address, start_after: u64, limit: u32) -> Result<Vec<Position>, NoetherError> {
+        get_trader_positions(&env, &trader, start_after, limit)
     }
 
     /// Get position PnL at current price.
@@ -652,22 +1237,96 @@ impl MarketContract {
         calculate_pnl(&position, current_price)
     }
 
-    /// Get market statistics.
-    pub fn get_market_stats(env: Env) -> MarketStats {
-        let funding_rate = Self::get_funding_rate(env.clone());
+    /// Get the health of a position at the current oracle price.
+    /// Funding and borrow fees are projected to now without being settled.
+    pub fn get_position_health(env: Env, position_id: u64) -> Result<PositionHealth, NoetherError> {
+        let position = get_position(&env, position_id)
+            .ok_or(NoetherError::PositionNotFound)?;
+
+        let current_price = Self::get_oracle_price(&env, &position.asset)?;
+        Self::calculate_position_health(&env, position, current_price)
+    }
+
+    /// Get the aggregate health of a page of a trader's open positions at current
+    /// oracle prices. Pages follow the same cursor rules as `get_positions`; callers
+    /// with more than one page sum the totals across pages.
+    /// Each asset's oracle price is read once.
+    pub fn get_account_health(
+        env: Env,
+        trader: Address,
+        start_after: u64,
+        limit: u32,
+    ) -> Result<AccountHealth, NoetherError> {
+        let mut prices: Map<Symbol, i128> = Map::new(&env);
+        let mut health = AccountHealth {
+            total_collateral: 0,
+            total_size: 0,
+            total_unrealized_pnl: 0,
+            total_accrued_funding: 0,
+            total_accrued_borrow_fee: 0,
+            total_equity: 0,
+            margin_ratio_bps: BASIS_POINTS as i128,
+            min_liquidation_distance_bps: BASIS_POINTS as i128,
+            liquidatable_count: 0,
+            estimated_liquidation_payout: 0,
+            positions: Vec::new(&env),
+        };
+
+        for position in get_trader_positions(&env, &trader, start_after, limit)?.iter() {
+            let current_price = Self::get_cached_oracle_price(&env, &mut prices, &position.asset)?;
+            health.total_collateral += position.collateral;
+            health.total_size += position.size;
+
+            let position_health = Self::calculate_position_health(&env, position, current_price)?;
+            health.total_unrealized_pnl += position_health.unrealized_pnl;
+            health.total_accrued_funding += position_health.accrued_funding;
+            health.total_accrued_borrow_fee += position_health.accrued_borrow_fee;
+            health.total_equity += position_health.equity;
+            health.min_liquidation_distance_bps = health
+                .min_liquidation_distance_bps
+                .min(position_health.distance_to_liquidation_bps);
+            if position_health.is_liquidatable {
+                health.liquidatable_count += 1;
+            }
+            health.estimated_liquidation_payout += position_health.estimated_liquidation_payout;
+            health.positions.push_back(position_health);
+        }
+
+        if health.total_size > 0 {
+            health.margin_ratio_bps = health.total_equity * (BASIS_POINTS as i128) / health.total_size;
+        }
+
+        Ok(health)
+    }
+
+    /// Get market statistics for an asset.
+    pub fn get_market_stats(env: Env, asset: Symbol) -> MarketStats {
+        let funding_rate = Self::get_funding_rate(env.clone(), asset.clone());
 
         MarketStats {
-            total_long_size: get_total_long_size(&env),
-            total_short_size: get_total_short_size(&env),
-            open_position_count: get_position_count(&env),
+            total_long_size: get_total_long_size(&env, &asset),
+            total_short_size: get_total_short_size(&env, &asset),
+            open_position_count: index_len(&env, &IndexKey::AssetPositions(asset.clone())),
             funding_rate,
-            last_funding_time: get_last_funding_time(&env),
+            funding_index: get_funding_index(&env, &asset),
+            last_funding_time: get_last_funding_time(&env, &asset),
         }
     }
 
-    /// Get all position IDs (for keeper iteration).
-    pub fn get_all_position_ids(env: Env) -> Vec<u64> {
-        get_all_position_ids(&env)
+    /// Get a page of open position IDs (for keeper iteration).
+    /// Pages follow the same cursor rules as `get_positions`.
+    pub fn get_all_position_ids(env: Env, start_after: u64, limit: u32) -> Result<Vec<u64>, NoetherError> {
+        index_page(&env, &IndexKey::AllPositions, start_after, limit)
+    }
+
+    /// Get a page of open position IDs in an asset.
+    pub fn get_asset_position_ids(env: Env, asset: Symbol, start_after: u64, limit: u32) -> Result<Vec<u64>, NoetherError> {
+        index_page(&env, &IndexKey::AssetPositions(asset), start_after, limit)
+    }
+
+    /// Get the number of open positions across all assets.
+    pub fn get_position_count(env: Env) -> u64 {
+        get_position_count(&env)
     }
 
     /// Get current price from oracle.
@@ -675,11 +1334,35 @@ impl MarketContract {
         Self::get_oracle_price(&env, &asset)
     }
 
-    /// Get market configuration.
+    /// Get default market configuration.
     pub fn get_config(env: Env) -> MarketConfig {
         get_config(&env)
     }
 
+    /// Get all listed assets with their market parameters.
+    pub fn get_assets(env: Env) -> Vec<AssetInfo> {
+        let assets = get_listed_assets(&env);
+        let mut infos = Vec::new(&env);
+
+        for i in 0..assets.len() {
+            let asset = assets.get(i).unwrap();
+            let config = Self::get_asset_config_or_default(&env, &asset);
+            infos.push_back(AssetInfo { asset, config });
+        }
+
+        infos
+    }
+
+    /// Get market parameters for an asset.
+    pub fn get_asset_config(env: Env, asset: Symbol) -> MarketConfig {
+        Self::get_asset_config_or_default(&env, &asset)
+    }
+
+    /// Get keeper execution fee parameters for an asset.
+    pub fn get_keeper_fee_config(env: Env, asset: Symbol) -> KeeperFeeConfig {
+        get_keeper_fee_config(&env, &asset)
+    }
+
     /// Get vault address.
     pub fn get_vault(env: Env) -> Result<Address, NoetherError> {
         require_initialized(&env)?;
@@ -709,9 +1392,7 @@ impl MarketContract {
         require_admin(&env)?;
 
         // Validate config
-        if config.max_leverage < 1 || config.max_leverage > 100 {
-            return Err(NoetherError::InvalidParameter);
-        }
+        Self::validate_config(&config)?;
 
         set_config(&env, &config);
 
@@ -723,107 +1404,240 @@ impl MarketContract {
         Ok(())
     }
 
-    /// Update oracle adapter address.
-    pub fn set_oracle_adapter(env: Env, oracle: Address) -> Result<(), NoetherError> {
+    /// List an asset for trading with its own market parameters.
+    pub fn list_asset(env: Env, asset: Symbol, config: MarketConfig) -> Result<(), NoetherError> {
         require_admin(&env)?;
-        let old = get_oracle_adapter(&env);
-        set_oracle_adapter(&env, &oracle);
+        Self::validate_config(&config)?;
+
+        let mut assets = get_listed_assets(&env);
+        if assets.contains(&asset) {
+            return Err(NoetherError::InvalidParameter);
+        }
+
+        assets.push_back(asset.clone());
+        set_listed_assets(&env, &assets);
+        set_asset_config(&env, &asset, &config);
 
         env.events().publish(
-            (Symbol::new(&env, "oracle_updated"),),
-            (old, oracle),
+            (Symbol::new(&env, "asset_listed"),),
+            (asset,),
         );
 
         Ok(())
     }
 
-    /// Update vault address.
-    pub fn set_vault(env: Env, vault: Address) -> Result<(), NoetherError> {
+    /// Update market parameters of a listed asset.
+    pub fn update_asset_config(env: Env, asset: Symbol, config: MarketConfig) -> Result<(), NoetherError> {
         require_admin(&env)?;
-        let old = get_vault(&env);
-        set_vault(&env, &vault);
+        Self::validate_config(&config)?;
+
+        if !is_asset_listed(&env, &asset) {
+            return Err(NoetherError::AssetNotListed);
+        }
+
+        set_asset_config(&env, &asset, &config);
 
         env.events().publish(
-            (Symbol::new(&env, "vault_updated"),),
-            (old, vault),
+            (Symbol::new(&env, "asset_config_updated"),),
+            (asset,),
         );
 
         Ok(())
     }
 
-    /// Pause the market (emergency).
-    pub fn pause(env: Env) -> Result<(), NoetherError> {
+    /// Set keeper execution fee parameters for an asset.
+    /// Applies to orders placed afterwards; pending orders keep the fee they escrowed.
+    pub fn set_keeper_fee_config(env: Env, asset: Symbol, config: KeeperFeeConfig) -> Result<(), NoetherError> {
         require_admin(&env)?;
-        set_paused(&env, true);
+
+        if config.base_fee < 0 || config.expiry_fee < 0 || config.variable_fee_bps > BASIS_POINTS {
+            return Err(NoetherError::InvalidParameter);
+        }
+
+        set_keeper_fee_config(&env, &asset, &config);
 
         env.events().publish(
-            (Symbol::new(&env, "paused"),),
-            (),
+            (Symbol::new(&env, "keeper_fee_config_updated"),),
+            (asset, config.base_fee, config.variable_fee_bps, config.expiry_fee),
         );
 
         Ok(())
     }
 
-    /// Unpause the market.
-    pub fn unpause(env: Env) -> Result<(), NoetherError> {
+    /// Switch between open keeper mode (anyone may run keeper jobs) and
+    /// allowlist mode (only registered keepers may).
+    pub fn set_keeper_allowlist(env: Env, enabled: bool) -> Result<(), NoetherError> {
         require_admin(&env)?;
-        set_paused(&env, false);
+
+        set_keeper_allowlist_enabled(&env, enabled);
 
         env.events().publish(
-            (Symbol::new(&env, "unpaused"),),
-            (),
+            (Symbol::new(&env, "keeper_allowlist_updated"),),
+            (enabled,),
         );
 
         Ok(())
     }
 
-    /// Transfer admin role.
-    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), NoetherError> {
+    /// Add a keeper to the allowlist.
+    pub fn register_keeper(env: Env, keeper: Address) -> Result<(), NoetherError> {
         require_admin(&env)?;
-        new_admin.require_auth();
 
-        let old = get_admin(&env);
-        set_admin(&env, &new_admin);
+        let mut info = get_keeper_info(&env, &keeper);
+        info.registered = true;
+        save_keeper_info(&env, &keeper, &info);
+        add_registered_keeper(&env, &keeper);
 
         env.events().publish(
-            (Symbol::new(&env, "admin_updated"),),
-            (old, new_admin),
+            (Symbol::new(&env, "keeper_registered"),),
+            (keeper,),
         );
 
         Ok(())
     }
 
-    /// Get admin address.
-    pub fn get_admin(env: Env) -> Result<Address, NoetherError> {
-        require_initialized(&env)?;
-        Ok(get_admin(&env))
-    }
+    /// Remove a keeper from the allowlist. Counters and unclaimed rewards are kept.
+    pub fn remove_keeper(env: Env, keeper: Address) -> Result<(), NoetherError> {
+        require_admin(&env)?;
 
-    /// Check if paused.
-    pub fn is_paused(env: Env) -> bool {
-        get_paused(&env)
+        let mut info = get_keeper_info(&env, &keeper);
+        info.registered = false;
+        save_keeper_info(&env, &keeper, &info);
+        remove_registered_keeper(&env, &keeper);
+
+        env.events().publish(
+            (Symbol::new(&env, "keeper_removed"),),
+            (keeper,),
+        );
+
+        Ok(())
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Order Functions (Limit Orders, Stop-Loss, Take-Profit)
-    // ═══════════════════════════════════════════════════════════════════════
+    /// Delist an asset.
+    /// New positions and orders are rejected; existing positions keep the
+    /// asset's parameters so they can still be closed or liquidated.
+    pub fn delist_asset(env: Env, asset: Symbol) -> Result<(), NoetherError> {
+        require_admin(&env)?;
 
-    /// Place a limit entry order.
-    /// Collateral is locked immediately when the order is placed.
-    /// Order executes when price reaches trigger_price (based on trigger_condition).
-    ///
-    /// # Arguments
-    /// * `trader` - Address of the trader
-    /// * `asset` - Asset symbol (e.g., "BTC", "ETH", "XLM")
-    /// * `direction` - Long or Short
-    /// * `collateral` - USDC collateral to lock (7 decimals)
+        let assets = get_listed_assets(&env);
+        let index = assets.first_index_of(&asset).ok_or(NoetherError::AssetNotListed)?;
+
+        let mut remaining = assets.clone();
+        remaining.remove(index);
+        set_listed_assets(&env, &remaining);
+
+        env.events().publish(
+            (Symbol::new(&env, "asset_delisted"),),
+            (asset,),
+        );
+
+        Ok(())
+    }
+
+    /// Update oracle adapter address.
+    pub fn set_oracle_adapter(env: Env, oracle: Address) -> Result<(), NoetherError> {
+        require_admin(&env)?;
+        let old = get_oracle_adapter(&env);
+        set_oracle_adapter(&env, &oracle);
+
+        env.events().publish(
+            (Symbol::new(&env, "oracle_updated"),),
+            (old, oracle),
+        );
+
+        Ok(())
+    }
+
+    /// Update vault address.
+    pub fn set_vault(env: Env, vault: Address) -> Result<(), NoetherError> {
+        require_admin(&env)?;
+        let old = get_vault(&env);
+        set_vault(&env, &vault);
+
+        env.events().publish(
+            (Symbol::new(&env, "vault_updated"),),
+            (old, vault),
+        );
+
+        Ok(())
+    }
+
+    /// Pause the market (emergency).
+    pub fn pause(env: Env) -> Result<(), NoetherError> {
+        require_admin(&env)?;
+        set_paused(&env, true);
+
+        env.events().publish(
+            (Symbol::new(&env, "paused"),),
+            (),
+        );
+
+        Ok(())
+    }
+
+    /// Unpause the market.
+    pub fn unpause(env: Env) -> Result<(), NoetherError> {
+        require_admin(&env)?;
+        set_paused(&env, false);
+
+        env.events().publish(
+            (Symbol::new(&env, "unpaused"),),
+            (),
+        );
+
+        Ok(())
+    }
+
+    /// Transfer admin role.
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), NoetherError> {
+        require_admin(&env)?;
+        new_admin.require_auth();
+
+        let old = get_admin(&env);
+        set_admin(&env, &new_admin);
+
+        env.events().publish(
+            (Symbol::new(&env, "admin_updated"),),
+            (old, new_admin),
+        );
+
+        Ok(())
+    }
+
+    /// Get admin address.
+    pub fn get_admin(env: Env) -> Result<Address, NoetherError> {
+        require_initialized(&env)?;
+        Ok(get_admin(&env))
+    }
+
+    /// Check if paused.
+    pub fn is_paused(env: Env) -> bool {
+        get_paused(&env)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Order Functions (Limit Orders, Stop-Loss, Take-Profit)
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Place a limit entry order.
+    /// Collateral is locked immediately when the order is placed.
+    /// Order executes when price reaches trigger_price (based on trigger_condition).
+    ///
+    /// # Arguments
+    /// * `trader` - Address of the trader
+    /// * `asset` - Asset symbol (e.g., "BTC", "ETH", "XLM")
+    /// * `direction` - Long or Short
+    /// * `collateral` - USDC collateral to lock (7 decimals)
     /// * `leverage` - Leverage multiplier (1-10)
     /// * `trigger_price` - Price at which to execute (7 decimals)
     /// * `trigger_above` - true = execute when price >= trigger, false = when price <= trigger
     /// * `slippage_tolerance_bps` - Max allowed slippage in basis points (e.g., 100 = 1%)
+    /// * `expires_at` - Unix time after which the order can be expired and refunded (0 = never)
     ///
     /// # Returns
     /// The created Order
+    // One argument per order field; `expires_at` takes the list past clippy's limit
+    #[allow(clippy::too_many_arguments)]
     pub fn place_limit_order(
         env: Env,
         trader: Address,
@@ -834,13 +1648,14 @@ impl MarketContract {
         trigger_price: i128,
         trigger_above: bool,
         slippage_tolerance_bps: u32,
+        expires_at: u64,
     ) -> Result<Order, NoetherError> {
         require_initialized(&env)?;
         require_not_paused(&env)?;
 
         trader.require_auth();
 
-        let config = get_config(&env);
+        let config = Self::get_listed_asset_config(&env, &asset)?;
 
         // Validate parameters
         if collateral < config.min_collateral {
@@ -855,6 +1670,7 @@ impl MarketContract {
         if slippage_tolerance_bps == 0 || slippage_tolerance_bps > 10000 {
             return Err(NoetherError::InvalidSlippageTolerance);
         }
+        Self::validate_order_expiry(&env, expires_at)?;
 
         // Calculate position size to check against limits
         let size = calculate_position_size(collateral, leverage);
@@ -862,10 +1678,11 @@ impl MarketContract {
             return Err(NoetherError::PositionTooLarge);
         }
 
-        // Transfer collateral from trader to market contract (lock it)
+        // Lock collateral plus the prepaid keeper execution fee in the market contract
+        let execution_fee = Self::calculate_execution_fee(&env, &asset, size);
         let usdc_token = get_usdc_token(&env);
         let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(&trader, &env.current_contract_address(), &collateral);
+        token_client.transfer(&trader, &env.current_contract_address(), &(collateral + execution_fee));
 
         // Generate order ID
         let order_id = next_order_id(&env);
@@ -888,14 +1705,20 @@ impl MarketContract {
             trigger_price,
             trigger_condition,
             slippage_tolerance_bps,
+            close_bps: 0,
+            close_size: 0,
+            trailing_distance_bps: 0,
+            trailing_mark: 0,
             position_id: 0,
             has_position: false,
             created_at: env.ledger().timestamp(),
+            expires_at,
+            execution_fee,
             status: OrderStatus::Pending,
         };
 
         // Store order
-        save_order(&env, &order);
+        save_new_order(&env, &order);
 
         extend_instance_ttl(&env);
 
@@ -908,29 +1731,38 @@ impl MarketContract {
     }
 
     /// Set a stop-loss order on an existing position.
-    /// Automatically closes the position when price reaches trigger to limit losses.
+    /// Automatically closes the position (or `close_bps` of it) when price
+    /// reaches trigger to limit losses. Several can be laddered on one position.
     ///
     /// # Arguments
     /// * `trader` - Address of the trader (must own position)
     /// * `position_id` - ID of the position to protect
     /// * `trigger_price` - Price at which to close (7 decimals)
+    /// * `close_bps` - Fraction of the position to close (1-10000, 10000 = full close)
     /// * `slippage_tolerance_bps` - Max allowed slippage in basis points
+    /// * `expires_at` - Unix time after which the order can be expired (0 = never)
     pub fn set_stop_loss(
         env: Env,
         trader: Address,
         position_id: u64,
         trigger_price: i128,
+        close_bps: u32,
         slippage_tolerance_bps: u32,
+        expires_at: u64,
     ) -> Result<Order, NoetherError> {
         require_initialized(&env)?;
         require_not_paused(&env)?;
 
         trader.require_auth();
 
-        // Validate slippage
+        // Validate size, slippage and expiry
+        if close_bps == 0 || close_bps > BASIS_POINTS {
+            return Err(NoetherError::InvalidParameter);
+        }
         if slippage_tolerance_bps == 0 || slippage_tolerance_bps > 10000 {
             return Err(NoetherError::InvalidSlippageTolerance);
         }
+        Self::validate_order_expiry(&env, expires_at)?;
 
         // Get position
         let position = get_position(&env, position_id)
@@ -941,94 +1773,68 @@ impl MarketContract {
             return Err(NoetherError::NotPositionOwner);
         }
 
-        // Check if SL already exists
-        if get_position_stop_loss(&env, position_id).is_some() {
-            return Err(NoetherError::OrderAlreadyExists);
-        }
+        // Bound the number of close orders per position
+        Self::check_position_order_capacity(&env, position_id)?;
 
         // Validate trigger price based on direction
         // For Long: stop-loss must be BELOW entry price (triggers when price falls)
         // For Short: stop-loss must be ABOVE entry price (triggers when price rises)
-        match position.direction {
-            Direction::Long => {
-                if trigger_price >= position.entry_price {
-                    return Err(NoetherError::InvalidTriggerPrice);
-                }
-            }
-            Direction::Short => {
-                if trigger_price <= position.entry_price {
-                    return Err(NoetherError::InvalidTriggerPrice);
-                }
-            }
+        if !is_stop_loss_trigger_valid(position.direction, position.entry_price, trigger_price) {
+            return Err(NoetherError::InvalidTriggerPrice);
         }
 
-        // Determine trigger condition
-        // Long position: close when price <= trigger (price falling)
-        // Short position: close when price >= trigger (price rising)
-        let trigger_condition = match position.direction {
-            Direction::Long => TriggerCondition::Below,
-            Direction::Short => TriggerCondition::Above,
-        };
-
-        // Generate order ID
-        let order_id = next_order_id(&env);
-
-        // Create order
-        let order = Order {
-            id: order_id,
-            trader: trader.clone(),
-            asset: position.asset.clone(),
-            order_type: OrderType::StopLoss,
-            direction: position.direction.clone(),
-            collateral: 0, // No collateral locked for SL
-            leverage: position.leverage,
-            trigger_price,
-            trigger_condition,
+        let order = Self::attach_close_order(
+            &env,
+            &position,
+            OrderType::StopLoss,
+            CloseTrigger::Price(trigger_price),
+            close_bps,
             slippage_tolerance_bps,
-            position_id,
-            has_position: true,
-            created_at: env.ledger().timestamp(),
-            status: OrderStatus::Pending,
-        };
-
-        // Store order and link to position
-        save_order(&env, &order);
-        set_position_stop_loss(&env, position_id, order_id);
-
-        extend_instance_ttl(&env);
+            expires_at,
+        );
 
         env.events().publish(
             (Symbol::new(&env, "stop_loss_set"),),
-            (order_id, position_id, trigger_price),
+            (order.id, position_id, trigger_price),
         );
 
         Ok(order)
     }
 
     /// Set a take-profit order on an existing position.
-    /// Automatically closes the position when price reaches trigger to lock in profits.
+    /// Automatically closes the position (or `close_bps` of it) when price
+    /// reaches trigger to lock in profits; this doubles as a reduce-only limit
+    /// close. Several can be laddered on one position.
     ///
     /// # Arguments
     /// * `trader` - Address of the trader (must own position)
     /// * `position_id` - ID of the position
     /// * `trigger_price` - Price at which to close (7 decimals)
+    /// * `close_bps` - Fraction of the position to close (1-10000, 10000 = full close)
     /// * `slippage_tolerance_bps` - Max allowed slippage in basis points
+    /// * `expires_at` - Unix time after which the order can be expired (0 = never)
     pub fn set_take_profit(
         env: Env,
         trader: Address,
         position_id: u64,
         trigger_price: i128,
+        close_bps: u32,
         slippage_tolerance_bps: u32,
+        expires_at: u64,
     ) -> Result<Order, NoetherError> {
         require_initialized(&env)?;
         require_not_paused(&env)?;
 
         trader.require_auth();
 
-        // Validate slippage
+        // Validate size, slippage and expiry
+        if close_bps == 0 || close_bps > BASIS_POINTS {
+            return Err(NoetherError::InvalidParameter);
+        }
         if slippage_tolerance_bps == 0 || slippage_tolerance_bps > 10000 {
             return Err(NoetherError::InvalidSlippageTolerance);
         }
+        Self::validate_order_expiry(&env, expires_at)?;
 
         // Get position
         let position = get_position(&env, position_id)
@@ -1039,392 +1845,1659 @@ impl MarketContract {
             return Err(NoetherError::NotPositionOwner);
         }
 
-        // Check if TP already exists
-        if get_position_take_profit(&env, position_id).is_some() {
-            return Err(NoetherError::OrderAlreadyExists);
-        }
+        // Bound the number of close orders per position
+        Self::check_position_order_capacity(&env, position_id)?;
 
         // Validate trigger price based on direction
         // For Long: take-profit must be ABOVE entry price (profit when price rises)
         // For Short: take-profit must be BELOW entry price (profit when price falls)
-        match position.direction {
-            Direction::Long => {
-                if trigger_price <= position.entry_price {
-                    return Err(NoetherError::InvalidTriggerPrice);
-                }
-            }
-            Direction::Short => {
-                if trigger_price >= position.entry_price {
-                    return Err(NoetherError::InvalidTriggerPrice);
-                }
-            }
+        if !is_take_profit_trigger_valid(position.direction, position.entry_price, trigger_price) {
+            return Err(NoetherError::InvalidTriggerPrice);
         }
 
-        // Determine trigger condition
-        // Long position: close when price >= trigger (price rising to target)
-        // Short position: close when price <= trigger (price falling to target)
-        let trigger_condition = match position.direction {
-            Direction::Long => TriggerCondition::Above,
-            Direction::Short => TriggerCondition::Below,
-        };
-
-        // Generate order ID
-        let order_id = next_order_id(&env);
-
-        // Create order
-        let order = Order {
-            id: order_id,
-            trader: trader.clone(),
-            asset: position.asset.clone(),
-            order_type: OrderType::TakeProfit,
-            direction: position.direction.clone(),
-            collateral: 0, // No collateral locked for TP
-            leverage: position.leverage,
-            trigger_price,
-            trigger_condition,
+        let order = Self::attach_close_order(
+            &env,
+            &position,
+            OrderType::TakeProfit,
+            CloseTrigger::Price(trigger_price),
+            close_bps,
             slippage_tolerance_bps,
-            position_id,
-            has_position: true,
-            created_at: env.ledger().timestamp(),
-            status: OrderStatus::Pending,
-        };
-
-        // Store order and link to position
-        save_order(&env, &order);
-        set_position_take_profit(&env, position_id, order_id);
-
-        extend_instance_ttl(&env);
+            expires_at,
+        );
 
         env.events().publish(
             (Symbol::new(&env, "take_profit_set"),),
-            (order_id, position_id, trigger_price),
+            (order.id, position_id, trigger_price),
         );
 
         Ok(order)
     }
 
-    /// Cancel a pending order.
-    /// For limit orders, refunds the locked collateral.
+    /// Set a trailing stop on an existing position.
+    /// The stop follows the best price seen (high-water for longs, low-water for
+    /// shorts) at `trailing_distance_bps` behind it, and closes the position when
+    /// price retraces that far. Keepers ratchet the mark via `update_trailing_stop`.
     ///
     /// # Arguments
-    /// * `trader` - Address of the trader (must own order)
-    /// * `order_id` - ID of the order to cancel
-    pub fn cancel_order(
+    /// * `trader` - Address of the trader (must own position)
+    /// * `position_id` - ID of the position to protect
+    /// * `trailing_distance_bps` - Retracement from the mark that triggers the close (1-9999)
+    /// * `close_bps` - Fraction of the position to close (1-10000, 10000 = full close)
+    /// * `slippage_tolerance_bps` - Max allowed slippage in basis points
+    /// * `expires_at` - Unix time after which the order can be expired (0 = never)
+    pub fn set_trailing_stop(
         env: Env,
         trader: Address,
-        order_id: u64,
-    ) -> Result<(), NoetherError> {
+        position_id: u64,
+        trailing_distance_bps: u32,
+        close_bps: u32,
+        slippage_tolerance_bps: u32,
+        expires_at: u64,
+    ) -> Result<Order, NoetherError> {
         require_initialized(&env)?;
+        require_not_paused(&env)?;
 
         trader.require_auth();
 
-        // Get order
-        let order = get_order(&env, order_id)
-            .ok_or(NoetherError::OrderNotFound)?;
-
-        // Verify ownership
-        if order.trader != trader {
-            return Err(NoetherError::NotOrderOwner);
+        // Validate distance, size, slippage and expiry
+        if trailing_distance_bps == 0 || trailing_distance_bps >= BASIS_POINTS {
+            return Err(NoetherError::InvalidTriggerPrice);
         }
-
-        // Check if still pending
-        if order.status != OrderStatus::Pending {
-            return Err(NoetherError::OrderNotPending);
+        if close_bps == 0 || close_bps > BASIS_POINTS {
+            return Err(NoetherError::InvalidParameter);
         }
-
-        // Refund collateral for limit orders
-        if order.order_type == OrderType::LimitEntry && order.collateral > 0 {
-            let usdc_token = get_usdc_token(&env);
-            let token_client = token::Client::new(&env, &usdc_token);
-            token_client.transfer(&env.current_contract_address(), &trader, &order.collateral);
+        if slippage_tolerance_bps == 0 || slippage_tolerance_bps > 10000 {
+            return Err(NoetherError::InvalidSlippageTolerance);
         }
+        Self::validate_order_expiry(&env, expires_at)?;
 
-        // Remove SL/TP links if attached to position
-        if order.has_position {
-            match order.order_type {
-                OrderType::StopLoss => remove_position_stop_loss(&env, order.position_id),
-                OrderType::TakeProfit => remove_position_take_profit(&env, order.position_id),
-                _ => {}
-            }
-        }
+        // Get position
+        let position = get_position(&env, position_id)
+            .ok_or(NoetherError::PositionNotFound)?;
 
-        // Update order status
-        update_order_status(&env, order_id, OrderStatus::Cancelled);
+        // Verify ownership
+        if position.trader != trader {
+            return Err(NoetherError::NotPositionOwner);
+        }
 
-        extend_instance_ttl(&env);
+        // Bound the number of close orders per position
+        Self::check_position_order_capacity(&env, position_id)?;
+
+        // Start trailing from the current price
+        let trailing_mark = Self::get_oracle_price(&env, &position.asset)?;
+        let order = Self::attach_close_order(
+            &env,
+            &position,
+            OrderType::TrailingStop,
+            CloseTrigger::Trailing { distance_bps: trailing_distance_bps, mark: trailing_mark },
+            close_bps,
+            slippage_tolerance_bps,
+            expires_at,
+        );
 
         env.events().publish(
-            (Symbol::new(&env, "order_cancelled"),),
-            (order_id, trader, Symbol::new(&env, "user_cancelled")),
+            (Symbol::new(&env, "trailing_stop_set"),),
+            (order.id, position_id, trailing_distance_bps, trailing_mark, order.trigger_price),
         );
 
-        Ok(())
+        Ok(order)
     }
 
-    /// Execute a triggered order (called by keeper).
-    /// Checks if price condition is met and executes the order.
-    /// Keeper receives a fee for successful execution.
+    /// Ratchet a trailing stop's mark to the current oracle price.
+    /// Open to any keeper (registered keepers only while the allowlist is on):
+    /// the mark only ever moves in the trader's favour, so keepers can call this
+    /// whenever price makes a new high (longs) or low (shorts).
     ///
     /// # Arguments
-    /// * `keeper` - Address of the keeper executing the order
-    /// * `order_id` - ID of the order to execute
+    /// * `keeper` - Address ratcheting the mark (recorded in the keeper registry)
+    /// * `order_id` - ID of the trailing stop order
     ///
     /// # Returns
-    /// Keeper reward amount
-    pub fn execute_order(
-        env: Env,
-        keeper: Address,
-        order_id: u64,
-    ) -> Result<i128, NoetherError> {
+    /// The order's stop price after the update
+    pub fn update_trailing_stop(env: Env, keeper: Address, order_id: u64) -> Result<i128, NoetherError> {
         require_initialized(&env)?;
 
         keeper.require_auth();
+        require_keeper(&env, &keeper)?;
 
-        // Get order
-        let order = get_order(&env, order_id)
+        let mut order = get_order(&env, order_id)
             .ok_or(NoetherError::OrderNotFound)?;
 
-        // Check if still pending
-        if order.status != OrderStatus::Pending {
+        if order.status != OrderStatus::Pending || order.order_type != OrderType::TrailingStop {
             return Err(NoetherError::OrderNotPending);
         }
 
-        // Get current price
         let current_price = Self::get_oracle_price(&env, &order.asset)?;
+        let new_mark = ratchet_trailing_mark(order.direction, order.trailing_mark, current_price);
 
-        // Check if trigger condition is met
-        let triggered = match order.trigger_condition {
-            TriggerCondition::Above => current_price >= order.trigger_price,
-            TriggerCondition::Below => current_price <= order.trigger_price,
-        };
+        if new_mark != order.trailing_mark {
+            order.trailing_mark = new_mark;
+            order.trigger_price =
+                calculate_trailing_stop_price(order.direction, new_mark, order.trailing_distance_bps);
+            save_order(&env, &order);
+            Self::record_keeper_jobs(&env, &keeper, KeeperJob::TrailingUpdate, 1, 0);
 
-        if !triggered {
-            return Err(NoetherError::OrderNotTriggered);
+            env.events().publish(
+                (Symbol::new(&env, "trailing_stop_updated"),),
+                (order_id, new_mark, order.trigger_price),
+            );
         }
 
-        // Check slippage
-        let price_diff = if current_price > order.trigger_price {
-            current_price - order.trigger_price
-        } else {
-            order.trigger_price - current_price
-        };
-        let actual_slippage_bps = (price_diff * 10_000) / order.trigger_price;
+        Ok(order.trigger_price)
+    }
 
-        if actual_slippage_bps > order.slippage_tolerance_bps as i128 {
-            // Slippage exceeded - cancel the order and commit the cancellation
-            // IMPORTANT: We return Ok(0) instead of Err() so the transaction commits
-            // and the order is properly removed from the pending list. Returning Err()
-            // would rollback all state changes, leaving the order stuck in pending.
+    /// Amend a pending order in place, keeping its ID and position link.
+    /// Avoids the cancel-then-replace window where a position is unprotected.
+    /// Triggers are re-validated exactly as in `set_stop_loss` / `set_take_profit`.
+    /// Trailing stops derive their trigger from the mark, so only slippage and
+    /// expiry can change (`params.trigger_price` must equal the current trigger).
+    ///
+    /// # Arguments
+    /// * `trader` - Address of the trader (must own order)
+    /// * `order_id` - ID of the order to amend
+    /// * `params` - New trigger, slippage and expiry; collateral and leverage
+    ///   apply to LimitEntry orders only (0 = unchanged)
+    ///
+    /// # Returns
+    /// The amended Order
+    pub fn modify_order(
+        env: Env,
+        trader: Address,
+        order_id: u64,
+        params: ModifyOrderParams,
+    ) -> Result<Order, NoetherError> {
+        require_initialized(&env)?;
+        require_not_paused(&env)?;
+
+        trader.require_auth();
+
+        // Get order
+        let mut order = get_order(&env, order_id)
+            .ok_or(NoetherError::OrderNotFound)?;
+
+        // Verify ownership and that it can still execute
+        if order.trader != trader {
+            return Err(NoetherError::NotOrderOwner);
+        }
+        if order.status != OrderStatus::Pending {
+            return Err(NoetherError::OrderNotPending);
+        }
+        if Self::is_order_expired(&env, &order) {
+            return Err(NoetherError::OrderExpired);
+        }
+
+        // Validate common parameters
+        if params.trigger_price <= 0 {
+            return Err(NoetherError::InvalidTriggerPrice);
+        }
+        if params.slippage_tolerance_bps == 0 || params.slippage_tolerance_bps > 10000 {
+            return Err(NoetherError::InvalidSlippageTolerance);
+        }
+        Self::validate_order_expiry(&env, params.expires_at)?;
 
-            if order.order_type == OrderType::LimitEntry && order.collateral > 0 {
-                // Refund collateral
+        match order.order_type {
+            OrderType::LimitEntry => {
+                let config = Self::get_listed_asset_config(&env, &order.asset)?;
+
+                let collateral = if params.collateral == 0 { order.collateral } else { params.collateral };
+                let leverage = if params.leverage == 0 { order.leverage } else { params.leverage };
+                if collateral < config.min_collateral {
+                    return Err(NoetherError::InsufficientCollateral);
+                }
+                if leverage < 1 || leverage > config.max_leverage {
+                    return Err(NoetherError::InvalidLeverage);
+                }
+                if calculate_position_size(collateral, leverage) > config.max_position_size {
+                    return Err(NoetherError::PositionTooLarge);
+                }
+
+                // Top up or refund the locked collateral and the re-priced execution fee
+                let execution_fee =
+                    Self::calculate_execution_fee(&env, &order.asset, calculate_position_size(collateral, leverage));
+                let locked_delta = (collateral + execution_fee) - (order.collateral + order.execution_fee);
                 let usdc_token = get_usdc_token(&env);
                 let token_client = token::Client::new(&env, &usdc_token);
-                token_client.transfer(&env.current_contract_address(), &order.trader, &order.collateral);
+                if locked_delta > 0 {
+                    token_client.transfer(&trader, &env.current_contract_address(), &locked_delta);
+                } else if locked_delta < 0 {
+                    token_client.transfer(&env.current_contract_address(), &trader, &(-locked_delta));
+                }
+
+                order.collateral = collateral;
+                order.leverage = leverage;
+                order.execution_fee = execution_fee;
             }
+            OrderType::StopLoss | OrderType::TakeProfit | OrderType::TrailingStop => {
+                // Collateral and leverage belong to the position, not the close order
+                if params.collateral != 0 || params.leverage != 0 {
+                    return Err(NoetherError::InvalidParameter);
+                }
 
-            // Remove SL/TP links
-            if order.has_position {
-                match order.order_type {
-                    OrderType::StopLoss => remove_position_stop_loss(&env, order.position_id),
-                    OrderType::TakeProfit => remove_position_take_profit(&env, order.position_id),
-                    _ => {}
+                let position = get_position(&env, order.position_id)
+                    .ok_or(NoetherError::PositionNotFound)?;
+
+                let valid = match order.order_type {
+                    OrderType::StopLoss => {
+                        is_stop_loss_trigger_valid(position.direction, position.entry_price, params.trigger_price)
+                    }
+                    OrderType::TakeProfit => {
+                        is_take_profit_trigger_valid(position.direction, position.entry_price, params.trigger_price)
+                    }
+                    _ => params.trigger_price == order.trigger_price,
+                };
+                if !valid {
+                    return Err(NoetherError::InvalidTriggerPrice);
                 }
             }
+        }
+
+        order.trigger_price = params.trigger_price;
+        order.slippage_tolerance_bps = params.slippage_tolerance_bps;
+        order.expires_at = params.expires_at;
 
-            update_order_status(&env, order_id, OrderStatus::CancelledSlippage);
+        save_order(&env, &order);
 
-            extend_instance_ttl(&env);
+        extend_instance_ttl(&env);
 
-            env.events().publish(
-                (Symbol::new(&env, "order_cancelled"),),
-                (order_id, order.trader.clone(), Symbol::new(&env, "slippage_exceeded")),
+        env.events().publish(
+            (Symbol::new(&env, "order_modified"),),
+            (
+                order_id,
+                trader,
+                order.trigger_price,
+                order.slippage_tolerance_bps,
+                order.expires_at,
+                order.collateral,
+                order.leverage,
+            ),
+        );
+
+        Ok(order)
+    }
+
+    /// Cancel a pending order.
+    /// For limit orders, refunds the locked collateral.
+    ///
+    /// # Arguments
+    /// * `trader` - Address of the trader (must own order)
+    /// * `order_id` - ID of the order to cancel
+    pub fn cancel_order(
+        env: Env,
+        trader: Address,
+        order_id: u64,
+    ) -> Result<(), NoetherError> {
+        require_initialized(&env)?;
+
+        trader.require_auth();
+
+        // Get order
+        let order = get_order(&env, order_id)
+            .ok_or(NoetherError::OrderNotFound)?;
+
+        // Verify ownership
+        if order.trader != trader {
+            return Err(NoetherError::NotOrderOwner);
+        }
+
+        // Check if still pending
+        if order.status != OrderStatus::Pending {
+            return Err(NoetherError::OrderNotPending);
+        }
+
+        // Refund collateral, unlink from position and mark cancelled
+        Self::cancel_pending_order(
+            &env,
+            &order,
+            OrderStatus::Cancelled,
+            Symbol::new(&env, "user_cancelled"),
+            0,
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Execute a triggered order (called by keeper).
+    /// Checks if price condition is met and executes the order.
+    /// Keeper is credited the order's prepaid execution fee from escrow.
+    ///
+    /// # Arguments
+    /// * `keeper` - Address of the keeper executing the order
+    /// * `order_id` - ID of the order to execute
+    ///
+    /// # Returns
+    /// Keeper reward amount
+    pub fn execute_order(
+        env: Env,
+        keeper: Address,
+        order_id: u64,
+    ) -> Result<i128, NoetherError> {
+        require_initialized(&env)?;
+
+        keeper.require_auth();
+        require_keeper(&env, &keeper)?;
+
+        // Get order
+        let order = get_order(&env, order_id)
+            .ok_or(NoetherError::OrderNotFound)?;
+
+        // Check if still pending and not past its expiry
+        if order.status != OrderStatus::Pending {
+            return Err(NoetherError::OrderNotPending);
+        }
+        if Self::is_order_expired(&env, &order) {
+            return Err(NoetherError::OrderExpired);
+        }
+
+        // Get current price
+        let current_price = Self::get_oracle_price(&env, &order.asset)?;
+
+        // Check if trigger condition is met
+        let triggered = match order.trigger_condition {
+            TriggerCondition::Above => current_price >= order.trigger_price,
+            TriggerCondition::Below => current_price <= order.trigger_price,
+        };
+
+        if !triggered {
+            return Err(NoetherError::OrderNotTriggered);
+        }
+
+        Self::execute_triggered_order(&env, &keeper, &order, current_price)
+    }
+
+    /// Execute several triggered orders in one transaction.
+    /// Each asset's oracle price is read once. IDs that are unknown, no longer
+    /// pending, expired, not triggered, detached from a closed position, whose
+    /// price is unavailable or whose execution fails (e.g. the Vault cannot
+    /// reserve or pay out) are skipped rather than reverting the batch.
+    /// Orders cancelled for slippage or the open interest cap count as processed
+    /// with zero reward, as in `execute_order`.
+    ///
+    /// # Arguments
+    /// * `keeper` - Address executing the orders (credited execution fees)
+    /// * `order_ids` - IDs of orders to execute
+    ///
+    /// # Returns
+    /// One result per input ID, in input order
+    pub fn execute_orders_batch(
+        env: Env,
+        keeper: Address,
+        order_ids: Vec<u64>,
+    ) -> Result<Vec<BatchResult>, NoetherError> {
+        require_initialized(&env)?;
+
+        keeper.require_auth();
+        require_keeper(&env, &keeper)?;
+
+        let mut prices: Map<Symbol, i128> = Map::new(&env);
+        let mut results = Vec::new(&env);
+        let mut executed: u32 = 0;
+        let mut total_reward: i128 = 0;
+
+        for order_id in order_ids.iter() {
+            let order = match get_order(&env, order_id) {
+                Some(order) => order,
+                None => {
+                    results.push_back(Self::skipped(order_id, NoetherError::OrderNotFound));
+                    continue;
+                }
+            };
+            if order.status != OrderStatus::Pending {
+                results.push_back(Self::skipped(order_id, NoetherError::OrderNotPending));
+                continue;
+            }
+            if Self::is_order_expired(&env, &order) {
+                results.push_back(Self::skipped(order_id, NoetherError::OrderExpired));
+                continue;
+            }
+            if order.has_position && get_position(&env, order.position_id).is_none() {
+                results.push_back(Self::skipped(order_id, NoetherError::PositionNotFound));
+                continue;
+            }
+
+            let current_price = match Self::get_cached_oracle_price(&env, &mut prices, &order.asset) {
+                Ok(price) => price,
+                Err(e) => {
+                    results.push_back(Self::skipped(order_id, e));
+                    continue;
+                }
+            };
+
+            let triggered = match order.trigger_condition {
+                TriggerCondition::Above => current_price >= order.trigger_price,
+                TriggerCondition::Below => current_price <= order.trigger_price,
+            };
+            if !triggered {
+                results.push_back(Self::skipped(order_id, NoetherError::OrderNotTriggered));
+                continue;
+            }
+
+            match Self::execute_triggered_order(&env, &keeper, &order, current_price) {
+                Ok(reward) => {
+                    executed += 1;
+                    total_reward += reward;
+                    results.push_back(BatchResult { id: order_id, success: true, reward, error_code: 0 });
+                }
+                Err(e) => results.push_back(Self::skipped(order_id, e)),
+            }
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "order_batch_executed"),),
+            (keeper, order_ids.len(), executed, total_reward),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(results)
+    }
+
+    /// Expire pending orders that have passed their `expires_at`.
+    /// Permissionless unless the keeper allowlist is on. The caller is credited the
+    /// asset's expiry fee out of each order's escrowed execution fee; the rest
+    /// of the escrow and any locked collateral go back to the trader.
+    /// IDs that are unknown, not pending or not yet expired are skipped.
+    ///
+    /// # Arguments
+    /// * `caller` - Address expiring the orders (receives the expiry fees)
+    /// * `order_ids` - Orders to check
+    ///
+    /// # Returns
+    /// IDs of the orders that were expired
+    pub fn expire_orders(
+        env: Env,
+        caller: Address,
+        order_ids: Vec<u64>,
+    ) -> Result<Vec<u64>, NoetherError> {
+        require_initialized(&env)?;
+
+        caller.require_auth();
+        require_keeper(&env, &caller)?;
+
+        let mut expired = Vec::new(&env);
+        let mut total_fees: i128 = 0;
+
+        for order_id in order_ids.iter() {
+            let order = match get_order(&env, order_id) {
+                Some(order) => order,
+                None => continue,
+            };
+            if order.status != OrderStatus::Pending || !Self::is_order_expired(&env, &order) {
+                continue;
+            }
+
+            let expiry_fee = get_keeper_fee_config(&env, &order.asset)
+                .expiry_fee
+                .min(order.execution_fee);
+            total_fees += expiry_fee;
+
+            Self::cancel_pending_order(
+                &env,
+                &order,
+                OrderStatus::Expired,
+                Symbol::new(&env, "expired"),
+                expiry_fee,
             );
+            expired.push_back(order_id);
+        }
+
+        if !expired.is_empty() {
+            Self::record_keeper_jobs(&env, &caller, KeeperJob::OrderExpiry, expired.len() as u64, total_fees);
+        }
+
+        extend_instance_ttl(&env);
+
+        env.events().publish(
+            (Symbol::new(&env, "orders_expired"),),
+            (caller, expired.len(), total_fees),
+        );
+
+        Ok(expired)
+    }
+
+    /// Check if an order should be executed at current price.
+    pub fn should_execute_order(env: Env, order_id: u64) -> Result<bool, NoetherError> {
+        let order = get_order(&env, order_id)
+            .ok_or(NoetherError::OrderNotFound)?;
+
+        if order.status != OrderStatus::Pending || Self::is_order_expired(&env, &order) {
+            return Ok(false);
+        }
+
+        let current_price = Self::get_oracle_price(&env, &order.asset)?;
+
+        let triggered = match order.trigger_condition {
+            TriggerCondition::Above => current_price >= order.trigger_price,
+            TriggerCondition::Below => current_price <= order.trigger_price,
+        };
+
+        Ok(triggered)
+    }
+
+    /// Get a page of a trader's pending orders, ordered by ID.
+    /// Pages follow the same cursor rules as `get_positions`.
+    pub fn get_orders(env: Env, trader: Address, start_after: u64, limit: u32) -> Result<Vec<Order>, NoetherError> {
+        get_trader_orders(&env, &trader, start_after, limit)
+    }
+
+    /// Get a specific order by ID.
+    pub fn get_order(env: Env, order_id: u64) -> Option<Order> {
+        get_order(&env, order_id)
+    }
+
+    /// Get a page of pending order IDs (for keeper).
+    /// Pages follow the same cursor rules as `get_positions`.
+    pub fn get_all_order_ids(env: Env, start_after: u64, limit: u32) -> Result<Vec<u64>, NoetherError> {
+        index_page(&env, &IndexKey::AllOrders, start_after, limit)
+    }
+
+    /// Get a page of pending order IDs in an asset.
+    pub fn get_asset_order_ids(env: Env, asset: Symbol, start_after: u64, limit: u32) -> Result<Vec<u64>, NoetherError> {
+        index_page(&env, &IndexKey::AssetOrders(asset), start_after, limit)
+    }
+
+    /// Get the number of pending orders across all assets.
+    pub fn get_order_count(env: Env) -> u64 {
+        get_order_count(&env)
+    }
+
+    /// Get close orders (SL/TP/trailing stop) attached to a position.
+    pub fn get_position_orders(env: Env, position_id: u64) -> Vec<Order> {
+        let mut orders = Vec::new(&env);
+        for order_id in get_position_order_ids(&env, position_id).iter() {
+            if let Some(order) = get_order(&env, order_id) {
+                orders.push_back(order);
+            }
+        }
+        orders
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Internal Functions
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Update a keeper's counters and credit `reward` to their claimable balance.
+    /// The reward stays in the market contract until claimed.
+    fn record_keeper_jobs(env: &Env, keeper: &Address, job: KeeperJob, count: u64, reward: i128) {
+        let mut info = get_keeper_info(env, keeper);
+        let job_name = match job {
+            KeeperJob::Liquidation => {
+                info.liquidations += count;
+                "liquidation"
+            }
+            KeeperJob::Deleverage => {
+                info.deleverages += count;
+                "deleverage"
+            }
+            KeeperJob::OrderExecution => {
+                info.orders_executed += count;
+                "order_execution"
+            }
+            KeeperJob::OrderExpiry => {
+                info.orders_expired += count;
+                "order_expiry"
+            }
+            KeeperJob::FundingTick => {
+                info.funding_ticks += count;
+                "funding_tick"
+            }
+            KeeperJob::TrailingUpdate => {
+                info.trailing_updates += count;
+                "trailing_update"
+            }
+            KeeperJob::PnlSync => {
+                info.pnl_syncs += count;
+                "pnl_sync"
+            }
+        };
+        info.accrued_rewards += reward;
+        info.last_active = env.ledger().timestamp();
+        save_keeper_info(env, keeper, &info);
+
+        env.events().publish(
+            (Symbol::new(env, "keeper_job"),),
+            (keeper.clone(), Symbol::new(env, job_name), count, reward),
+        );
+    }
+
+    /// Validate market configuration parameters.
+    fn validate_config(config: &MarketConfig) -> Result<(), NoetherError> {
+        if config.max_leverage < 1 || config.max_leverage > 100 {
+            return Err(NoetherError::InvalidParameter);
+        }
+        if config.insurance_fee_bps > BASIS_POINTS {
+            return Err(NoetherError::InvalidParameter);
+        }
+        if config.max_long_open_interest < 0 || config.max_short_open_interest < 0 {
+            return Err(NoetherError::InvalidParameter);
+        }
+        if config.price_impact_skew_scale < 0 || config.max_price_impact_bps >= BASIS_POINTS {
+            return Err(NoetherError::InvalidParameter);
+        }
+        if config.adl_target_coverage_bps <= BASIS_POINTS
+            || config.adl_target_coverage_bps < config.adl_trigger_coverage_bps
+        {
+            return Err(NoetherError::InvalidParameter);
+        }
+        if config.partial_liquidation_target_bps > 0
+            && (config.partial_liquidation_target_bps <= config.maintenance_margin_bps
+                || config.full_liquidation_margin_bps > config.maintenance_margin_bps)
+        {
+            return Err(NoetherError::InvalidParameter);
+        }
+        Ok(())
+    }
+
+    /// Ensure adding `size` on one side keeps that side's open interest within its cap.
+    fn check_open_interest_cap(
+        env: &Env,
+        asset: &Symbol,
+        config: &MarketConfig,
+        direction: Direction,
+        size: i128,
+    ) -> Result<(), NoetherError> {
+        let (open_interest, cap) = match direction {
+            Direction::Long => (get_total_long_size(env, asset), config.max_long_open_interest),
+            Direction::Short => (get_total_short_size(env, asset), config.max_short_open_interest),
+        };
+        if open_interest + size > cap {
+            return Err(NoetherError::OpenInterestCapExceeded);
+        }
+        Ok(())
+    }
+
+    /// Get parameters for an asset that is open for new exposure.
+    fn get_listed_asset_config(env: &Env, asset: &Symbol) -> Result<MarketConfig, NoetherError> {
+        if !is_asset_listed(env, asset) {
+            return Err(NoetherError::AssetNotListed);
+        }
+        Ok(Self::get_asset_config_or_default(env, asset))
+    }
+
+    /// Get parameters for an asset, falling back to the market defaults.
+    /// Delisted assets keep their parameters for closing and liquidation.
+    fn get_asset_config_or_default(env: &Env, asset: &Symbol) -> MarketConfig {
+        get_asset_config(env, asset).unwrap_or_else(|| get_config(env))
+    }
+
+    /// Fetch price from oracle adapter.
+    fn get_oracle_price(env: &Env, asset: &Symbol) -> Result<i128, NoetherError> {
+        let oracle_address = get_oracle_adapter(env);
+
+        // Call oracle adapter using invoke_contract
+        let args: Vec<soroban_sdk::Val> = (asset.clone(),).into_val(env);
+        let (price, timestamp): (i128, u64) = env.invoke_contract(
+            &oracle_address,
+            &Symbol::new(env, "lastprice"),
+            args,
+        );
+
+        // Check staleness
+        let config = Self::get_asset_config_or_default(env, asset);
+        let current_time = env.ledger().timestamp();
+
+        if current_time > timestamp && current_time - timestamp > config.max_price_staleness {
+            return Err(NoetherError::PriceStale);
+        }
+
+        if price <= 0 {
+            return Err(NoetherError::InvalidPrice);
+        }
+
+        Ok(price)
+    }
+
+    /// Apply skew-based price impact to an oracle price.
+    /// Opening longs and closing shorts buy (raise skew); the rest sell.
+    ///
+    /// # Returns
+    /// (execution_price, price_impact) with price_impact as the USD cost to the trader
+    fn get_execution_price(
+        env: &Env,
+        asset: &Symbol,
+        config: &MarketConfig,
+        oracle_price: i128,
+        direction: Direction,
+        size: i128,
+        is_opening: bool,
+    ) -> (i128, i128) {
+        let skew = get_total_long_size(env, asset) - get_total_short_size(env, asset);
+        let skew_delta = if (direction == Direction::Long) == is_opening { size } else { -size };
+
+        calculate_execution_price(
+            oracle_price,
+            skew,
+            skew_delta,
+            config.price_impact_skew_scale,
+            config.max_price_impact_bps,
+        )
+    }
+
+    /// Reserve Vault liquidity for a potential payout.
+    fn reserve_vault_liquidity(env: &Env, vault: &Address, amount: i128) -> Result<(), NoetherError> {
+        // Call vault's reserve_for_position function
+        // Fails if unreserved liquidity cannot cover the amount; no funds move
+        let args: Vec<soroban_sdk::Val> = (amount,).into_val(env);
+        let result = env.try_invoke_contract::<(), NoetherError>(
+            vault,
+            &Symbol::new(env, "reserve_for_position"),
+            args,
+        );
+
+        Self::vault_call_result(result)
+    }
+
+    /// Release Vault liquidity reserved for closed size.
+    fn release_vault_liquidity(env: &Env, vault: &Address, amount: i128) -> Result<(), NoetherError> {
+        if amount <= 0 {
+            return Ok(());
+        }
+
+        let args: Vec<soroban_sdk::Val> = (amount,).into_val(env);
+        let _: () = env.invoke_contract(
+            vault,
+            &Symbol::new(env, "release_reserve"),
+            args,
+        );
+
+        Ok(())
+    }
+
+    /// Force-reduce a winning position at the oracle price to realize up to
+    /// `profit_needed`. Profit beyond the Vault's USDC is not paid (haircut).
+    ///
+    /// # Returns
+    /// Profit removed from open trader PnL by the reduction
+    fn execute_adl(
+        env: &Env,
+        vault_address: &Address,
+        position_id: u64,
+        current_price: i128,
+        profit_needed: i128,
+    ) -> Result<i128, NoetherError> {
+        let mut position = get_position(env, position_id)
+            .ok_or(NoetherError::PositionNotFound)?;
+
+        // Apply pending funding
+        Self::apply_funding_to_position(env, &mut position)?;
+
+        let pnl = calculate_pnl(&position, current_price)?;
+        let config = Self::get_asset_config_or_default(env, &position.asset);
+
+        // Split the position; tiny remainders are closed in full
+        let mut close_bps = calculate_adl_close_bps(pnl, profit_needed);
+        if close_bps == 0 {
+            return Ok(0);
+        }
+        let (mut close_collateral, mut close_size, remaining_collateral, _) =
+            calculate_partial_close(&position, close_bps);
+        if close_bps >= BASIS_POINTS || remaining_collateral < config.min_collateral {
+            close_bps = BASIS_POINTS;
+            close_collateral = position.collateral;
+            close_size = position.size;
+        }
+        let full_close = close_bps >= BASIS_POINTS;
+
+        // Realize PnL and funding on the closed portion only
+        let mut closed_part = position.clone();
+        closed_part.size = close_size;
+        let closed_pnl = calculate_pnl(&closed_part, current_price)?;
+        let closed_funding = position.accumulated_funding * (close_bps as i128) / (BASIS_POINTS as i128);
+        let closed_borrow_fee = position.accrued_borrow_fee * (close_bps as i128) / (BASIS_POINTS as i128);
+
+        // Pay at most what the Vault holds
+        let paid_pnl = closed_pnl.min(Self::get_vault_total_usdc(env, vault_address).max(0));
+        let haircut = closed_pnl - paid_pnl;
+        let to_trader = close_collateral + paid_pnl - closed_funding - closed_borrow_fee;
+
+        let usdc_token = get_usdc_token(env);
+        let token_client = token::Client::new(env, &usdc_token);
+
+        // Settle with vault and pay the closed share of accrued borrow fees
+        // before releasing the closed size's reservation, so a failed Vault
+        // call leaves the position as it was
+        Self::settle_with_vault(env, vault_address, paid_pnl)?;
+        Self::pay_fees_to_vault(env, vault_address, &token_client, closed_borrow_fee, 0)?;
+        Self::release_vault_liquidity(env, vault_address, close_size)?;
+
+        // Transfer realized funding to vault (if any)
+        if closed_funding > 0 {
+            token_client.transfer(&env.current_contract_address(), vault_address, &closed_funding);
+        }
+
+        // Transfer to trader (if positive)
+        if to_trader > 0 {
+            token_client.transfer(&env.current_contract_address(), &position.trader, &to_trader);
+        }
+
+        // Update market stats
+        let remaining_size = position.size - close_size;
+        let closed_quantity = calculate_position_quantity(position.size, position.entry_price)
+            - calculate_position_quantity(remaining_size, position.entry_price);
+        Self::update_open_interest(
+            env,
+            &position.asset,
+            position.direction,
+            -close_size,
+            -closed_quantity,
+        );
+
+        if full_close {
+            Self::cancel_position_orders(env, position.id, Symbol::new(env, "auto_deleveraged"));
+            delete_position(env, position.id, &position.trader);
+        } else {
+            position.collateral -= close_collateral;
+            position.size = remaining_size;
+            position.accumulated_funding -= closed_funding;
+            position.accrued_borrow_fee -= closed_borrow_fee;
+            position.liquidation_price = calculate_liquidation_price_from_collateral(
+                position.entry_price,
+                position.size,
+                position.collateral,
+                position.direction,
+                config.maintenance_margin_bps,
+            );
+            save_position(env, &position);
+        }
+
+        env.events().publish(
+            (Symbol::new(env, "adl_executed"),),
+            (
+                position.id,
+                position.trader.clone(),
+                position.asset.clone(),
+                position.direction,
+                close_size,
+                remaining_size,
+                current_price,
+                paid_pnl,
+                haircut,
+            ),
+        );
+
+        Ok(closed_pnl)
+    }
+
+    /// Liquidate a position already known to be liquidatable at `current_price`.
+    /// Shared by `liquidate` and `liquidate_batch`.
+    fn liquidate_position(
+        env: &Env,
+        keeper: &Address,
+        mut position: Position,
+        current_price: i128,
+    ) -> Result<i128, NoetherError> {
+        let position_id = position.id;
+
+        // Apply pending funding
+        Self::apply_funding_to_position(env, &mut position)?;
+
+        let config = Self::get_asset_config_or_default(env, &position.asset);
+
+        // Reduce the position instead if it is not too far under water
+        if config.partial_liquidation_target_bps > 0
+            && calculate_margin_ratio_bps(&position, current_price)
+                >= config.full_liquidation_margin_bps as i128
+        {
+            if let Some(plan) = calculate_partial_liquidation(
+                &position,
+                current_price,
+                config.partial_liquidation_target_bps,
+                config.liquidation_fee_bps,
+            ) {
+                if plan.remaining_collateral >= config.min_collateral {
+                    return Self::partially_liquidate(env, keeper, position, current_price, &plan, &config);
+                }
+            }
+        }
+
+        // Calculate PnL
+        let pnl = calculate_pnl(&position, current_price)?;
+
+        // Liquidation fee comes from remaining equity and is split between the
+        // insurance fund and the keeper; losses beyond collateral are bad debt
+        let (actual_keeper_reward, insurance_fee, bad_debt) = calculate_liquidation_payout(
+            &position,
+            current_price,
+            config.liquidation_fee_bps,
+            config.insurance_fee_bps,
+        );
+        let liquidation_fee = actual_keeper_reward + insurance_fee;
+
+        // Calculate what goes to Vault (everything except the liquidation fee)
+        let vault_receives = if position.collateral > liquidation_fee {
+            position.collateral - liquidation_fee
+        } else {
+            0
+        };
+
+        // Get addresses and token client
+        let vault_address = get_vault(env);
+        let usdc_token = get_usdc_token(env);
+        let token_client = token::Client::new(env, &usdc_token);
+
+        // Accrued borrow fees are paid out of what the Vault receives as fees;
+        // the rest is settled as trader loss
+        let borrow_fee_paid = position.accrued_borrow_fee.clamp(0, vault_receives);
+        let vault_settles = vault_receives - borrow_fee_paid;
+
+        // Vault calls that can fail run before the reservation is released, so a
+        // failure leaves the position as it was and a batch can skip it.
+        // Bad debt is recorded against the insurance fund first.
+        if bad_debt > 0 {
+            Self::cover_vault_bad_debt(env, &vault_address, bad_debt)?;
+        }
+
+        // Settle with vault - pass the amount Vault is receiving (as negative pnl)
+        // This ensures Vault's total_usdc accounting matches actual token receipt
+        if vault_settles > 0 {
+            Self::settle_with_vault(env, &vault_address, -vault_settles)?;
+        }
+
+        // Pay borrow fees and the insurance cut of the liquidation fee
+        Self::pay_fees_to_vault(env, &vault_address, &token_client, borrow_fee_paid, insurance_fee)?;
+
+        // Release the position's reserved liquidity
+        Self::release_vault_liquidity(env, &vault_address, position.size)?;
+
+        if vault_settles > 0 {
+            token_client.transfer(&env.current_contract_address(), &vault_address, &vault_settles);
+        }
+
+        // Credit keeper reward (claimable via claim_keeper_rewards)
+        Self::record_keeper_jobs(env, keeper, KeeperJob::Liquidation, 1, actual_keeper_reward);
+
+        // Update market stats
+        Self::update_open_interest(
+            env,
+            &position.asset,
+            position.direction,
+            -position.size,
+            -calculate_position_quantity(position.size, position.entry_price),
+        );
+
+        // Cancel attached close orders, then delete position
+        Self::cancel_position_orders(env, position_id, Symbol::new(env, "position_liquidated"));
+        delete_position(env, position_id, &position.trader);
+
+        // Emit comprehensive event with full trade data for frontend history
+        env.events().publish(
+            (Symbol::new(env, "position_liquidated"),),
+            (
+                position_id,
+                position.trader,
+                position.asset,
+                position.direction,
+                position.size,
+                position.entry_price,
+                current_price,
+                pnl,
+                keeper.clone(),
+                actual_keeper_reward,
+                bad_debt,
+            ),
+        );
+
+        extend_instance_ttl(env);
+
+        Ok(actual_keeper_reward)
+    }
+
+    /// Close part of an underwater position so the rest is back at the target margin.
+    fn partially_liquidate(
+        env: &Env,
+        keeper: &Address,
+        mut position: Position,
+        current_price: i128,
+        plan: &PartialLiquidation,
+        config: &MarketConfig,
+    ) -> Result<i128, NoetherError> {
+        let vault_address = get_vault(env);
+        let usdc_token = get_usdc_token(env);
+        let token_client = token::Client::new(env, &usdc_token);
+
+        // Split the fee between the insurance fund and the keeper
+        let insurance_fee = plan.liquidation_fee * (config.insurance_fee_bps as i128) / (BASIS_POINTS as i128);
+        let keeper_reward = plan.liquidation_fee - insurance_fee;
+
+        // Vault calls that can fail run before the reservation is released, so a
+        // failure leaves the position as it was; the closed share of accrued
+        // borrow fees is paid with the insurance cut
+        Self::settle_with_vault(env, &vault_address, plan.realized_pnl)?;
+        Self::pay_fees_to_vault(env, &vault_address, &token_client, plan.realized_borrow_fee, insurance_fee)?;
+        Self::release_vault_liquidity(env, &vault_address, plan.close_size)?;
+
+        if plan.realized_pnl < 0 {
+            let loss = -plan.realized_pnl;
+            token_client.transfer(&env.current_contract_address(), &vault_address, &loss);
+        }
+
+        // Transfer realized funding to vault (if any)
+        if plan.realized_funding > 0 {
+            token_client.transfer(&env.current_contract_address(), &vault_address, &plan.realized_funding);
+        }
+
+        Self::record_keeper_jobs(env, keeper, KeeperJob::Liquidation, 1, keeper_reward);
+
+        // Update market stats
+        let remaining_size = position.size - plan.close_size;
+        let closed_quantity = calculate_position_quantity(position.size, position.entry_price)
+            - calculate_position_quantity(remaining_size, position.entry_price);
+        Self::update_open_interest(
+            env,
+            &position.asset,
+            position.direction,
+            -plan.close_size,
+            -closed_quantity,
+        );
+
+        // Keep the remainder open
+        position.size = remaining_size;
+        position.collateral = plan.remaining_collateral;
+        position.accumulated_funding -= plan.realized_funding;
+        position.accrued_borrow_fee -= plan.realized_borrow_fee;
+        position.liquidation_price = calculate_liquidation_price_from_collateral(
+            position.entry_price,
+            position.size,
+            position.collateral,
+            position.direction,
+            config.maintenance_margin_bps,
+        );
+
+        save_position(env, &position);
+
+        env.events().publish(
+            (Symbol::new(env, "position_partially_liquidated"),),
+            (
+                position.id,
+                position.trader.clone(),
+                position.asset.clone(),
+                position.direction,
+                plan.close_size,
+                position.size,
+                current_price,
+                plan.realized_pnl,
+                keeper.clone(),
+                keeper_reward,
+            ),
+        );
+
+        extend_instance_ttl(env);
+
+        Ok(keeper_reward)
+    }
+
+    /// Transfer a trading fee to the vault, crediting the insurance fund's share.
+    fn pay_trading_fee(
+        env: &Env,
+        vault: &Address,
+        token_client: &token::Client,
+        fee: i128,
+        insurance_fee_bps: u32,
+    ) -> Result<(), NoetherError> {
+        let insurance_fee = fee * (insurance_fee_bps as i128) / (BASIS_POINTS as i128);
+        Self::pay_fees_to_vault(env, vault, token_client, fee - insurance_fee, insurance_fee)
+    }
+
+    /// Record fees with the vault (LP share and insurance share), then transfer them.
+    /// Nothing is transferred if the vault rejects the fees.
+    fn pay_fees_to_vault(
+        env: &Env,
+        vault: &Address,
+        token_client: &token::Client,
+        lp_fee: i128,
+        insurance_fee: i128,
+    ) -> Result<(), NoetherError> {
+        let total = lp_fee + insurance_fee;
+        if total <= 0 {
+            return Ok(());
+        }
+
+        let args: Vec<soroban_sdk::Val> = (lp_fee, insurance_fee).into_val(env);
+        let result = env.try_invoke_contract::<(), NoetherError>(
+            vault,
+            &Symbol::new(env, "receive_fees"),
+            args,
+        );
+        Self::vault_call_result(result)?;
+
+        token_client.transfer(&env.current_contract_address(), vault, &total);
+
+        Ok(())
+    }
+
+    /// Record bad debt with the vault, which covers what it can from the insurance fund.
+    fn cover_vault_bad_debt(env: &Env, vault: &Address, amount: i128) -> Result<(), NoetherError> {
+        let args: Vec<soroban_sdk::Val> = (amount,).into_val(env);
+        let result = env.try_invoke_contract::<i128, NoetherError>(
+            vault,
+            &Symbol::new(env, "cover_bad_debt"),
+            args,
+        );
+
+        match result {
+            Ok(Ok(_)) => Ok(()),
+            Err(Ok(e)) => Err(e),
+            _ => Err(NoetherError::InsufficientLiquidity),
+        }
+    }
+
+    /// Get the Vault's accounted USDC.
+    fn get_vault_total_usdc(env: &Env, vault: &Address) -> i128 {
+        env.invoke_contract(
+            vault,
+            &Symbol::new(env, "get_total_usdc"),
+            Vec::new(env),
+        )
+    }
+
+    /// Get the Vault's utilization (reserved / total USDC) in basis points.
+    fn get_vault_utilization(env: &Env, vault: &Address) -> u32 {
+        env.invoke_contract(
+            vault,
+            &Symbol::new(env, "get_utilization"),
+            Vec::new(env),
+        )
+    }
+
+    /// Settle PnL with vault contract.
+    fn settle_with_vault(env: &Env, vault: &Address, pnl: i128) -> Result<(), NoetherError> {
+        // Call vault's settle_pnl function
+        // - If pnl > 0: Vault transfers profit to Market
+        // - If pnl < 0: Vault updates accounting (Market transfers loss separately)
+        // Fails if the Vault cannot cover a profit; nothing is settled then
+        let args: Vec<soroban_sdk::Val> = (pnl,).into_val(env);
+        let result = env.try_invoke_contract::<(), NoetherError>(
+            vault,
+            &Symbol::new(env, "settle_pnl"),
+            args,
+        );
+
+        Self::vault_call_result(result)
+    }
+
+    /// Turn a fallible Vault call into a market error instead of a trap, so
+    /// batch callers can skip the item. The Vault's own state changes from a
+    /// failed call are rolled back.
+    fn vault_call_result(
+        result: Result<Result<(), soroban_sdk::ConversionError>, Result<NoetherError, soroban_sdk::InvokeError>>,
+    ) -> Result<(), NoetherError> {
+        match result {
+            Ok(Ok(())) => Ok(()),
+            Err(Ok(e)) => Err(e),
+            _ => Err(NoetherError::InsufficientLiquidity),
+        }
+    }
+
+    /// Add (or remove, if negative) size from an asset's open interest.
+    /// `quantity_delta` is the matching change in size / entry_price, which
+    /// keeps the aggregates used for pool-wide unrealized PnL exact.
+    /// The funding rate is refreshed so it reflects the new imbalance, and the
+    /// borrow rates of all assets so they reflect the vault utilization after
+    /// reservations change.
+    fn update_open_interest(
+        env: &Env,
+        asset: &Symbol,
+        direction: Direction,
+        size_delta: i128,
+        quantity_delta: i128,
+    ) {
+        if size_delta > 0 {
+            track_open_interest_asset(env, asset);
+        }
+
+        match direction {
+            Direction::Long => {
+                let total = get_total_long_size(env, asset);
+                set_total_long_size(env, asset, total + size_delta);
+                let quantity = get_total_long_quantity(env, asset);
+                set_total_long_quantity(env, asset, quantity + quantity_delta);
+            }
+            Direction::Short => {
+                let total = get_total_short_size(env, asset);
+                set_total_short_size(env, asset, total + size_delta);
+                let quantity = get_total_short_quantity(env, asset);
+                set_total_short_quantity(env, asset, quantity + quantity_delta);
+            }
+        }
+
+        Self::refresh_funding_rate(env, asset);
+        Self::refresh_borrow_rates(env);
+    }
+
+    /// Accrue the stored funding rate into the asset's cumulative index.
+    /// Must run before open interest changes so the old rate covers the elapsed time.
+    fn update_funding_index(env: &Env, asset: &Symbol) -> i128 {
+        let current_time = env.ledger().timestamp();
+        let last_update = get_last_funding_time(env, asset);
+        let mut funding_index = get_funding_index(env, asset);
+
+        if last_update > 0 && current_time > last_update {
+            let funding_rate = get_current_funding_rate(env, asset);
+            funding_index += calculate_funding_index_delta(funding_rate, current_time - last_update);
+            set_funding_index(env, asset, funding_index);
+        }
+
+        if current_time != last_update {
+            set_last_funding_time(env, asset, current_time);
+        }
+
+        funding_index
+    }
+
+    /// Recalculate and store the funding rate from current open interest.
+    fn refresh_funding_rate(env: &Env, asset: &Symbol) {
+        let config = Self::get_asset_config_or_default(env, asset);
+        let funding_rate = calculate_funding_rate(
+            get_total_long_size(env, asset),
+            get_total_short_size(env, asset),
+            config.base_funding_rate_bps,
+        );
+        set_current_funding_rate(env, asset, funding_rate);
+    }
+
+    /// Accrue the stored borrow rate into the asset's cumulative borrow index.
+    /// Must run before utilization changes so the old rate covers the elapsed time.
+    fn update_borrow_index(env: &Env, asset: &Symbol) -> i128 {
+        let current_time = env.ledger().timestamp();
+        let last_update = get_last_borrow_time(env, asset);
+        let mut borrow_index = get_borrow_index(env, asset);
+
+        if last_update > 0 && current_time > last_update {
+            let borrow_rate = get_current_borrow_rate(env, asset);
+            borrow_index += calculate_borrow_index_delta(borrow_rate, current_time - last_update);
+            set_borrow_index(env, asset, borrow_index);
+        }
+
+        if current_time != last_update {
+            set_last_borrow_time(env, asset, current_time);
+        }
+
+        borrow_index
+    }
+
+    /// Recalculate and store the borrow rate from current vault utilization.
+    fn refresh_borrow_rate(env: &Env, asset: &Symbol) {
+        let config = Self::get_asset_config_or_default(env, asset);
+        let utilization = Self::get_vault_utilization(env, &get_vault(env));
+        set_current_borrow_rate(env, asset, calculate_borrow_rate(utilization, config.max_borrow_rate_bps));
+    }
+
+    /// Checkpoint and re-price borrowing for every asset with open interest.
+    /// Utilization is shared across assets, so a trade in one asset changes
+    /// the rate all of them pay; each index accrues at its old rate first.
+    fn refresh_borrow_rates(env: &Env) {
+        let utilization = Self::get_vault_utilization(env, &get_vault(env));
+        for asset in get_open_interest_assets(env).iter() {
+            Self::update_borrow_index(env, &asset);
+            let config = Self::get_asset_config_or_default(env, &asset);
+            set_current_borrow_rate(env, &asset, calculate_borrow_rate(utilization, config.max_borrow_rate_bps));
+        }
+    }
+
+    /// Compute a position's health at `current_price` without modifying state.
+    fn calculate_position_health(
+        env: &Env,
+        mut position: Position,
+        current_price: i128,
+    ) -> Result<PositionHealth, NoetherError> {
+        // Project funding and borrow fees to now on a copy of the position
+        Self::project_fees(env, &mut position);
+
+        let config = Self::get_asset_config_or_default(env, &position.asset);
+        let (estimated_liquidation_payout, _, estimated_bad_debt) = calculate_liquidation_payout(
+            &position,
+            current_price,
+            config.liquidation_fee_bps,
+            config.insurance_fee_bps,
+        );
+
+        Ok(PositionHealth {
+            position_id: position.id,
+            current_price,
+            unrealized_pnl: calculate_pnl(&position, current_price)?,
+            accrued_funding: position.accumulated_funding,
+            accrued_borrow_fee: position.accrued_borrow_fee,
+            equity: calculate_current_margin(&position, current_price),
+            margin_ratio_bps: calculate_margin_ratio_bps(&position, current_price),
+            distance_to_liquidation_bps: calculate_distance_to_liquidation_pct(&position, current_price),
+            is_liquidatable: should_liquidate(&position, current_price, config.maintenance_margin_bps),
+            estimated_liquidation_payout,
+            estimated_bad_debt,
+        })
+    }
+
+    /// Whether a position is liquidatable once its funding and borrow fees are
+    /// projected to now.
+    fn is_position_liquidatable(env: &Env, position: &Position, current_price: i128) -> bool {
+        let mut position = position.clone();
+        Self::project_fees(env, &mut position);
+        let config = Self::get_asset_config_or_default(env, &position.asset);
+        should_liquidate(&position, current_price, config.maintenance_margin_bps)
+    }
+
+    /// Add funding and borrow fees accrued since the position's last checkpoint
+    /// without settling them.
+    fn project_fees(env: &Env, position: &mut Position) {
+        position.accumulated_funding += calculate_accrued_funding(
+            position.size,
+            position.direction,
+            position.funding_index,
+            Self::current_funding_index(env, &position.asset),
+        );
+        position.accrued_borrow_fee += calculate_accrued_borrow_fee(
+            position.size,
+            position.borrow_index,
+            Self::current_borrow_index(env, &position.asset),
+        );
+    }
+
+    /// Project the asset's funding index to now without storing it.
+    fn current_funding_index(env: &Env, asset: &Symbol) -> i128 {
+        let current_time = env.ledger().timestamp();
+        let last_update = get_last_funding_time(env, asset);
+        let funding_index = get_funding_index(env, asset);
+
+        if last_update > 0 && current_time > last_update {
+            funding_index + calculate_funding_index_delta(get_current_funding_rate(env, asset), current_time - last_update)
+        } else {
+            funding_index
+        }
+    }
+
+    /// Project the asset's borrow index to now without storing it.
+    fn current_borrow_index(env: &Env, asset: &Symbol) -> i128 {
+        let current_time = env.ledger().timestamp();
+        let last_update = get_last_borrow_time(env, asset);
+        let borrow_index = get_borrow_index(env, asset);
+
+        if last_update > 0 && current_time > last_update {
+            borrow_index + calculate_borrow_index_delta(get_current_borrow_rate(env, asset), current_time - last_update)
+        } else {
+            borrow_index
+        }
+    }
+
+    /// Settle funding and borrow fees accrued since the position's last checkpoint.
+    /// Owed = size × (index_now − index_entry), independent of how rates changed.
+    /// Borrow fees go to `accrued_borrow_fee` rather than `accumulated_funding`,
+    /// so funding received cannot net them away; they are paid to the vault as
+    /// fees when the position is reduced, closed or liquidated.
+    fn apply_funding_to_position(env: &Env, position: &mut Position) -> Result<(), NoetherError> {
+        let funding_index = Self::update_funding_index(env, &position.asset);
+        let borrow_index = Self::update_borrow_index(env, &position.asset);
+
+        position.accumulated_funding += calculate_accrued_funding(
+            position.size,
+            position.direction,
+            position.funding_index,
+            funding_index,
+        );
+        position.accrued_borrow_fee += calculate_accrued_borrow_fee(
+            position.size,
+            position.borrow_index,
+            borrow_index,
+        );
+        position.funding_index = funding_index;
+        position.borrow_index = borrow_index;
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Internal Order Functions
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Calculate the keeper execution fee for an order on `size` of an asset.
+    /// Fee = base_fee + variable_fee_bps of size, from the asset's keeper fee config.
+    fn calculate_execution_fee(env: &Env, asset: &Symbol, size: i128) -> i128 {
+        let fee_config = get_keeper_fee_config(env, asset);
+
+        let variable_fee = (size * fee_config.variable_fee_bps as i128) / 10_000;
+        fee_config.base_fee + variable_fee
+    }
+
+    /// Take the execution fee for a close order from the trader into escrow.
+    /// The fee is priced on the size the order would close (0 = the whole position).
+    fn escrow_close_order_fee(env: &Env, trader: &Address, position: &Position, close_size: i128) -> i128 {
+        let fee_size = if close_size > 0 { close_size } else { position.size };
+        let execution_fee = Self::calculate_execution_fee(env, &position.asset, fee_size);
+
+        if execution_fee > 0 {
+            let usdc_token = get_usdc_token(env);
+            let token_client = token::Client::new(env, &usdc_token);
+            token_client.transfer(trader, &env.current_contract_address(), &execution_fee);
+        }
+
+        execution_fee
+    }
+
+    /// Create a close order on a position the caller has already validated,
+    /// escrow its execution fee and link it to the position.
+    /// Stop-losses and trailing stops fire as price moves against the position,
+    /// take-profits as it moves in its favour.
+    fn attach_close_order(
+        env: &Env,
+        position: &Position,
+        order_type: OrderType,
+        trigger: CloseTrigger,
+        close_bps: u32,
+        slippage_tolerance_bps: u32,
+        expires_at: u64,
+    ) -> Order {
+        let (trigger_price, trailing_distance_bps, trailing_mark) = match trigger {
+            CloseTrigger::Price(price) => (price, 0, 0),
+            CloseTrigger::Trailing { distance_bps, mark } => (
+                calculate_trailing_stop_price(position.direction, mark, distance_bps),
+                distance_bps,
+                mark,
+            ),
+        };
+
+        // Long position: take-profit closes when price >= trigger, stops when price <= trigger
+        // Short position: the reverse
+        let trigger_condition = match (order_type == OrderType::TakeProfit, position.direction) {
+            (true, Direction::Long) | (false, Direction::Short) => TriggerCondition::Above,
+            (true, Direction::Short) | (false, Direction::Long) => TriggerCondition::Below,
+        };
 
-            // Return Ok(0) - no keeper reward for cancelled orders, but transaction commits
-            return Ok(0);
-        }
+        // Fix the size to close now, so laddered partial orders don't compound
+        let close_size = if close_bps < BASIS_POINTS { calculate_close_size(position.size, close_bps) } else { 0 };
 
-        // Calculate keeper fee
-        let keeper_fee = Self::calculate_keeper_order_fee(&env, &order);
+        // Escrow the keeper execution fee for the size this order would close,
+        // only once every check has passed
+        let execution_fee = Self::escrow_close_order_fee(env, &position.trader, position, close_size);
 
-        // Execute based on order type
-        let result = match order.order_type {
-            OrderType::LimitEntry => {
-                Self::execute_limit_entry(&env, &order, current_price, keeper_fee, &keeper)
-            }
-            OrderType::StopLoss | OrderType::TakeProfit => {
-                Self::execute_close_order(&env, &order, current_price, keeper_fee, &keeper)
-            }
+        let order = Order {
+            id: next_order_id(env),
+            trader: position.trader.clone(),
+            asset: position.asset.clone(),
+            order_type,
+            direction: position.direction,
+            collateral: 0, // No collateral locked for close orders
+            leverage: position.leverage,
+            trigger_price,
+            trigger_condition,
+            slippage_tolerance_bps,
+            close_bps,
+            close_size,
+            trailing_distance_bps,
+            trailing_mark,
+            position_id: position.id,
+            has_position: true,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            execution_fee,
+            status: OrderStatus::Pending,
         };
 
-        match result {
-            Ok(reward) => {
-                update_order_status(&env, order_id, OrderStatus::Executed);
+        // Store order and link to position
+        save_new_order(env, &order);
+        add_position_order(env, position.id, order.id);
 
-                extend_instance_ttl(&env);
+        extend_instance_ttl(env);
 
-                env.events().publish(
-                    (Symbol::new(&env, "order_executed"),),
-                    (order_id, order.trader, order.order_type, current_price, reward),
-                );
+        order
+    }
 
-                Ok(reward)
+    /// Cancel every pending close order attached to a position and clear its order list.
+    /// Any path that deletes a position must call this first so no orders are left
+    /// pending against a missing position. Partial closes (decrease, partial
+    /// liquidation, ADL, partial close orders) keep the orders attached: a close
+    /// order's size is capped at the remaining size when it fills, so they stay valid.
+    fn cancel_position_orders(env: &Env, position_id: u64, reason: Symbol) {
+        for order_id in get_position_order_ids(env, position_id).iter() {
+            if let Some(order) = get_order(env, order_id) {
+                if order.status == OrderStatus::Pending {
+                    Self::cancel_pending_order(env, &order, OrderStatus::Cancelled, reason.clone(), 0);
+                }
             }
-            Err(e) => Err(e),
         }
+        remove_position_orders(env, position_id);
     }
 
-    /// Check if an order should be executed at current price.
-    pub fn should_execute_order(env: Env, order_id: u64) -> Result<bool, NoetherError> {
-        let order = get_order(&env, order_id)
-            .ok_or(NoetherError::OrderNotFound)?;
-
-        if order.status != OrderStatus::Pending {
-            return Ok(false);
+    /// Cancel a pending order without executing it.
+    /// Refunds locked collateral for limit entries and the escrowed execution
+    /// fee, less any `fee_paid` already taken from it, and detaches close
+    /// orders from their position.
+    fn cancel_pending_order(env: &Env, order: &Order, status: OrderStatus, reason: Symbol, fee_paid: i128) {
+        let locked_collateral = if order.order_type == OrderType::LimitEntry { order.collateral } else { 0 };
+        let refund = locked_collateral + order.execution_fee - fee_paid;
+        if refund > 0 {
+            let usdc_token = get_usdc_token(env);
+            let token_client = token::Client::new(env, &usdc_token);
+            token_client.transfer(&env.current_contract_address(), &order.trader, &refund);
         }
 
-        let current_price = Self::get_oracle_price(&env, &order.asset)?;
-
-        let triggered = match order.trigger_condition {
-            TriggerCondition::Above => current_price >= order.trigger_price,
-            TriggerCondition::Below => current_price <= order.trigger_price,
-        };
-
-        Ok(triggered)
-    }
-
-    /// Get all orders for a trader.
-    pub fn get_orders(env: Env, trader: Address) -> Vec<Order> {
-        get_trader_orders(&env, &trader)
-    }
+        if order.has_position {
+            remove_position_order(env, order.position_id, order.id);
+        }
 
-    /// Get a specific order by ID.
-    pub fn get_order(env: Env, order_id: u64) -> Option<Order> {
-        get_order(&env, order_id)
-    }
+        update_order_status(env, order.id, status);
 
-    /// Get all pending order IDs (for keeper).
-    pub fn get_all_order_ids(env: Env) -> Vec<u64> {
-        get_all_order_ids(&env)
+        env.events().publish(
+            (Symbol::new(env, "order_cancelled"),),
+            (order.id, order.trader.clone(), reason),
+        );
     }
 
-    /// Get stop-loss order ID attached to a position.
-    pub fn get_position_sl(env: Env, position_id: u64) -> Option<u64> {
-        get_position_stop_loss(&env, position_id)
-    }
+    /// Execute a pending order whose trigger is met at `current_price`.
+    /// Shared by `execute_order` and `execute_orders_batch`.
+    fn execute_triggered_order(
+        env: &Env,
+        keeper: &Address,
+        order: &Order,
+        current_price: i128,
+    ) -> Result<i128, NoetherError> {
+        // Check slippage
+        let price_diff = if current_price > order.trigger_price {
+            current_price - order.trigger_price
+        } else {
+            order.trigger_price - current_price
+        };
+        let actual_slippage_bps = (price_diff * 10_000) / order.trigger_price;
 
-    /// Get take-profit order ID attached to a position.
-    pub fn get_position_tp(env: Env, position_id: u64) -> Option<u64> {
-        get_position_take_profit(&env, position_id)
-    }
+        if actual_slippage_bps > order.slippage_tolerance_bps as i128 {
+            // Slippage exceeded - cancel the order and commit the cancellation
+            // IMPORTANT: We return Ok(0) instead of Err() so the transaction commits
+            // and the order is properly removed from the pending list. Returning Err()
+            // would rollback all state changes, leaving the order stuck in pending.
+            Self::cancel_pending_order(
+                env,
+                order,
+                OrderStatus::CancelledSlippage,
+                Symbol::new(env, "slippage_exceeded"),
+                0,
+            );
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Internal Functions
-    // ═══════════════════════════════════════════════════════════════════════
+            extend_instance_ttl(env);
 
-    /// Fetch price from oracle adapter.
-    fn get_oracle_price(env: &Env, asset: &Symbol) -> Result<i128, NoetherError> {
-        let oracle_address = get_oracle_adapter(env);
+            // Return Ok(0) - no keeper reward for cancelled orders, but transaction commits
+            return Ok(0);
+        }
 
-        // Call oracle adapter using invoke_contract
-        let args: Vec<soroban_sdk::Val> = (asset.clone(),).into_val(env);
-        let (price, timestamp): (i128, u64) = env.invoke_contract(
-            &oracle_address,
-            &Symbol::new(env, "lastprice"),
-            args,
-        );
+        // A limit entry that no longer fits under the open interest cap would
+        // revert on every attempt; cancel it (refunding collateral) the same way
+        if order.order_type == OrderType::LimitEntry {
+            let config = Self::get_asset_config_or_default(env, &order.asset);
+            let size = calculate_position_size(order.collateral, order.leverage);
+            if Self::check_open_interest_cap(env, &order.asset, &config, order.direction, size).is_err() {
+                Self::cancel_pending_order(
+                    env,
+                    order,
+                    OrderStatus::Cancelled,
+                    Symbol::new(env, "open_interest_cap"),
+                    0,
+                );
 
-        // Check staleness
-        let config = get_config(env);
-        let current_time = env.ledger().timestamp();
+                extend_instance_ttl(env);
 
-        if current_time > timestamp && current_time - timestamp > config.max_price_staleness {
-            return Err(NoetherError::PriceStale);
+                return Ok(0);
+            }
         }
 
-        if price <= 0 {
-            return Err(NoetherError::InvalidPrice);
+        // Execute based on order type
+        match order.order_type {
+            OrderType::LimitEntry => Self::execute_limit_entry(env, order, current_price)?,
+            OrderType::StopLoss | OrderType::TakeProfit | OrderType::TrailingStop => {
+                Self::execute_close_order(env, order, current_price)?
+            }
         }
 
-        Ok(price)
-    }
+        // Credit the keeper the order's prepaid execution fee
+        let keeper_fee = order.execution_fee;
+        Self::record_keeper_jobs(env, keeper, KeeperJob::OrderExecution, 1, keeper_fee);
 
-    /// Check if Vault has enough liquidity for a potential payout.
-    fn check_vault_liquidity(env: &Env, vault: &Address, amount: i128) -> Result<(), NoetherError> {
-        // Call vault's reserve_for_position function
-        // This checks liquidity without moving funds
-        let args: Vec<soroban_sdk::Val> = (amount,).into_val(env);
-        let _: () = env.invoke_contract(
-            vault,
-            &Symbol::new(env, "reserve_for_position"),
-            args,
-        );
+        update_order_status(env, order.id, OrderStatus::Executed);
 
-        Ok(())
-    }
+        extend_instance_ttl(env);
 
-    /// Settle PnL with vault contract.
-    fn settle_with_vault(env: &Env, vault: &Address, pnl: i128) -> Result<(), NoetherError> {
-        // Call vault's settle_pnl function
-        // - If pnl > 0: Vault transfers profit to Market
-        // - If pnl < 0: Vault updates accounting (Market transfers loss separately)
-        let args: Vec<soroban_sdk::Val> = (pnl,).into_val(env);
-        let _: () = env.invoke_contract(
-            vault,
-            &Symbol::new(env, "settle_pnl"),
-            args,
+        env.events().publish(
+            (Symbol::new(env, "order_executed"),),
+            (order.id, order.trader.clone(), order.order_type, current_price, keeper_fee),
         );
 
-        Ok(())
+        Ok(keeper_fee)
     }
 
-    /// Apply pending funding to a position.
-    fn apply_funding_to_position(env: &Env, position: &mut Position) -> Result<(), NoetherError> {
-        let current_time = env.ledger().timestamp();
-        let hours_elapsed = (current_time - position.last_funding_time) / 3600;
-
-        if hours_elapsed == 0 {
-            return Ok(());
+    /// Read an asset's oracle price, reusing a price already read in this call.
+    fn get_cached_oracle_price(
+        env: &Env,
+        prices: &mut Map<Symbol, i128>,
+        asset: &Symbol,
+    ) -> Result<i128, NoetherError> {
+        if let Some(price) = prices.get(asset.clone()) {
+            return Ok(price);
         }
+        let price = Self::get_oracle_price(env, asset)?;
+        prices.set(asset.clone(), price);
+        Ok(price)
+    }
 
-        let funding_rate = get_current_funding_rate(env);
-        let funding_payment = calculate_funding_payment(
-            position.size,
-            funding_rate,
-            position.direction.clone(),
-            hours_elapsed,
-        );
-
-        position.accumulated_funding += funding_payment;
-        position.last_funding_time = current_time;
+    /// Batch result for an ID skipped as ineligible or whose execution failed.
+    fn skipped(id: u64, error: NoetherError) -> BatchResult {
+        BatchResult { id, success: false, reward: 0, error_code: error as u32 }
+    }
 
+    /// Ensure another close order can be attached to a position.
+    fn check_position_order_capacity(env: &Env, position_id: u64) -> Result<(), NoetherError> {
+        if get_position_order_ids(env, position_id).len() >= MAX_POSITION_ORDERS {
+            return Err(NoetherError::OrderAlreadyExists);
+        }
         Ok(())
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Internal Order Functions
-    // ═══════════════════════════════════════════════════════════════════════
-
-    /// Calculate keeper fee for order execution.
-    /// Fee = base_fee (0.50 USDC) + variable_fee (0.05% of position size)
-    fn calculate_keeper_order_fee(env: &Env, order: &Order) -> i128 {
-        let fee_config = KeeperFeeConfig::default();
-
-        let position_size = match order.order_type {
-            OrderType::LimitEntry => calculate_position_size(order.collateral, order.leverage),
-            OrderType::StopLoss | OrderType::TakeProfit => {
-                // For SL/TP, get size from the position
-                if let Some(pos) = get_position(env, order.position_id) {
-                    pos.size
-                } else {
-                    0
-                }
-            }
-        };
+    /// Reject expiry times that are already in the past (0 = never expires).
+    fn validate_order_expiry(env: &Env, expires_at: u64) -> Result<(), NoetherError> {
+        if expires_at != 0 && expires_at <= env.ledger().timestamp() {
+            return Err(NoetherError::InvalidParameter);
+        }
+        Ok(())
+    }
 
-        let variable_fee = (position_size * fee_config.variable_fee_bps as i128) / 10_000;
-        fee_config.base_fee + variable_fee
+    /// Check whether an order has passed its expiry time.
+    fn is_order_expired(env: &Env, order: &Order) -> bool {
+        order.expires_at != 0 && env.ledger().timestamp() >= order.expires_at
     }
 
     /// Execute a limit entry order - opens a new position.
@@ -1432,36 +3505,48 @@ impl MarketContract {
         env: &Env,
         order: &Order,
         current_price: i128,
-        keeper_fee: i128,
-        keeper: &Address,
-    ) -> Result<i128, NoetherError> {
-        let config = get_config(env);
+    ) -> Result<(), NoetherError> {
+        let config = Self::get_listed_asset_config(env, &order.asset)?;
 
         // Calculate position size
         let size = calculate_position_size(order.collateral, order.leverage);
+        Self::check_open_interest_cap(env, &order.asset, &config, order.direction, size)?;
+
+        // Calculate trading fee (the keeper is paid from the escrowed execution fee)
+        let trading_fee = calculate_trading_fee(size, config.trading_fee_bps);
+        let net_collateral = order.collateral - trading_fee;
+
+        if net_collateral <= 0 {
+            return Err(NoetherError::InsufficientCollateral);
+        }
 
-        // Check Vault has enough liquidity
+        // Reserve Vault liquidity for potential payout. This is the first state
+        // change, so a failed entry leaves nothing behind for batch callers.
         let vault_address = get_vault(env);
-        Self::check_vault_liquidity(env, &vault_address, size)?;
+        Self::reserve_vault_liquidity(env, &vault_address, size)?;
 
-        // Calculate liquidation price using current price as entry
-        let liquidation_price = calculate_liquidation_price(
+        // Apply skew-based price impact to the fill
+        let (entry_price, price_impact) = Self::get_execution_price(
+            env,
+            &order.asset,
+            &config,
             current_price,
+            order.direction,
+            size,
+            true,
+        );
+
+        // Calculate liquidation price using the fill price as entry
+        let liquidation_price = calculate_liquidation_price(
+            entry_price,
             order.leverage,
             order.direction.clone(),
             config.maintenance_margin_bps,
         );
 
-        // Calculate trading fee
-        let trading_fee = calculate_trading_fee(size, config.trading_fee_bps);
-
-        // Total fees = trading fee + keeper fee
-        let total_fees = trading_fee + keeper_fee;
-        let net_collateral = order.collateral - total_fees;
-
-        if net_collateral <= 0 {
-            return Err(NoetherError::InsufficientCollateral);
-        }
+        // Bring the funding and borrow indexes up to date; the position accrues from here
+        let funding_index = Self::update_funding_index(env, &order.asset);
+        let borrow_index = Self::update_borrow_index(env, &order.asset);
 
         // Generate position ID
         let position_id = next_position_id(env);
@@ -1473,57 +3558,170 @@ impl MarketContract {
             asset: order.asset.clone(),
             collateral: net_collateral,
             size,
-            entry_price: current_price,
+            entry_price,
             direction: order.direction.clone(),
             leverage: order.leverage,
             liquidation_price,
             timestamp: env.ledger().timestamp(),
-            last_funding_time: env.ledger().timestamp(),
+            funding_index,
+            borrow_index,
             accumulated_funding: 0,
+            accrued_borrow_fee: 0,
         };
 
         // Store position
-        save_position(env, &position);
+        save_new_position(env, &position);
 
         // Update market stats
-        match order.direction {
-            Direction::Long => {
-                let total = get_total_long_size(env);
-                set_total_long_size(env, total + size);
-            }
-            Direction::Short => {
-                let total = get_total_short_size(env);
-                set_total_short_size(env, total + size);
-            }
+        Self::update_open_interest(
+            env,
+            &order.asset,
+            order.direction,
+            size,
+            calculate_position_quantity(size, entry_price),
+        );
+
+        // Transfer trading fee to vault, with the insurance fund's share
+        let usdc_token = get_usdc_token(env);
+        let token_client = token::Client::new(env, &usdc_token);
+        Self::pay_trading_fee(env, &vault_address, &token_client, trading_fee, config.insurance_fee_bps)?;
+
+        // Emit position opened event
+        env.events().publish(
+            (Symbol::new(env, "position_opened"),),
+            (position.id, order.trader.clone(), order.asset.clone(), size, order.direction.clone(), order.leverage, entry_price, price_impact),
+        );
+
+        Ok(())
+    }
+
+    /// Close `close_size` of a position and keep the remainder open.
+    /// Shared by `decrease_position` and partial close orders; funding must
+    /// already be applied.
+    ///
+    /// # Returns
+    /// Realized PnL of the closed portion
+    fn reduce_position(
+        env: &Env,
+        mut position: Position,
+        close_size: i128,
+        oracle_price: i128,
+        acceptable_price: i128,
+    ) -> Result<i128, NoetherError> {
+        // Split the position
+        let (close_collateral, close_size, remaining_collateral, remaining_size) =
+            calculate_partial_close_size(&position, close_size);
+
+        // Get execution price and check it against the trader's limit
+        let config = Self::get_asset_config_or_default(env, &position.asset);
+        let (current_price, price_impact) = Self::get_execution_price(
+            env,
+            &position.asset,
+            &config,
+            oracle_price,
+            position.direction,
+            close_size,
+            false,
+        );
+        if !is_exit_price_acceptable(position.direction, current_price, acceptable_price) {
+            return Err(NoetherError::SlippageExceeded);
         }
 
-        // Transfer trading fee to vault
+        // Realize PnL and funding on the closed portion only
+        let mut closed_part = position.clone();
+        closed_part.size = close_size;
+        let pnl = calculate_pnl(&closed_part, current_price)?;
+        let closed_funding = position.accumulated_funding * close_size / position.size;
+        let closed_borrow_fee = position.accrued_borrow_fee * close_size / position.size;
+
+        // Calculate amount to return to trader
+        let to_trader = close_collateral + pnl - closed_funding - closed_borrow_fee;
+
+        // Settle with vault (the only step that can fail), then release reserved
+        // liquidity for the closed size
+        let vault_address = get_vault(env);
+        Self::settle_with_vault(env, &vault_address, pnl)?;
+        Self::release_vault_liquidity(env, &vault_address, close_size)?;
+
+        // Get token client for transfers
         let usdc_token = get_usdc_token(env);
         let token_client = token::Client::new(env, &usdc_token);
-        token_client.transfer(&env.current_contract_address(), &vault_address, &trading_fee);
 
-        // Pay keeper fee
-        if keeper_fee > 0 {
-            token_client.transfer(&env.current_contract_address(), keeper, &keeper_fee);
+        // If trader lost, transfer the loss amount to Vault
+        if pnl < 0 {
+            let loss = -pnl;
+            token_client.transfer(&env.current_contract_address(), &vault_address, &loss);
+        }
+
+        // Transfer realized funding to vault (if any)
+        if closed_funding > 0 {
+            token_client.transfer(&env.current_contract_address(), &vault_address, &closed_funding);
         }
 
-        // Emit position opened event
+        // Pay the closed share of accrued borrow fees to the vault
+        Self::pay_fees_to_vault(env, &vault_address, &token_client, closed_borrow_fee, 0)?;
+
+        // Transfer to trader (if positive)
+        if to_trader > 0 {
+            token_client.transfer(&env.current_contract_address(), &position.trader, &to_trader);
+        }
+
+        // Update market stats
+        let closed_quantity = calculate_position_quantity(position.size, position.entry_price)
+            - calculate_position_quantity(remaining_size, position.entry_price);
+        Self::update_open_interest(
+            env,
+            &position.asset,
+            position.direction,
+            -close_size,
+            -closed_quantity,
+        );
+
+        // Keep the remainder open
+        position.collateral = remaining_collateral;
+        position.size = remaining_size;
+        position.accumulated_funding -= closed_funding;
+        position.accrued_borrow_fee -= closed_borrow_fee;
+
+        position.liquidation_price = calculate_liquidation_price_from_collateral(
+            position.entry_price,
+            position.size,
+            position.collateral,
+            position.direction,
+            config.maintenance_margin_bps,
+        );
+
+        save_position(env, &position);
+
         env.events().publish(
-            (Symbol::new(env, "position_opened"),),
-            (position.id, order.trader.clone(), order.asset.clone(), size, order.direction.clone(), order.leverage, current_price),
+            (Symbol::new(env, "position_decreased"),),
+            (
+                position.id,
+                position.trader,
+                position.asset,
+                position.direction,
+                close_size,
+                position.size,
+                position.entry_price,
+                current_price,  // exit_price
+                pnl,
+                closed_funding,
+                price_impact,
+            ),
         );
 
-        Ok(keeper_fee)
+        Ok(pnl)
     }
 
-    /// Execute a stop-loss or take-profit order - closes the position.
+    /// Execute a stop-loss, take-profit or trailing-stop order.
+    /// Closes `order.close_size` of the position, capped at its remaining size;
+    /// a close that would leave less than the minimum collateral behind closes
+    /// the whole position.
     fn execute_close_order(
         env: &Env,
         order: &Order,
         current_price: i128,
-        keeper_fee: i128,
-        keeper: &Address,
-    ) -> Result<i128, NoetherError> {
+    ) -> Result<(), NoetherError> {
         // Get position
         let mut position = get_position(env, order.position_id)
             .ok_or(NoetherError::PositionNotFound)?;
@@ -1531,15 +3729,39 @@ impl MarketContract {
         // Apply pending funding
         Self::apply_funding_to_position(env, &mut position)?;
 
+        // Reduce-only partial close: keep the rest of the position and its other orders
+        let config = Self::get_asset_config_or_default(env, &position.asset);
+        if order.close_size > 0 && order.close_size < position.size {
+            let (_, _, remaining_collateral, _) = calculate_partial_close_size(&position, order.close_size);
+            if remaining_collateral >= config.min_collateral {
+                Self::reduce_position(env, position, order.close_size, current_price, 0)?;
+                remove_position_order(env, order.position_id, order.id);
+                return Ok(());
+            }
+        }
+
+        // Apply skew-based price impact to the fill
+        let (exit_price, price_impact) = Self::get_execution_price(
+            env,
+            &position.asset,
+            &config,
+            current_price,
+            position.direction,
+            position.size,
+            false,
+        );
+
         // Calculate PnL
-        let pnl = calculate_pnl(&position, current_price)?;
+        let pnl = calculate_pnl(&position, exit_price)?;
 
         // Calculate amount to return to trader
-        let to_trader = position.collateral + pnl - position.accumulated_funding - keeper_fee;
+        let to_trader = position.collateral + pnl - position.accumulated_funding - position.accrued_borrow_fee;
 
-        // Settle with vault
+        // Settle with vault (the only step that can fail), then release the
+        // position's reserved liquidity
         let vault_address = get_vault(env);
         Self::settle_with_vault(env, &vault_address, pnl)?;
+        Self::release_vault_liquidity(env, &vault_address, position.size)?;
 
         // Get token client for transfers
         let usdc_token = get_usdc_token(env);
@@ -1560,10 +3782,8 @@ impl MarketContract {
             );
         }
 
-        // Pay keeper fee
-        if keeper_fee > 0 {
-            token_client.transfer(&env.current_contract_address(), keeper, &keeper_fee);
-        }
+        // Pay accrued borrow fees to the vault
+        Self::pay_fees_to_vault(env, &vault_address, &token_client, position.accrued_borrow_fee, 0)?;
 
         // Transfer to trader (if positive)
         if to_trader > 0 {
@@ -1571,20 +3791,17 @@ impl MarketContract {
         }
 
         // Update market stats
-        match position.direction {
-            Direction::Long => {
-                let total = get_total_long_size(env);
-                set_total_long_size(env, total - position.size);
-            }
-            Direction::Short => {
-                let total = get_total_short_size(env);
-                set_total_short_size(env, total - position.size);
-            }
-        }
+        Self::update_open_interest(
+            env,
+            &position.asset,
+            position.direction,
+            -position.size,
+            -calculate_position_quantity(position.size, position.entry_price),
+        );
 
-        // Remove SL/TP links
-        remove_position_stop_loss(env, position.id);
-        remove_position_take_profit(env, position.id);
+        // Detach the executing order, then cancel the position's other close orders
+        remove_position_order(env, position.id, order.id);
+        Self::cancel_position_orders(env, position.id, Symbol::new(env, "position_closed"));
 
         // Delete position
         delete_position(env, position.id, &position.trader);
@@ -1599,13 +3816,14 @@ impl MarketContract {
                 position.direction,
                 position.size,
                 position.entry_price,
-                current_price,
+                exit_price,
                 pnl,
                 position.accumulated_funding,
+                price_impact,
             ),
         );
 
-        Ok(keeper_fee)
+        Ok(())
     }
 }
 
@@ -1614,6 +3832,4 @@ impl MarketContract {
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[cfg(test)]
-mod tests {
-    // Integration tests in separate file due to complexity
-}
+mod test;
@@ -21,7 +21,7 @@ pub fn calculate_current_margin(position: &Position, current_price: i128) -> i12
         }
     };
 
-    position.collateral + pnl - position.accumulated_funding
+    position.collateral + pnl - position.accumulated_funding - position.accrued_borrow_fee
 }
 
 /// Calculate margin ratio for liquidation check.
@@ -35,12 +35,11 @@ pub fn calculate_margin_ratio_bps(position: &Position, current_price: i128) -> i
 }
 
 /// Check if a position should be liquidated.
-/// Simpler check: just compare price to liquidation price.
-pub fn check_liquidation(position: &Position, current_price: i128) -> bool {
-    match position.direction {
-        Direction::Long => current_price <= position.liquidation_price,
-        Direction::Short => current_price >= position.liquidation_price,
-    }
+/// Uses the margin left after funding and borrow fees owed, so fees alone can
+/// bring a position to the maintenance margin before price reaches its
+/// liquidation price.
+pub fn should_liquidate(position: &Position, current_price: i128, maintenance_margin_bps: u32) -> bool {
+    calculate_margin_ratio_bps(position, current_price) <= maintenance_margin_bps as i128
 }
 
 /// Calculate liquidation proceeds distribution.
@@ -59,7 +58,7 @@ pub fn calculate_liquidation_distribution(
         }
     };
 
-    let remaining = position.collateral + pnl - position.accumulated_funding;
+    let remaining = position.collateral + pnl - position.accumulated_funding - position.accrued_borrow_fee;
 
     if remaining <= 0 {
         // Bad debt scenario - position lost more than collateral
@@ -75,6 +74,156 @@ pub fn calculate_liquidation_distribution(
     (to_vault, keeper_reward, 0)
 }
 
+/// Estimate a full liquidation at `current_price`.
+/// Returns (keeper_reward, insurance_fee, bad_debt). The liquidation fee is capped
+/// at 10% of collateral when it would exceed the collateral, and split between the
+/// insurance fund and the keeper.
+pub fn calculate_liquidation_payout(
+    position: &Position,
+    current_price: i128,
+    liquidation_fee_bps: u32,
+    insurance_fee_bps: u32,
+) -> (i128, i128, i128) {
+    // Liquidation fee comes from remaining equity; losses beyond collateral are bad debt
+    let (_, liquidation_fee, bad_debt) =
+        calculate_liquidation_distribution(position, current_price, liquidation_fee_bps);
+
+    // Ensure liquidation fee doesn't exceed position collateral
+    let liquidation_fee = if liquidation_fee > position.collateral {
+        position.collateral / 10 // Cap at 10% of collateral as safety
+    } else {
+        liquidation_fee
+    };
+
+    let insurance_fee = liquidation_fee * (insurance_fee_bps as i128) / (BASIS_POINTS as i128);
+    (liquidation_fee - insurance_fee, insurance_fee, bad_debt)
+}
+
+/// Outcome of partially liquidating a position.
+pub struct PartialLiquidation {
+    /// Size closed
+    pub close_size: i128,
+    /// PnL realized on the closed size
+    pub realized_pnl: i128,
+    /// Accumulated funding realized on the closed size
+    pub realized_funding: i128,
+    /// Borrow fees realized on the closed size
+    pub realized_borrow_fee: i128,
+    /// Liquidation fee on the closed share of equity
+    pub liquidation_fee: i128,
+    /// Collateral left on the remaining position
+    pub remaining_collateral: i128,
+}
+
+/// Calculate the fraction of a position to close so the remainder is back at
+/// the target margin ratio after paying the liquidation fee.
+///
+/// # Formula
+/// Closing fraction f realizes PnL but keeps equity E on the position, minus a
+/// fee r × E × f. Solving (E - r·E·f) / (S·(1 - f)) = target for f:
+/// ```
+/// f = (target·S - E) / (target·S - r·E)
+/// ```
+///
+/// # Returns
+/// Fraction to close in basis points (rounded up); BASIS_POINTS means full liquidation
+pub fn calculate_partial_liquidation_bps(
+    equity: i128,
+    size: i128,
+    target_margin_bps: u32,
+    liquidation_fee_bps: u32,
+) -> u32 {
+    if equity <= 0 || size <= 0 {
+        return BASIS_POINTS;
+    }
+
+    let bps = BASIS_POINTS as i128;
+    let target_margin = size * (target_margin_bps as i128);
+    let scaled_equity = equity * bps;
+    if scaled_equity >= target_margin {
+        return 0;
+    }
+
+    let numerator = (target_margin - scaled_equity) * bps;
+    let denominator = target_margin - equity * (liquidation_fee_bps as i128);
+    if denominator <= 0 {
+        return BASIS_POINTS;
+    }
+
+    let close_bps = (numerator + denominator - 1) / denominator;
+    close_bps.clamp(1, bps) as u32
+}
+
+/// Plan a partial liquidation restoring `target_margin_bps`.
+/// Returns None when the position must be liquidated in full.
+pub fn calculate_partial_liquidation(
+    position: &Position,
+    current_price: i128,
+    target_margin_bps: u32,
+    liquidation_fee_bps: u32,
+) -> Option<PartialLiquidation> {
+    let equity = calculate_current_margin(position, current_price);
+    let close_bps = calculate_partial_liquidation_bps(
+        equity,
+        position.size,
+        target_margin_bps,
+        liquidation_fee_bps,
+    );
+    if close_bps == 0 || close_bps >= BASIS_POINTS {
+        return None;
+    }
+
+    let bps = BASIS_POINTS as i128;
+    let close_size = position.size * (close_bps as i128) / bps;
+    let pnl = match position.direction {
+        Direction::Long => {
+            close_size * (current_price - position.entry_price) / position.entry_price
+        }
+        Direction::Short => {
+            close_size * (position.entry_price - current_price) / position.entry_price
+        }
+    };
+    let realized_funding = position.accumulated_funding * (close_bps as i128) / bps;
+    let realized_borrow_fee = position.accrued_borrow_fee * (close_bps as i128) / bps;
+    let closed_equity = equity * (close_bps as i128) / bps;
+    let liquidation_fee = closed_equity * (liquidation_fee_bps as i128) / bps;
+
+    Some(PartialLiquidation {
+        close_size,
+        realized_pnl: pnl,
+        realized_funding,
+        realized_borrow_fee,
+        liquidation_fee,
+        remaining_collateral: position.collateral + pnl - realized_funding - realized_borrow_fee - liquidation_fee,
+    })
+}
+
+/// Calculate the liquidation price from exact collateral rather than integer leverage.
+///
+/// # Formula
+/// For Long:  liq_price = entry_price × (1 - collateral/size + maintenance_margin)
+/// For Short: liq_price = entry_price × (1 + collateral/size - maintenance_margin)
+pub fn calculate_liquidation_price_from_collateral(
+    entry_price: i128,
+    size: i128,
+    collateral: i128,
+    direction: Direction,
+    maintenance_margin_bps: u32,
+) -> i128 {
+    if size <= 0 {
+        return 0;
+    }
+
+    let bps = BASIS_POINTS as i128;
+    let margin_bps = collateral * bps / size - (maintenance_margin_bps as i128);
+    let adjustment = entry_price * margin_bps / bps;
+
+    match direction {
+        Direction::Long => (entry_price - adjustment).max(0),
+        Direction::Short => entry_price + adjustment,
+    }
+}
+
 /// Calculate safe price distance from liquidation.
 /// Returns the price change needed to trigger liquidation.
 pub fn calculate_distance_to_liquidation(position: &Position, current_price: i128) -> i128 {
@@ -99,7 +248,8 @@ pub fn calculate_distance_to_liquidation_pct(position: &Position, current_price:
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{Env, Address, Symbol};
+    use noether_common::PRECISION;
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
     fn create_long_position(env: &Env) -> Position {
         Position {
@@ -113,24 +263,38 @@ mod tests {
             leverage: 10,
             liquidation_price: PRECISION * 91 / 100, // $0.91
             timestamp: 1000000,
-            last_funding_time: 1000000,
+            funding_index: 0,
+            borrow_index: 0,
             accumulated_funding: 0,
+            accrued_borrow_fee: 0,
         }
     }
 
     #[test]
-    fn test_check_liquidation_long() {
+    fn test_should_liquidate_long() {
         let env = Env::default();
         let position = create_long_position(&env);
 
         // Price above liquidation - should not liquidate
-        assert!(!check_liquidation(&position, PRECISION * 95 / 100));
+        assert!(!should_liquidate(&position, PRECISION * 95 / 100, 100));
 
         // Price at liquidation - should liquidate
-        assert!(check_liquidation(&position, PRECISION * 91 / 100));
+        assert!(should_liquidate(&position, PRECISION * 91 / 100, 100));
 
         // Price below liquidation - should liquidate
-        assert!(check_liquidation(&position, PRECISION * 85 / 100));
+        assert!(should_liquidate(&position, PRECISION * 85 / 100, 100));
+    }
+
+    #[test]
+    fn test_should_liquidate_counts_fees_owed() {
+        let env = Env::default();
+        let mut position = create_long_position(&env);
+
+        // 5% above the liquidation price, but fees owed eat the margin
+        assert!(!should_liquidate(&position, PRECISION * 96 / 100, 100));
+        position.accumulated_funding = 40 * PRECISION;
+        position.accrued_borrow_fee = 15 * PRECISION;
+        assert!(should_liquidate(&position, PRECISION * 96 / 100, 100));
     }
 
     #[test]
@@ -192,4 +356,61 @@ mod tests {
         assert_eq!(to_keeper, 0);
         assert!(bad_debt > 0);
     }
+
+    #[test]
+    fn test_liquidation_payout() {
+        let env = Env::default();
+        let position = create_long_position(&env);
+
+        // Price $0.95: 50 USDC equity, 5% fee = 2.5 USDC, 20% of it to insurance
+        let (keeper_reward, insurance_fee, bad_debt) =
+            calculate_liquidation_payout(&position, PRECISION * 95 / 100, 500, 2000);
+        assert_eq!(insurance_fee, PRECISION / 2);
+        assert_eq!(keeper_reward, 2 * PRECISION);
+        assert_eq!(bad_debt, 0);
+
+        // Price $0.85: equity is -50 USDC, nothing to pay out
+        let (keeper_reward, insurance_fee, bad_debt) =
+            calculate_liquidation_payout(&position, PRECISION * 85 / 100, 500, 2000);
+        assert_eq!(keeper_reward + insurance_fee, 0);
+        assert_eq!(bad_debt, 50 * PRECISION);
+    }
+
+    #[test]
+    fn test_partial_liquidation_restores_target_margin() {
+        let env = Env::default();
+        let mut position = create_long_position(&env);
+
+        // 9.2% loss leaves $8 equity on $1000 (0.8% margin)
+        let price = PRECISION * 908 / 1000;
+        let plan = calculate_partial_liquidation(&position, price, 300, 500).unwrap();
+        assert!(plan.close_size > 0 && plan.close_size < position.size);
+
+        // Apply the plan; the remainder sits at (or just above) 3% margin
+        position.size -= plan.close_size;
+        position.collateral = plan.remaining_collateral;
+        let ratio = calculate_margin_ratio_bps(&position, price);
+        assert!((300..=301).contains(&ratio));
+    }
+
+    #[test]
+    fn test_partial_liquidation_bps_edge_cases() {
+        // Already above target - nothing to close
+        assert_eq!(calculate_partial_liquidation_bps(50 * PRECISION, 1000 * PRECISION, 300, 500), 0);
+        // No equity left - full liquidation
+        assert_eq!(calculate_partial_liquidation_bps(0, 1000 * PRECISION, 300, 500), BASIS_POINTS);
+    }
+
+    #[test]
+    fn test_liquidation_price_from_collateral() {
+        // 10x long with 1% maintenance matches the leverage-based formula ($0.91)
+        let liq = calculate_liquidation_price_from_collateral(
+            PRECISION,
+            1000 * PRECISION,
+            100 * PRECISION,
+            Direction::Long,
+            100,
+        );
+        assert_eq!(liq, PRECISION * 91 / 100);
+    }
 }
@@ -2,19 +2,26 @@
 //!
 //! Position-related utilities and helpers.
 
-use soroban_sdk::{Address, Env};
-use noether_common::Direction;
-use crate::storage::{get_position, get_all_position_ids};
+use soroban_sdk::{Address, Env, Symbol};
+use noether_common::{Direction, NoetherError, calculate_aggregate_pnl};
+use crate::storage::{
+    IndexKey, get_open_interest_assets, get_total_long_quantity, get_total_long_size,
+    get_total_short_quantity, get_total_short_size, get_trader_positions, index_len,
+};
 
 /// Check if an address has any open positions.
 pub fn has_open_positions(env: &Env, trader: &Address) -> bool {
-    let positions = crate::storage::get_trader_positions(env, trader);
-    !positions.is_empty()
+    index_len(env, &IndexKey::TraderPositions(trader.clone())) > 0
 }
 
-/// Get total position value for a trader.
-pub fn get_trader_total_value(env: &Env, trader: &Address) -> i128 {
-    let positions = crate::storage::get_trader_positions(env, trader);
+/// Get total position value over a page of a trader's positions.
+pub fn get_trader_total_value(
+    env: &Env,
+    trader: &Address,
+    start_after: u64,
+    limit: u32,
+) -> Result<i128, NoetherError> {
+    let positions = get_trader_positions(env, trader, start_after, limit)?;
     let mut total = 0i128;
 
     for i in 0..positions.len() {
@@ -22,12 +29,17 @@ pub fn get_trader_total_value(env: &Env, trader: &Address) -> i128 {
         total += pos.size;
     }
 
-    total
+    Ok(total)
 }
 
-/// Get total collateral for a trader.
-pub fn get_trader_total_collateral(env: &Env, trader: &Address) -> i128 {
-    let positions = crate::storage::get_trader_positions(env, trader);
+/// Get total collateral over a page of a trader's positions.
+pub fn get_trader_total_collateral(
+    env: &Env,
+    trader: &Address,
+    start_after: u64,
+    limit: u32,
+) -> Result<i128, NoetherError> {
+    let positions = get_trader_positions(env, trader, start_after, limit)?;
     let mut total = 0i128;
 
     for i in 0..positions.len() {
@@ -35,36 +47,47 @@ pub fn get_trader_total_collateral(env: &Env, trader: &Address) -> i128 {
         total += pos.collateral;
     }
 
-    total
+    Ok(total)
+}
+
+/// Calculate unrealized PnL of all positions in an asset from its aggregates.
+pub fn calculate_asset_unrealized_pnl(env: &Env, asset: &Symbol, current_price: i128) -> i128 {
+    calculate_aggregate_pnl(
+        Direction::Long,
+        get_total_long_size(env, asset),
+        get_total_long_quantity(env, asset),
+        current_price,
+    ) + calculate_aggregate_pnl(
+        Direction::Short,
+        get_total_short_size(env, asset),
+        get_total_short_quantity(env, asset),
+        current_price,
+    )
 }
 
 /// Calculate total unrealized PnL for all positions.
 /// This is used by the vault to track its liabilities.
+///
+/// Runs in time proportional to the number of assets, not positions.
+/// Assets without open interest are skipped and need no price.
 pub fn calculate_total_unrealized_pnl(
     env: &Env,
-    get_price: impl Fn(&Env, &soroban_sdk::Symbol) -> Option<i128>,
-) -> i128 {
-    let position_ids = get_all_position_ids(env);
+    get_price: impl Fn(&Env, &Symbol) -> Result<i128, NoetherError>,
+) -> Result<i128, NoetherError> {
+    let assets = get_open_interest_assets(env);
     let mut total_pnl = 0i128;
 
-    for i in 0..position_ids.len() {
-        let id = position_ids.get(i).unwrap();
-        if let Some(position) = get_position(env, id) {
-            if let Some(current_price) = get_price(env, &position.asset) {
-                let pnl = match position.direction {
-                    Direction::Long => {
-                        position.size * (current_price - position.entry_price) / position.entry_price
-                    }
-                    Direction::Short => {
-                        position.size * (position.entry_price - current_price) / position.entry_price
-                    }
-                };
-                total_pnl += pnl;
-            }
+    for i in 0..assets.len() {
+        let asset = assets.get(i).unwrap();
+        if get_total_long_size(env, &asset) == 0 && get_total_short_size(env, &asset) == 0 {
+            continue;
         }
+
+        let current_price = get_price(env, &asset)?;
+        total_pnl += calculate_asset_unrealized_pnl(env, &asset, current_price);
     }
 
-    total_pnl
+    Ok(total_pnl)
 }
 
 /// Validate position parameters.
@@ -94,6 +117,7 @@ pub fn validate_position_params(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use noether_common::PRECISION;
 
     #[test]
     fn test_validate_params_valid() {
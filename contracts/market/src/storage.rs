@@ -2,8 +2,10 @@
 //!
 //! Storage keys and helpers for the Market contract.
 
-use soroban_sdk::{contracttype, Address, Env, Vec};
-use noether_common::{NoetherError, Position, MarketConfig, Order, OrderStatus};
+use soroban_sdk::{contracttype, Address, Env, Symbol, Vec};
+use noether_common::{
+    NoetherError, Position, MarketConfig, KeeperFeeConfig, KeeperInfo, Order, OrderStatus, MAX_PAGE_SIZE,
+};
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Storage Keys
@@ -20,42 +22,113 @@ pub enum DataKey {
     Vault,
     /// USDC token contract address
     UsdcToken,
-    /// Market configuration
+    /// Market configuration (defaults for assets without their own)
     Config,
+    /// Listed asset symbols
+    Assets,
+    /// Market configuration for an asset
+    AssetConfig(Symbol),
+    /// Keeper execution fee parameters for an asset
+    KeeperFeeConfig(Symbol),
     /// Position counter (for ID generation)
     PositionCounter,
-    /// Total long position size
-    TotalLongSize,
-    /// Total short position size
-    TotalShortSize,
-    /// Last funding time
-    LastFundingTime,
-    /// Current funding rate
-    CurrentFundingRate,
+    /// Total long position size for an asset
+    TotalLongSize(Symbol),
+    /// Total short position size for an asset
+    TotalShortSize(Symbol),
+    /// Sum of size / entry_price over long positions for an asset
+    TotalLongQuantity(Symbol),
+    /// Sum of size / entry_price over short positions for an asset
+    TotalShortQuantity(Symbol),
+    /// Assets that have carried open interest (for pool PnL aggregation)
+    OpenInterestAssets,
+    /// Last time the funding index was updated for an asset
+    LastFundingTime(Symbol),
+    /// Current funding rate for an asset
+    CurrentFundingRate(Symbol),
+    /// Cumulative funding index for an asset
+    FundingIndex(Symbol),
+    /// Last time the borrow index was updated for an asset
+    LastBorrowTime(Symbol),
+    /// Current hourly borrow rate for an asset
+    CurrentBorrowRate(Symbol),
+    /// Cumulative borrow fee index for an asset
+    BorrowIndex(Symbol),
     /// Whether initialized
     Initialized,
     /// Whether paused
     Paused,
     /// Position by ID
     Position(u64),
-    /// Position IDs for a trader
-    TraderPositions(Address),
-    /// Global position index (all position IDs)
-    AllPositions,
     /// Order by ID
     Order(u64),
     /// Order counter (for ID generation)
     OrderCounter,
-    /// Order IDs for a trader
-    TraderOrders(Address),
-    /// Global order index (all pending order IDs)
+    /// Length and shard list ends of an ID index
+    IndexMeta(IndexKey),
+    /// IDs of an index within one shard's ID range, linked to the neighbouring non-empty shards
+    IndexShard(IndexKey, u64),
+    /// Close order IDs (SL/TP/trailing stop) attached to a position
+    PositionOrders(u64),
+    /// Whether only registered keepers may run keeper jobs
+    KeeperAllowlistEnabled,
+    /// Registry entry for a keeper
+    Keeper(Address),
+    /// Addresses of keepers registered on the allowlist, in registration order
+    Keepers,
+}
+
+/// ID indexes over open positions and pending orders.
+/// Each index is split into shards covering `INDEX_SHARD_SIZE` consecutive IDs.
+/// Non-empty shards form a linked list, so updates touch one shard (plus its
+/// neighbours when it is created or emptied) and pages read only the shards
+/// they return.
+#[contracttype]
+#[derive(Clone)]
+pub enum IndexKey {
+    /// All open positions
+    AllPositions,
+    /// Open positions in an asset
+    AssetPositions(Symbol),
+    /// Open positions of a trader
+    TraderPositions(Address),
+    /// All pending orders
     AllOrders,
-    /// Stop-loss order ID attached to a position
-    PositionStopLoss(u64),
-    /// Take-profit order ID attached to a position
-    PositionTakeProfit(u64),
+    /// Pending orders in an asset
+    AssetOrders(Symbol),
+    /// Pending orders of a trader
+    TraderOrders(Address),
 }
 
+/// Bookkeeping for one ID index
+#[contracttype]
+#[derive(Clone, Default)]
+pub struct IndexMeta {
+    /// Number of IDs in the index
+    pub len: u64,
+    /// Number of non-empty shards
+    pub shard_count: u64,
+    /// Lowest non-empty shard
+    pub head: Option<u64>,
+    /// Highest non-empty shard
+    pub tail: Option<u64>,
+}
+
+/// One non-empty shard of an ID index
+#[contracttype]
+#[derive(Clone)]
+pub struct IndexShardEntry {
+    /// IDs in the shard's range, ascending
+    pub ids: Vec<u64>,
+    /// Next lower non-empty shard
+    pub prev: Option<u64>,
+    /// Next higher non-empty shard
+    pub next: Option<u64>,
+}
+
+/// Number of consecutive IDs covered by one index shard
+const INDEX_SHARD_SIZE: u64 = 128;
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Instance Storage
 // ═══════════════════════════════════════════════════════════════════════════
@@ -108,6 +181,14 @@ pub fn set_usdc_token(env: &Env, token: &Address) {
     env.storage().instance().set(&DataKey::UsdcToken, token);
 }
 
+pub fn is_keeper_allowlist_enabled(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::KeeperAllowlistEnabled).unwrap_or(false)
+}
+
+pub fn set_keeper_allowlist_enabled(env: &Env, value: bool) {
+    env.storage().instance().set(&DataKey::KeeperAllowlistEnabled, &value);
+}
+
 pub fn get_config(env: &Env) -> MarketConfig {
     env.storage().instance().get(&DataKey::Config).unwrap_or_default()
 }
@@ -116,6 +197,49 @@ pub fn set_config(env: &Env, config: &MarketConfig) {
     env.storage().instance().set(&DataKey::Config, config);
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// Persistent Storage - Asset Registry
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub fn get_listed_assets(env: &Env) -> Vec<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Assets)
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_listed_assets(env: &Env, assets: &Vec<Symbol>) {
+    env.storage().persistent().set(&DataKey::Assets, assets);
+    extend_persistent_ttl(env, &DataKey::Assets);
+}
+
+pub fn is_asset_listed(env: &Env, asset: &Symbol) -> bool {
+    get_listed_assets(env).contains(asset)
+}
+
+pub fn get_asset_config(env: &Env, asset: &Symbol) -> Option<MarketConfig> {
+    env.storage().persistent().get(&DataKey::AssetConfig(asset.clone()))
+}
+
+pub fn set_asset_config(env: &Env, asset: &Symbol, config: &MarketConfig) {
+    let key = DataKey::AssetConfig(asset.clone());
+    env.storage().persistent().set(&key, config);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_keeper_fee_config(env: &Env, asset: &Symbol) -> KeeperFeeConfig {
+    env.storage()
+        .persistent()
+        .get(&DataKey::KeeperFeeConfig(asset.clone()))
+        .unwrap_or_default()
+}
+
+pub fn set_keeper_fee_config(env: &Env, asset: &Symbol, config: &KeeperFeeConfig) {
+    let key = DataKey::KeeperFeeConfig(asset.clone());
+    env.storage().persistent().set(&key, config);
+    extend_persistent_ttl(env, &key);
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Persistent Storage - Market State
 // ═══════════════════════════════════════════════════════════════════════════
@@ -136,40 +260,121 @@ pub fn next_position_id(env: &Env) -> u64 {
     next_id
 }
 
-pub fn get_total_long_size(env: &Env) -> i128 {
-    env.storage().persistent().get(&DataKey::TotalLongSize).unwrap_or(0)
+pub fn get_total_long_size(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::TotalLongSize(asset.clone())).unwrap_or(0)
 }
 
-pub fn set_total_long_size(env: &Env, size: i128) {
-    env.storage().persistent().set(&DataKey::TotalLongSize, &size);
-    extend_persistent_ttl(env, &DataKey::TotalLongSize);
+pub fn set_total_long_size(env: &Env, asset: &Symbol, size: i128) {
+    let key = DataKey::TotalLongSize(asset.clone());
+    env.storage().persistent().set(&key, &size);
+    extend_persistent_ttl(env, &key);
 }
 
-pub fn get_total_short_size(env: &Env) -> i128 {
-    env.storage().persistent().get(&DataKey::TotalShortSize).unwrap_or(0)
+pub fn get_total_short_size(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::TotalShortSize(asset.clone())).unwrap_or(0)
 }
 
-pub fn set_total_short_size(env: &Env, size: i128) {
-    env.storage().persistent().set(&DataKey::TotalShortSize, &size);
-    extend_persistent_ttl(env, &DataKey::TotalShortSize);
+pub fn set_total_short_size(env: &Env, asset: &Symbol, size: i128) {
+    let key = DataKey::TotalShortSize(asset.clone());
+    env.storage().persistent().set(&key, &size);
+    extend_persistent_ttl(env, &key);
 }
 
-pub fn get_last_funding_time(env: &Env) -> u64 {
-    env.storage().persistent().get(&DataKey::LastFundingTime).unwrap_or(0)
+pub fn get_total_long_quantity(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::TotalLongQuantity(asset.clone())).unwrap_or(0)
 }
 
-pub fn set_last_funding_time(env: &Env, time: u64) {
-    env.storage().persistent().set(&DataKey::LastFundingTime, &time);
-    extend_persistent_ttl(env, &DataKey::LastFundingTime);
+pub fn set_total_long_quantity(env: &Env, asset: &Symbol, quantity: i128) {
+    let key = DataKey::TotalLongQuantity(asset.clone());
+    env.storage().persistent().set(&key, &quantity);
+    extend_persistent_ttl(env, &key);
 }
 
-pub fn get_current_funding_rate(env: &Env) -> i128 {
-    env.storage().persistent().get(&DataKey::CurrentFundingRate).unwrap_or(0)
+pub fn get_total_short_quantity(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::TotalShortQuantity(asset.clone())).unwrap_or(0)
 }
 
-pub fn set_current_funding_rate(env: &Env, rate: i128) {
-    env.storage().persistent().set(&DataKey::CurrentFundingRate, &rate);
-    extend_persistent_ttl(env, &DataKey::CurrentFundingRate);
+pub fn set_total_short_quantity(env: &Env, asset: &Symbol, quantity: i128) {
+    let key = DataKey::TotalShortQuantity(asset.clone());
+    env.storage().persistent().set(&key, &quantity);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_open_interest_assets(env: &Env) -> Vec<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OpenInterestAssets)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Record that an asset carries open interest (no-op if already tracked).
+pub fn track_open_interest_asset(env: &Env, asset: &Symbol) {
+    let mut assets = get_open_interest_assets(env);
+    if !assets.contains(asset) {
+        assets.push_back(asset.clone());
+        env.storage().persistent().set(&DataKey::OpenInterestAssets, &assets);
+    }
+    extend_persistent_ttl(env, &DataKey::OpenInterestAssets);
+}
+
+pub fn get_last_funding_time(env: &Env, asset: &Symbol) -> u64 {
+    env.storage().persistent().get(&DataKey::LastFundingTime(asset.clone())).unwrap_or(0)
+}
+
+pub fn set_last_funding_time(env: &Env, asset: &Symbol, time: u64) {
+    let key = DataKey::LastFundingTime(asset.clone());
+    env.storage().persistent().set(&key, &time);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_current_funding_rate(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::CurrentFundingRate(asset.clone())).unwrap_or(0)
+}
+
+pub fn set_current_funding_rate(env: &Env, asset: &Symbol, rate: i128) {
+    let key = DataKey::CurrentFundingRate(asset.clone());
+    env.storage().persistent().set(&key, &rate);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_funding_index(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::FundingIndex(asset.clone())).unwrap_or(0)
+}
+
+pub fn set_funding_index(env: &Env, asset: &Symbol, index: i128) {
+    let key = DataKey::FundingIndex(asset.clone());
+    env.storage().persistent().set(&key, &index);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_last_borrow_time(env: &Env, asset: &Symbol) -> u64 {
+    env.storage().persistent().get(&DataKey::LastBorrowTime(asset.clone())).unwrap_or(0)
+}
+
+pub fn set_last_borrow_time(env: &Env, asset: &Symbol, time: u64) {
+    let key = DataKey::LastBorrowTime(asset.clone());
+    env.storage().persistent().set(&key, &time);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_current_borrow_rate(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::CurrentBorrowRate(asset.clone())).unwrap_or(0)
+}
+
+pub fn set_current_borrow_rate(env: &Env, asset: &Symbol, rate: i128) {
+    let key = DataKey::CurrentBorrowRate(asset.clone());
+    env.storage().persistent().set(&key, &rate);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_borrow_index(env: &Env, asset: &Symbol) -> i128 {
+    env.storage().persistent().get(&DataKey::BorrowIndex(asset.clone())).unwrap_or(0)
+}
+
+pub fn set_borrow_index(env: &Env, asset: &Symbol, index: i128) {
+    let key = DataKey::BorrowIndex(asset.clone());
+    env.storage().persistent().set(&key, &index);
+    extend_persistent_ttl(env, &key);
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -181,110 +386,51 @@ pub fn get_position(env: &Env, id: u64) -> Option<Position> {
 }
 
 pub fn save_position(env: &Env, position: &Position) {
-    // Save position
     env.storage().persistent().set(&DataKey::Position(position.id), position);
     extend_persistent_ttl(env, &DataKey::Position(position.id));
+}
 
-    // Add to trader's position list
-    let trader_key = DataKey::TraderPositions(position.trader.clone());
-    let mut trader_positions: Vec<u64> = env.storage()
-        .persistent()
-        .get(&trader_key)
-        .unwrap_or(Vec::new(env));
-
-    // Only add if not already in list
-    let mut found = false;
-    for i in 0..trader_positions.len() {
-        if trader_positions.get(i).unwrap() == position.id {
-            found = true;
-            break;
-        }
-    }
-    if !found {
-        trader_positions.push_back(position.id);
-        env.storage().persistent().set(&trader_key, &trader_positions);
-        extend_persistent_ttl(env, &trader_key);
-    }
+/// Save a newly opened position and add it to the global, asset and trader indexes.
+pub fn save_new_position(env: &Env, position: &Position) {
+    save_position(env, position);
 
-    // Add to global position index
-    let mut all_positions = get_all_position_ids(env);
-    let mut found_global = false;
-    for i in 0..all_positions.len() {
-        if all_positions.get(i).unwrap() == position.id {
-            found_global = true;
-            break;
-        }
-    }
-    if !found_global {
-        all_positions.push_back(position.id);
-        env.storage().persistent().set(&DataKey::AllPositions, &all_positions);
-        extend_persistent_ttl(env, &DataKey::AllPositions);
-    }
+    index_add(env, &IndexKey::AllPositions, position.id);
+    index_add(env, &IndexKey::AssetPositions(position.asset.clone()), position.id);
+    index_add(env, &IndexKey::TraderPositions(position.trader.clone()), position.id);
 }
 
 pub fn delete_position(env: &Env, id: u64, trader: &Address) {
+    // Remove from indexes while the asset is still known
+    if let Some(position) = get_position(env, id) {
+        index_remove(env, &IndexKey::AssetPositions(position.asset), id);
+    }
+    index_remove(env, &IndexKey::AllPositions, id);
+    index_remove(env, &IndexKey::TraderPositions(trader.clone()), id);
+
     // Remove from storage
     env.storage().persistent().remove(&DataKey::Position(id));
-
-    // Remove from trader's list
-    let trader_key = DataKey::TraderPositions(trader.clone());
-    let trader_positions: Vec<u64> = env.storage()
-        .persistent()
-        .get(&trader_key)
-        .unwrap_or(Vec::new(env));
-
-    let mut new_list = Vec::new(env);
-    for i in 0..trader_positions.len() {
-        let pos_id = trader_positions.get(i).unwrap();
-        if pos_id != id {
-            new_list.push_back(pos_id);
-        }
-    }
-    env.storage().persistent().set(&trader_key, &new_list);
-
-    // Remove from global index
-    let all_positions = get_all_position_ids(env);
-    let mut new_all = Vec::new(env);
-    for i in 0..all_positions.len() {
-        let pos_id = all_positions.get(i).unwrap();
-        if pos_id != id {
-            new_all.push_back(pos_id);
-        }
-    }
-    env.storage().persistent().set(&DataKey::AllPositions, &new_all);
 }
 
-pub fn get_trader_positions(env: &Env, trader: &Address) -> Vec<Position> {
-    let trader_key = DataKey::TraderPositions(trader.clone());
-    let position_ids: Vec<u64> = env.storage()
-        .persistent()
-        .get(&trader_key)
-        .unwrap_or(Vec::new(env));
+/// Get a page of a trader's open positions with IDs after `start_after`.
+pub fn get_trader_positions(
+    env: &Env,
+    trader: &Address,
+    start_after: u64,
+    limit: u32,
+) -> Result<Vec<Position>, NoetherError> {
+    let position_ids = index_page(env, &IndexKey::TraderPositions(trader.clone()), start_after, limit)?;
 
     let mut positions = Vec::new(env);
-    for i in 0..position_ids.len() {
-        let id = position_ids.get(i).unwrap();
+    for id in position_ids.iter() {
         if let Some(pos) = get_position(env, id) {
             positions.push_back(pos);
         }
     }
-    positions
-}
-
-pub fn init_position_index(env: &Env) {
-    let empty: Vec<u64> = Vec::new(env);
-    env.storage().persistent().set(&DataKey::AllPositions, &empty);
-}
-
-pub fn get_all_position_ids(env: &Env) -> Vec<u64> {
-    env.storage()
-        .persistent()
-        .get(&DataKey::AllPositions)
-        .unwrap_or(Vec::new(env))
+    Ok(positions)
 }
 
 pub fn get_position_count(env: &Env) -> u64 {
-    get_all_position_ids(env).len() as u64
+    index_len(env, &IndexKey::AllPositions)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -312,6 +458,14 @@ pub fn require_admin(env: &Env) -> Result<(), NoetherError> {
     Ok(())
 }
 
+/// In allowlist mode only registered keepers may run keeper jobs; in open mode anyone may.
+pub fn require_keeper(env: &Env, keeper: &Address) -> Result<(), NoetherError> {
+    if is_keeper_allowlist_enabled(env) && !get_keeper_info(env, keeper).registered {
+        return Err(NoetherError::KeeperNotRegistered);
+    }
+    Ok(())
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // TTL Management
 // ═══════════════════════════════════════════════════════════════════════════
@@ -349,47 +503,17 @@ pub fn get_order(env: &Env, id: u64) -> Option<Order> {
 }
 
 pub fn save_order(env: &Env, order: &Order) {
-    // Save order
     env.storage().persistent().set(&DataKey::Order(order.id), order);
     extend_persistent_ttl(env, &DataKey::Order(order.id));
+}
 
-    // Add to trader's order list if pending
-    if order.status == OrderStatus::Pending {
-        let trader_key = DataKey::TraderOrders(order.trader.clone());
-        let mut trader_orders: Vec<u64> = env.storage()
-            .persistent()
-            .get(&trader_key)
-            .unwrap_or(Vec::new(env));
-
-        // Only add if not already in list
-        let mut found = false;
-        for i in 0..trader_orders.len() {
-            if trader_orders.get(i).unwrap() == order.id {
-                found = true;
-                break;
-            }
-        }
-        if !found {
-            trader_orders.push_back(order.id);
-            env.storage().persistent().set(&trader_key, &trader_orders);
-            extend_persistent_ttl(env, &trader_key);
-        }
+/// Save a newly placed order and add it to the global, asset and trader indexes.
+pub fn save_new_order(env: &Env, order: &Order) {
+    save_order(env, order);
 
-        // Add to global order index
-        let mut all_orders = get_all_order_ids(env);
-        let mut found_global = false;
-        for i in 0..all_orders.len() {
-            if all_orders.get(i).unwrap() == order.id {
-                found_global = true;
-                break;
-            }
-        }
-        if !found_global {
-            all_orders.push_back(order.id);
-            env.storage().persistent().set(&DataKey::AllOrders, &all_orders);
-            extend_persistent_ttl(env, &DataKey::AllOrders);
-        }
-    }
+    index_add(env, &IndexKey::AllOrders, order.id);
+    index_add(env, &IndexKey::AssetOrders(order.asset.clone()), order.id);
+    index_add(env, &IndexKey::TraderOrders(order.trader.clone()), order.id);
 }
 
 pub fn update_order_status(env: &Env, order_id: u64, status: OrderStatus) {
@@ -398,108 +522,428 @@ pub fn update_order_status(env: &Env, order_id: u64, status: OrderStatus) {
         env.storage().persistent().set(&DataKey::Order(order_id), &order);
         extend_persistent_ttl(env, &DataKey::Order(order_id));
 
-        // Remove from active lists if no longer pending
+        // Remove from active indexes if no longer pending
         if status != OrderStatus::Pending {
-            remove_order_from_lists(env, order_id, &order.trader);
+            remove_order_from_lists(env, &order);
         }
     }
 }
 
-pub fn remove_order_from_lists(env: &Env, order_id: u64, trader: &Address) {
-    // Remove from trader's list
-    let trader_key = DataKey::TraderOrders(trader.clone());
-    let trader_orders: Vec<u64> = env.storage()
-        .persistent()
-        .get(&trader_key)
-        .unwrap_or(Vec::new(env));
+pub fn remove_order_from_lists(env: &Env, order: &Order) {
+    index_remove(env, &IndexKey::AllOrders, order.id);
+    index_remove(env, &IndexKey::AssetOrders(order.asset.clone()), order.id);
+    index_remove(env, &IndexKey::TraderOrders(order.trader.clone()), order.id);
+}
 
-    let mut new_list = Vec::new(env);
-    for i in 0..trader_orders.len() {
-        let id = trader_orders.get(i).unwrap();
-        if id != order_id {
-            new_list.push_back(id);
+/// Get a page of a trader's pending orders with IDs after `start_after`.
+pub fn get_trader_orders(
+    env: &Env,
+    trader: &Address,
+    start_after: u64,
+    limit: u32,
+) -> Result<Vec<Order>, NoetherError> {
+    let order_ids = index_page(env, &IndexKey::TraderOrders(trader.clone()), start_after, limit)?;
+
+    let mut orders = Vec::new(env);
+    for id in order_ids.iter() {
+        if let Some(order) = get_order(env, id) {
+            orders.push_back(order);
         }
     }
-    env.storage().persistent().set(&trader_key, &new_list);
+    Ok(orders)
+}
+
+pub fn get_order_count(env: &Env) -> u64 {
+    index_len(env, &IndexKey::AllOrders)
+}
+
+// Position close-order attachment helpers
+pub fn get_position_order_ids(env: &Env, position_id: u64) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PositionOrders(position_id))
+        .unwrap_or(Vec::new(env))
+}
 
-    // Remove from global index
-    let all_orders = get_all_order_ids(env);
-    let mut new_all = Vec::new(env);
-    for i in 0..all_orders.len() {
-        let id = all_orders.get(i).unwrap();
+pub fn add_position_order(env: &Env, position_id: u64, order_id: u64) {
+    let key = DataKey::PositionOrders(position_id);
+    let mut order_ids = get_position_order_ids(env, position_id);
+    order_ids.push_back(order_id);
+    env.storage().persistent().set(&key, &order_ids);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn remove_position_order(env: &Env, position_id: u64, order_id: u64) {
+    let key = DataKey::PositionOrders(position_id);
+    let order_ids = get_position_order_ids(env, position_id);
+
+    let mut remaining: Vec<u64> = Vec::new(env);
+    for id in order_ids.iter() {
         if id != order_id {
-            new_all.push_back(id);
+            remaining.push_back(id);
         }
     }
-    env.storage().persistent().set(&DataKey::AllOrders, &new_all);
+
+    if remaining.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &remaining);
+        extend_persistent_ttl(env, &key);
+    }
 }
 
-pub fn delete_order(env: &Env, order_id: u64, trader: &Address) {
-    // Remove from storage
-    env.storage().persistent().remove(&DataKey::Order(order_id));
+pub fn remove_position_orders(env: &Env, position_id: u64) {
+    env.storage().persistent().remove(&DataKey::PositionOrders(position_id));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Sharded ID Indexes
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Add an ID to an index. Does nothing if it is already indexed.
+/// IDs are added when positions and orders are created, so a new shard
+/// normally goes at the tail of the shard list.
+pub fn index_add(env: &Env, index: &IndexKey, id: u64) {
+    let shard = id / INDEX_SHARD_SIZE;
+    let mut meta = get_index_meta(env, index);
+
+    match get_index_shard(env, index, shard) {
+        Some(mut entry) => {
+            if !insert_sorted(&mut entry.ids, id) {
+                return;
+            }
+            set_index_shard(env, index, shard, &entry);
+        }
+        None => {
+            // Find the non-empty shards either side of the new one
+            let mut prev = meta.tail;
+            let mut next = None;
+            while let Some(candidate) = prev.filter(|&p| p > shard) {
+                next = Some(candidate);
+                prev = get_index_shard(env, index, candidate).and_then(|entry| entry.prev);
+            }
+
+            match prev.and_then(|p| get_index_shard(env, index, p).map(|entry| (p, entry))) {
+                Some((p, mut entry)) => {
+                    entry.next = Some(shard);
+                    set_index_shard(env, index, p, &entry);
+                }
+                None => meta.head = Some(shard),
+            }
+            match next.and_then(|n| get_index_shard(env, index, n).map(|entry| (n, entry))) {
+                Some((n, mut entry)) => {
+                    entry.prev = Some(shard);
+                    set_index_shard(env, index, n, &entry);
+                }
+                None => meta.tail = Some(shard),
+            }
+
+            let mut ids = Vec::new(env);
+            ids.push_back(id);
+            set_index_shard(env, index, shard, &IndexShardEntry { ids, prev, next });
+            meta.shard_count += 1;
+        }
+    }
 
-    // Remove from lists
-    remove_order_from_lists(env, order_id, trader);
+    meta.len += 1;
+    set_index_meta(env, index, &meta);
 }
 
-pub fn get_trader_orders(env: &Env, trader: &Address) -> Vec<Order> {
-    let trader_key = DataKey::TraderOrders(trader.clone());
-    let order_ids: Vec<u64> = env.storage()
-        .persistent()
-        .get(&trader_key)
-        .unwrap_or(Vec::new(env));
+/// Remove an ID from an index. Does nothing if it is not indexed.
+pub fn index_remove(env: &Env, index: &IndexKey, id: u64) {
+    let shard = id / INDEX_SHARD_SIZE;
+    let mut entry = match get_index_shard(env, index, shard) {
+        Some(entry) => entry,
+        None => return,
+    };
 
-    let mut orders = Vec::new(env);
-    for i in 0..order_ids.len() {
-        let id = order_ids.get(i).unwrap();
-        if let Some(order) = get_order(env, id) {
-            if order.status == OrderStatus::Pending {
-                orders.push_back(order);
+    let mut remaining = Vec::new(env);
+    for existing in entry.ids.iter() {
+        if existing != id {
+            remaining.push_back(existing);
+        }
+    }
+    if remaining.len() == entry.ids.len() {
+        return;
+    }
+
+    let mut meta = get_index_meta(env, index);
+    if remaining.is_empty() {
+        // Drop the empty shard and link its neighbours to each other
+        match entry.prev.and_then(|p| get_index_shard(env, index, p).map(|prev| (p, prev))) {
+            Some((p, mut prev)) => {
+                prev.next = entry.next;
+                set_index_shard(env, index, p, &prev);
+            }
+            None => meta.head = entry.next,
+        }
+        match entry.next.and_then(|n| get_index_shard(env, index, n).map(|next| (n, next))) {
+            Some((n, mut next)) => {
+                next.prev = entry.prev;
+                set_index_shard(env, index, n, &next);
+            }
+            None => meta.tail = entry.prev,
+        }
+
+        env.storage().persistent().remove(&DataKey::IndexShard(index.clone(), shard));
+        meta.shard_count = meta.shard_count.saturating_sub(1);
+    } else {
+        entry.ids = remaining;
+        set_index_shard(env, index, shard, &entry);
+    }
+
+    meta.len = meta.len.saturating_sub(1);
+    set_index_meta(env, index, &meta);
+}
+
+/// Get up to `limit` IDs of an index after `start_after`, ascending.
+/// Pass 0 to start from the beginning and the last returned ID for the next page.
+/// `limit` is capped at `MAX_PAGE_SIZE`.
+///
+/// Fails with `InvalidCursor` if the cursor's shard has emptied since and
+/// non-empty shards remain on both sides of it; finding the next one would mean
+/// walking the shard list, so the caller restarts from 0 instead.
+pub fn index_page(env: &Env, index: &IndexKey, start_after: u64, limit: u32) -> Result<Vec<u64>, NoetherError> {
+    let limit = limit.min(MAX_PAGE_SIZE);
+    let mut page = Vec::new(env);
+    if limit == 0 {
+        return Ok(page);
+    }
+
+    // Resume in the cursor's shard; if it has emptied, the head is next when the
+    // cursor is before it and nothing is left when the cursor is past the tail
+    let cursor_shard = start_after / INDEX_SHARD_SIZE;
+    let mut shard = match get_index_shard(env, index, cursor_shard) {
+        Some(entry) => Some(entry),
+        None => {
+            let meta = get_index_meta(env, index);
+            match (meta.head, meta.tail) {
+                (Some(head), _) if cursor_shard < head => get_index_shard(env, index, head),
+                (Some(_), Some(tail)) if cursor_shard < tail => return Err(NoetherError::InvalidCursor),
+                _ => None,
             }
         }
+    };
+
+    while let Some(entry) = shard {
+        for id in entry.ids.iter() {
+            if id <= start_after {
+                continue;
+            }
+            page.push_back(id);
+            if page.len() >= limit {
+                return Ok(page);
+            }
+        }
+        shard = entry.next.and_then(|next| get_index_shard(env, index, next));
     }
-    orders
+
+    Ok(page)
 }
 
-pub fn init_order_index(env: &Env) {
-    let empty: Vec<u64> = Vec::new(env);
-    env.storage().persistent().set(&DataKey::AllOrders, &empty);
+/// Get the number of IDs in an index.
+pub fn index_len(env: &Env, index: &IndexKey) -> u64 {
+    get_index_meta(env, index).len
 }
 
-pub fn get_all_order_ids(env: &Env) -> Vec<u64> {
+fn get_index_meta(env: &Env, index: &IndexKey) -> IndexMeta {
     env.storage()
         .persistent()
-        .get(&DataKey::AllOrders)
-        .unwrap_or(Vec::new(env))
+        .get(&DataKey::IndexMeta(index.clone()))
+        .unwrap_or_default()
 }
 
-pub fn get_order_count(env: &Env) -> u64 {
-    get_all_order_ids(env).len() as u64
+fn set_index_meta(env: &Env, index: &IndexKey, meta: &IndexMeta) {
+    let key = DataKey::IndexMeta(index.clone());
+    env.storage().persistent().set(&key, meta);
+    extend_persistent_ttl(env, &key);
+}
+
+fn get_index_shard(env: &Env, index: &IndexKey, shard: u64) -> Option<IndexShardEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::IndexShard(index.clone(), shard))
+}
+
+fn set_index_shard(env: &Env, index: &IndexKey, shard: u64, entry: &IndexShardEntry) {
+    let key = DataKey::IndexShard(index.clone(), shard);
+    env.storage().persistent().set(&key, entry);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Insert a value into an ascending list. Returns false if it was already present.
+fn insert_sorted(values: &mut Vec<u64>, value: u64) -> bool {
+    let mut pos = values.len();
+    for (i, existing) in values.iter().enumerate() {
+        if existing == value {
+            return false;
+        }
+        if existing > value {
+            pos = i as u32;
+            break;
+        }
+    }
+    values.insert(pos, value);
+    true
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Keeper Registry
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub fn get_keeper_info(env: &Env, keeper: &Address) -> KeeperInfo {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Keeper(keeper.clone()))
+        .unwrap_or_default()
 }
 
-// Position SL/TP attachment helpers
-pub fn get_position_stop_loss(env: &Env, position_id: u64) -> Option<u64> {
-    env.storage().persistent().get(&DataKey::PositionStopLoss(position_id))
+pub fn save_keeper_info(env: &Env, keeper: &Address, info: &KeeperInfo) {
+    let key = DataKey::Keeper(keeper.clone());
+    env.storage().persistent().set(&key, info);
+    extend_persistent_ttl(env, &key);
 }
 
-pub fn set_position_stop_loss(env: &Env, position_id: u64, order_id: u64) {
-    env.storage().persistent().set(&DataKey::PositionStopLoss(position_id), &order_id);
-    extend_persistent_ttl(env, &DataKey::PositionStopLoss(position_id));
+/// Registered keepers. Only the admin adds to this list, so it stays small;
+/// keepers that merely run jobs in open mode are tracked by their entry alone.
+fn get_registered_keepers(env: &Env) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Keepers)
+        .unwrap_or(Vec::new(env))
 }
 
-pub fn remove_position_stop_loss(env: &Env, position_id: u64) {
-    env.storage().persistent().remove(&DataKey::PositionStopLoss(position_id));
+fn set_registered_keepers(env: &Env, keepers: &Vec<Address>) {
+    if keepers.is_empty() {
+        env.storage().persistent().remove(&DataKey::Keepers);
+    } else {
+        env.storage().persistent().set(&DataKey::Keepers, keepers);
+        extend_persistent_ttl(env, &DataKey::Keepers);
+    }
+}
+
+pub fn add_registered_keeper(env: &Env, keeper: &Address) {
+    let mut keepers = get_registered_keepers(env);
+    if !keepers.contains(keeper) {
+        keepers.push_back(keeper.clone());
+        set_registered_keepers(env, &keepers);
+    }
 }
 
-pub fn get_position_take_profit(env: &Env, position_id: u64) -> Option<u64> {
-    env.storage().persistent().get(&DataKey::PositionTakeProfit(position_id))
+pub fn remove_registered_keeper(env: &Env, keeper: &Address) {
+    let mut keepers = get_registered_keepers(env);
+    if let Some(pos) = keepers.first_index_of(keeper) {
+        keepers.remove(pos);
+        set_registered_keepers(env, &keepers);
+    }
 }
 
-pub fn set_position_take_profit(env: &Env, position_id: u64, order_id: u64) {
-    env.storage().persistent().set(&DataKey::PositionTakeProfit(position_id), &order_id);
-    extend_persistent_ttl(env, &DataKey::PositionTakeProfit(position_id));
+/// Get up to `limit` registered keepers after `start_after`, in registration order.
+/// Pass None to start from the beginning and the last returned keeper for the next page.
+/// `limit` is capped at `MAX_PAGE_SIZE`.
+pub fn get_keepers(env: &Env, start_after: Option<Address>, limit: u32) -> Vec<Address> {
+    let limit = limit.min(MAX_PAGE_SIZE);
+    let keepers = get_registered_keepers(env);
+    let start = match start_after {
+        Some(keeper) => match keepers.first_index_of(&keeper) {
+            Some(pos) => pos + 1,
+            None => return Vec::new(env),
+        },
+        None => 0,
+    };
+    let end = keepers.len().min(start.saturating_add(limit));
+    if start >= end {
+        return Vec::new(env);
+    }
+    keepers.slice(start..end)
 }
 
-pub fn remove_position_take_profit(env: &Env, position_id: u64) {
-    env.storage().persistent().remove(&DataKey::PositionTakeProfit(position_id));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MarketContract;
+
+    fn ids(env: &Env, values: &[u64]) -> Vec<u64> {
+        let mut ids = Vec::new(env);
+        for value in values {
+            ids.push_back(*value);
+        }
+        ids
+    }
+
+    #[test]
+    fn test_index_pages_across_shards() {
+        let env = Env::default();
+        let contract = env.register_contract(None, MarketContract);
+        env.as_contract(&contract, || {
+            let index = IndexKey::AllPositions;
+            for id in [1, 2, 130, 300, 301] {
+                index_add(&env, &index, id);
+            }
+            // Re-adding is a no-op
+            index_add(&env, &index, 130);
+
+            assert_eq!(index_len(&env, &index), 5);
+            assert_eq!(get_index_meta(&env, &index).shard_count, 3);
+            assert_eq!(index_page(&env, &index, 0, 2), Ok(ids(&env, &[1, 2])));
+            assert_eq!(index_page(&env, &index, 2, 2), Ok(ids(&env, &[130, 300])));
+            assert_eq!(index_page(&env, &index, 300, 2), Ok(ids(&env, &[301])));
+            assert_eq!(index_page(&env, &index, 301, 2), Ok(ids(&env, &[])));
+        });
+    }
+
+    #[test]
+    fn test_index_page_rejects_cursor_in_drained_middle_shard() {
+        let env = Env::default();
+        let contract = env.register_contract(None, MarketContract);
+        env.as_contract(&contract, || {
+            let index = IndexKey::AllPositions;
+            for id in [1, 130, 131, 300] {
+                index_add(&env, &index, id);
+            }
+            assert_eq!(index_page(&env, &index, 0, 2), Ok(ids(&env, &[1, 130])));
+
+            // The shard holding the cursor drains between pages
+            index_remove(&env, &index, 130);
+            index_remove(&env, &index, 131);
+            assert_eq!(index_page(&env, &index, 130, 2), Err(NoetherError::InvalidCursor));
+            assert_eq!(index_page(&env, &index, 0, 2), Ok(ids(&env, &[1, 300])));
+
+            // Cursors before the head or past the tail need no walk
+            index_remove(&env, &index, 1);
+            assert_eq!(index_page(&env, &index, 1, 2), Ok(ids(&env, &[300])));
+            assert_eq!(index_page(&env, &index, 500, 2), Ok(ids(&env, &[])));
+        });
+    }
+
+    #[test]
+    fn test_index_unlinks_emptied_shards() {
+        let env = Env::default();
+        let contract = env.register_contract(None, MarketContract);
+        env.as_contract(&contract, || {
+            let index = IndexKey::AllOrders;
+            for id in [1, 130, 300] {
+                index_add(&env, &index, id);
+            }
+
+            // Emptying the middle shard links its neighbours
+            index_remove(&env, &index, 130);
+            assert_eq!(index_page(&env, &index, 0, 10), Ok(ids(&env, &[1, 300])));
+
+            // Emptying the ends moves the head and tail
+            index_remove(&env, &index, 1);
+            index_remove(&env, &index, 300);
+            let meta = get_index_meta(&env, &index);
+            assert_eq!((meta.len, meta.shard_count, meta.head, meta.tail), (0, 0, None, None));
+            assert_eq!(index_page(&env, &index, 0, 10), Ok(ids(&env, &[])));
+
+            // Removing an unknown ID is a no-op
+            index_remove(&env, &index, 7);
+            assert_eq!(index_len(&env, &index), 0);
+
+            index_add(&env, &index, 400);
+            index_add(&env, &index, 5);
+            assert_eq!(index_page(&env, &index, 0, 10), Ok(ids(&env, &[5, 400])));
+        });
+    }
 }
@@ -0,0 +1,1077 @@
+//! Contract-level tests: the market registered in a test `Env` against a
+//! Stellar asset USDC token, a mock oracle adapter and a mock vault.
+
+extern crate std;
+
+use std::vec::Vec;
+
+use crate::{MarketContract, MarketContractClient};
+use crate::liquidation::{
+    calculate_liquidation_price_from_collateral, calculate_margin_ratio_bps, calculate_partial_liquidation,
+};
+use noether_common::{
+    calculate_accrued_borrow_fee, calculate_borrow_index_delta, Direction, KeeperFeeConfig, MarketConfig, ModifyOrderParams,
+    NoetherError, Order, OrderStatus, Position, MAX_PAGE_SIZE, PRECISION,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, vec, Address, Env, IntoVal, Symbol, TryFromVal,
+};
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Mock Oracle Adapter
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: Symbol, price: i128) {
+        env.storage().instance().set(&asset, &price);
+    }
+
+    pub fn lastprice(env: Env, asset: Symbol) -> (i128, u64) {
+        let price: i128 = env.storage().instance().get(&asset).unwrap_or(0);
+        (price, env.ledger().timestamp())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Mock Vault
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Mirrors the accounting of the vault contract's market interface.
+#[contract]
+pub struct MockVault;
+
+#[contractimpl]
+impl MockVault {
+    pub fn init(env: Env, usdc: Address, market: Address) {
+        env.storage().instance().set(&symbol_short!("usdc"), &usdc);
+        env.storage().instance().set(&symbol_short!("market"), &market);
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        token::Client::new(&env, &Self::usdc(&env)).transfer(&from, &env.current_contract_address(), &amount);
+        Self::set(&env, symbol_short!("total"), Self::get_total_usdc(env.clone()) + amount);
+    }
+
+    pub fn settle_pnl(env: Env, pnl: i128) -> Result<(), NoetherError> {
+        let total = Self::get_total_usdc(env.clone());
+        if pnl > 0 {
+            if pnl > total {
+                return Err(NoetherError::InsufficientLiquidity);
+            }
+            let market: Address = env.storage().instance().get(&symbol_short!("market")).unwrap();
+            token::Client::new(&env, &Self::usdc(&env)).transfer(&env.current_contract_address(), &market, &pnl);
+        }
+        Self::set(&env, symbol_short!("total"), total - pnl);
+        Ok(())
+    }
+
+    pub fn reserve_for_position(env: Env, amount: i128) -> Result<(), NoetherError> {
+        let reserved = Self::get_reserved_usdc(env.clone());
+        if reserved + amount > Self::get_total_usdc(env.clone()) {
+            return Err(NoetherError::InsufficientLiquidity);
+        }
+        Self::set(&env, symbol_short!("reserved"), reserved + amount);
+        Ok(())
+    }
+
+    pub fn release_reserve(env: Env, amount: i128) {
+        let reserved = Self::get_reserved_usdc(env.clone());
+        Self::set(&env, symbol_short!("reserved"), (reserved - amount).max(0));
+    }
+
+    pub fn receive_fees(env: Env, amount: i128, insurance_amount: i128) {
+        let insurance = Self::get_insurance_fund(env.clone());
+        Self::set(&env, symbol_short!("fees"), Self::get(&env, symbol_short!("fees")) + amount);
+        Self::set(&env, symbol_short!("insurance"), insurance + insurance_amount);
+    }
+
+    pub fn cover_bad_debt(env: Env, amount: i128) -> Result<i128, NoetherError> {
+        if env.storage().instance().has(&symbol_short!("rejectbd")) {
+            return Err(NoetherError::Paused);
+        }
+        let insurance = Self::get_insurance_fund(env.clone());
+        let covered = amount.min(insurance);
+        Self::set(&env, symbol_short!("insurance"), insurance - covered);
+        Self::set(&env, symbol_short!("total"), Self::get_total_usdc(env.clone()) + covered);
+        Ok(covered)
+    }
+
+    /// Make `cover_bad_debt` fail, as a vault that rejects the call would.
+    pub fn reject_bad_debt(env: Env) {
+        env.storage().instance().set(&symbol_short!("rejectbd"), &true);
+    }
+
+    pub fn update_unrealized_pnl(env: Env, pnl: i128) {
+        Self::set(&env, symbol_short!("upnl"), pnl);
+    }
+
+    pub fn get_total_usdc(env: Env) -> i128 {
+        Self::get(&env, symbol_short!("total"))
+    }
+
+    pub fn get_reserved_usdc(env: Env) -> i128 {
+        Self::get(&env, symbol_short!("reserved"))
+    }
+
+    pub fn get_insurance_fund(env: Env) -> i128 {
+        Self::get(&env, symbol_short!("insurance"))
+    }
+
+    pub fn get_fees(env: Env) -> i128 {
+        Self::get(&env, symbol_short!("fees"))
+    }
+
+    pub fn get_unrealized_pnl(env: Env) -> i128 {
+        Self::get(&env, symbol_short!("upnl"))
+    }
+
+    pub fn get_utilization(env: Env) -> u32 {
+        let total = Self::get_total_usdc(env.clone());
+        if total <= 0 {
+            return 0;
+        }
+        (Self::get_reserved_usdc(env) * 10_000 / total).clamp(0, 10_000) as u32
+    }
+}
+
+impl MockVault {
+    fn usdc(env: &Env) -> Address {
+        env.storage().instance().get(&symbol_short!("usdc")).unwrap()
+    }
+
+    fn get(env: &Env, key: Symbol) -> i128 {
+        env.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    fn set(env: &Env, key: Symbol, value: i128) {
+        env.storage().instance().set(&key, &value);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Setup
+// ═══════════════════════════════════════════════════════════════════════════
+
+const VAULT_LIQUIDITY: i128 = 1_000_000 * PRECISION;
+const TRADER_BALANCE: i128 = 100_000 * PRECISION;
+
+struct TestMarket<'a> {
+    env: Env,
+    market: MarketContractClient<'a>,
+    oracle: MockOracleClient<'a>,
+    vault: MockVaultClient<'a>,
+    usdc: token::Client<'a>,
+    usdc_admin: token::StellarAssetClient<'a>,
+}
+
+impl<'a> TestMarket<'a> {
+    fn new() -> Self {
+        Self::with_liquidity(VAULT_LIQUIDITY)
+    }
+
+    fn with_liquidity(liquidity: i128) -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+
+        let admin = Address::generate(&env);
+        let usdc_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
+        let market_address = env.register_contract(None, MarketContract);
+        let oracle_address = env.register_contract(None, MockOracle);
+        let vault_address = env.register_contract(None, MockVault);
+
+        let market = MarketContractClient::new(&env, &market_address);
+        let oracle = MockOracleClient::new(&env, &oracle_address);
+        let vault = MockVaultClient::new(&env, &vault_address);
+        let usdc = token::Client::new(&env, &usdc_address);
+        let usdc_admin = token::StellarAssetClient::new(&env, &usdc_address);
+
+        vault.init(&usdc_address, &market_address);
+        let lp = Address::generate(&env);
+        usdc_admin.mint(&lp, &liquidity);
+        vault.deposit(&lp, &liquidity);
+
+        market.initialize(&admin, &oracle_address, &vault_address, &usdc_address, &MarketConfig::default());
+        market.list_asset(&symbol_short!("XLM"), &MarketConfig::default());
+        oracle.set_price(&symbol_short!("XLM"), &PRECISION);
+
+        TestMarket { env, market, oracle, vault, usdc, usdc_admin }
+    }
+
+    fn trader(&self) -> Address {
+        let trader = Address::generate(&self.env);
+        self.usdc_admin.mint(&trader, &TRADER_BALANCE);
+        trader
+    }
+
+    fn set_price(&self, asset: &str, price: i128) {
+        self.oracle.set_price(&Symbol::new(&self.env, asset), &price);
+    }
+
+    fn open(&self, trader: &Address, collateral: i128, leverage: u32, direction: Direction) -> Position {
+        self.market.open_position(trader, &symbol_short!("XLM"), &collateral, &leverage, &direction, &0)
+    }
+
+    /// Place a 5x XLM long entry that fills at or below `trigger_price`.
+    fn place_long_entry(&self, trader: &Address, collateral: i128, trigger_price: i128, expires_at: u64) -> Order {
+        self.market.place_limit_order(
+            trader,
+            &symbol_short!("XLM"),
+            &Direction::Long,
+            &collateral,
+            &5,
+            &trigger_price,
+            &false,
+            &100,
+            &expires_at,
+        )
+    }
+
+    fn now(&self) -> u64 {
+        self.env.ledger().timestamp()
+    }
+
+    fn advance(&self, seconds: u64) {
+        self.env.ledger().with_mut(|li| li.timestamp += seconds);
+    }
+
+    /// Cap open interest on each side of XLM, keeping the rest of the default config.
+    fn cap_open_interest(&self, max_long: i128, max_short: i128) {
+        self.market.update_asset_config(
+            &symbol_short!("XLM"),
+            &MarketConfig { max_long_open_interest: max_long, max_short_open_interest: max_short, ..MarketConfig::default() },
+        );
+    }
+
+    /// `(order_id, reason)` of each `order_cancelled` event from the last call.
+    fn cancelled_orders(&self) -> Vec<(u64, Symbol)> {
+        let topic = Symbol::new(&self.env, "order_cancelled");
+        self.env
+            .events()
+            .all()
+            .iter()
+            .filter(|(_, topics, _)| {
+                Symbol::try_from_val(&self.env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+            })
+            .map(|(_, _, data)| {
+                let (order_id, _, reason): (u64, Address, Symbol) = data.into_val(&self.env);
+                (order_id, reason)
+            })
+            .collect()
+    }
+
+    /// Amounts of each USDC transfer to `to` from the last call.
+    fn usdc_transfers_to(&self, to: &Address) -> Vec<i128> {
+        let topic = Symbol::new(&self.env, "transfer");
+        self.env
+            .events()
+            .all()
+            .iter()
+            .filter(|(contract, topics, _)| {
+                *contract == self.usdc.address
+                    && Symbol::try_from_val(&self.env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+                    && Address::try_from_val(&self.env, &topics.get(2).unwrap()).ok().as_ref() == Some(to)
+            })
+            .map(|(_, _, data)| data.into_val(&self.env))
+            .collect()
+    }
+}
+
+/// The liquidation price implied by a position's exact collateral and size.
+fn exact_liquidation_price(position: &Position) -> i128 {
+    calculate_liquidation_price_from_collateral(
+        position.entry_price,
+        position.size,
+        position.collateral,
+        position.direction,
+        MarketConfig::default().maintenance_margin_bps,
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Position Management
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_decrease_position_keeps_exact_liquidation_price() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+
+    // 101 USDC at 10x leaves ~10.1x after the opening fee
+    let position = t.open(&trader, 101 * PRECISION, 10, Direction::Long);
+    t.market.decrease_position(&trader, &position.id, &5_000, &0);
+
+    let reduced = t.market.get_position(&position.id).unwrap();
+    assert_eq!(reduced.size, position.size / 2);
+    assert_eq!(t.vault.get_reserved_usdc(), reduced.size);
+    assert_eq!(reduced.liquidation_price, exact_liquidation_price(&reduced));
+
+    // Equity is below maintenance just under the exact liquidation price
+    t.set_price("XLM", reduced.liquidation_price - 1);
+    assert!(t.market.is_liquidatable(&position.id));
+}
+
+#[test]
+fn test_remove_collateral_uses_fractional_leverage() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+
+    // 1000 USDC of size; withdraw down to 101 collateral (~9.9x, 10x max)
+    let position = t.open(&trader, 200 * PRECISION, 5, Direction::Long);
+    assert_eq!(position.size, 1000 * PRECISION);
+    let withdraw = position.collateral - 101 * PRECISION;
+    t.market.remove_collateral(&trader, &position.id, &withdraw);
+
+    let updated = t.market.get_position(&position.id).unwrap();
+    assert_eq!(updated.collateral, 101 * PRECISION);
+    assert_eq!(updated.liquidation_price, exact_liquidation_price(&updated));
+
+    // Truncating to 9x would put the liquidation price ~1% lower
+    t.set_price("XLM", updated.liquidation_price - 1);
+    assert!(t.market.is_liquidatable(&position.id));
+}
+
+#[test]
+fn test_remove_collateral_rejects_excess_leverage() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+
+    let position = t.open(&trader, 200 * PRECISION, 5, Direction::Long);
+    let withdraw = position.collateral - 99 * PRECISION;
+    let result = t.market.try_remove_collateral(&trader, &position.id, &withdraw);
+    assert_eq!(result, Err(Ok(NoetherError::InsufficientMargin)));
+}
+
+#[test]
+fn test_increase_position_blends_entry_and_rounds_leverage_up() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+
+    let position = t.open(&trader, 100 * PRECISION, 5, Direction::Long);
+    t.set_price("XLM", 2 * PRECISION);
+    let increased = t.market.increase_position(&trader, &position.id, &(50 * PRECISION), &10);
+
+    // 500 @ 1.0 + 500 @ ~2.0 gives an entry near 1.33
+    assert_eq!(increased.size, 1000 * PRECISION);
+    assert_eq!(t.vault.get_reserved_usdc(), 1000 * PRECISION);
+    assert!(increased.entry_price > 13_300_000 && increased.entry_price < 13_400_000);
+
+    // ~6.7x is stored as 7x and the liquidation price uses the exact collateral
+    assert_eq!(increased.leverage, 7);
+    assert_eq!(increased.liquidation_price, exact_liquidation_price(&increased));
+
+    t.set_price("XLM", increased.liquidation_price - 1);
+    assert!(t.market.is_liquidatable(&position.id));
+}
+
+#[test]
+fn test_open_position_rejects_entry_worse_than_acceptable_price() {
+    let t = TestMarket::new();
+    let xlm = symbol_short!("XLM");
+
+    // Longs buy, so the entry may not be above the acceptable price; shorts the reverse
+    for (direction, too_tight, loose) in [
+        (Direction::Long, PRECISION - 1, 10_100_000),
+        (Direction::Short, PRECISION + 1, 9_900_000),
+    ] {
+        let trader = t.trader();
+        let reserved = t.vault.get_reserved_usdc();
+        let result = t.market.try_open_position(&trader, &xlm, &(100 * PRECISION), &5, &direction, &too_tight);
+        assert_eq!(result.err(), Some(Ok(NoetherError::SlippageExceeded)));
+        assert_eq!(t.usdc.balance(&trader), TRADER_BALANCE);
+        assert_eq!(t.vault.get_reserved_usdc(), reserved);
+        assert!(t.market.get_positions(&trader, &0, &10).is_empty());
+
+        let position = t.market.open_position(&trader, &xlm, &(100 * PRECISION), &5, &direction, &loose);
+        assert_eq!(t.market.get_positions(&trader, &0, &10).len(), 1);
+        assert_eq!(t.vault.get_reserved_usdc(), reserved + position.size);
+    }
+}
+
+#[test]
+fn test_close_position_rejects_exit_worse_than_acceptable_price() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+
+    // The trader signs a close expecting ~1.0, but price moves against them before inclusion
+    for (direction, moved_price, signed_limit, wider_limit) in [
+        (Direction::Long, 9_500_000, 9_900_000, 9_400_000),
+        (Direction::Short, 10_500_000, 10_100_000, 10_600_000),
+    ] {
+        t.set_price("XLM", PRECISION);
+        let position = t.open(&trader, 100 * PRECISION, 5, direction);
+        let balance = t.usdc.balance(&trader);
+
+        t.set_price("XLM", moved_price);
+        let result = t.market.try_close_position(&trader, &position.id, &signed_limit);
+        assert_eq!(result.err(), Some(Ok(NoetherError::SlippageExceeded)));
+        assert_eq!(t.market.get_position(&position.id).unwrap().size, position.size);
+        assert_eq!(t.usdc.balance(&trader), balance);
+
+        // A limit that accepts the moved price lets the close through
+        t.market.close_position(&trader, &position.id, &wider_limit);
+        assert!(t.market.get_position(&position.id).is_none());
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Open Interest Caps
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_open_position_rejects_size_past_open_interest_cap() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    t.cap_open_interest(1_000 * PRECISION, 500 * PRECISION);
+
+    // Two 500 USDC longs fill the long cap exactly
+    t.open(&trader, 100 * PRECISION, 5, Direction::Long);
+    t.open(&trader, 100 * PRECISION, 5, Direction::Long);
+
+    let result = t.market.try_open_position(
+        &trader, &symbol_short!("XLM"), &(10 * PRECISION), &1, &Direction::Long, &0,
+    );
+    assert_eq!(result.err(), Some(Ok(NoetherError::OpenInterestCapExceeded)));
+
+    // The short side has its own cap
+    t.open(&trader, 100 * PRECISION, 5, Direction::Short);
+    let result = t.market.try_open_position(
+        &trader, &symbol_short!("XLM"), &(10 * PRECISION), &1, &Direction::Short, &0,
+    );
+    assert_eq!(result.err(), Some(Ok(NoetherError::OpenInterestCapExceeded)));
+
+    let stats = t.market.get_market_stats(&symbol_short!("XLM"));
+    assert_eq!((stats.total_long_size, stats.total_short_size), (1_000 * PRECISION, 500 * PRECISION));
+}
+
+#[test]
+fn test_limit_entry_past_open_interest_cap_is_cancelled_and_refunded() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let keeper = Address::generate(&t.env);
+    t.cap_open_interest(800 * PRECISION, 800 * PRECISION);
+
+    // The entry fits when placed, but another long fills the cap before it triggers
+    let order = t.place_long_entry(&trader, 100 * PRECISION, 9_000_000, 0);
+    t.open(&t.trader(), 100 * PRECISION, 5, Direction::Long);
+
+    t.set_price("XLM", 9_000_000);
+    assert_eq!(t.market.execute_order(&keeper, &order.id), 0);
+    assert_eq!(
+        t.cancelled_orders(),
+        std::vec![(order.id, Symbol::new(&t.env, "open_interest_cap"))],
+    );
+
+    // Collateral and escrowed fee come back; nothing is opened or paid out
+    assert_eq!(t.market.get_order(&order.id).unwrap().status, OrderStatus::Cancelled);
+    assert_eq!(t.usdc.balance(&trader), TRADER_BALANCE);
+    assert!(t.market.get_positions(&trader, &0, &10).is_empty());
+    assert_eq!(t.market.get_market_stats(&symbol_short!("XLM")).total_long_size, 500 * PRECISION);
+    assert_eq!(t.market.get_keeper_info(&keeper).accrued_rewards, 0);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Auto-Deleveraging
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_auto_deleverage_reduces_highest_ranked_winner() {
+    let t = TestMarket::with_liquidity(30_000 * PRECISION);
+    let trader = t.trader();
+    let keeper = Address::generate(&t.env);
+
+    // Same size, but the first position is twice as leveraged
+    let levered = t.open(&trader, 1_000 * PRECISION, 10, Direction::Long);
+    let safer = t.open(&trader, 2_000 * PRECISION, 5, Direction::Long);
+    let ids = vec![&t.env, safer.id, levered.id];
+
+    // Not needed while the vault covers trader PnL
+    let result = t.market.try_auto_deleverage(&keeper, &ids);
+    assert_eq!(result, Err(Ok(NoetherError::AdlNotRequired)));
+
+    // At 2.5x the price traders are up ~30k against 30k in the vault
+    t.set_price("XLM", 25_000_000);
+    assert!(t.market.get_vault_coverage() < 12_000);
+
+    // In allowlist mode only registered keepers may deleverage
+    t.market.set_keeper_allowlist(&true);
+    let result = t.market.try_auto_deleverage(&keeper, &ids);
+    assert_eq!(result, Err(Ok(NoetherError::KeeperNotRegistered)));
+    t.market.register_keeper(&keeper);
+
+    // Candidate lists are bounded like other keeper pages
+    let mut too_many = ids.clone();
+    for _ in 0..MAX_PAGE_SIZE - 1 {
+        too_many.push_back(999);
+    }
+    let result = t.market.try_auto_deleverage(&keeper, &too_many);
+    assert_eq!(result, Err(Ok(NoetherError::InvalidParameter)));
+
+    let reduced = t.market.auto_deleverage(&keeper, &ids);
+    // Ranked by (pnl / collateral) x leverage, not by the order passed in
+    assert_eq!(reduced, vec![&t.env, levered.id, safer.id]);
+    assert!(t.market.get_vault_coverage() >= 14_900);
+    assert_eq!(t.market.get_keeper_info(&keeper).deleverages, 2);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Borrow Fees
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_borrow_rate_reprices_every_asset_on_utilization_change() {
+    let t = TestMarket::with_liquidity(200_000 * PRECISION);
+    let btc = symbol_short!("BTC");
+    t.market.list_asset(&btc, &MarketConfig::default());
+    t.set_price("BTC", 60_000 * PRECISION);
+
+    // Balanced BTC book, so BTC positions only pay borrow fees
+    let trader = t.trader();
+    let btc_long = t.market.open_position(&trader, &btc, &(1_000 * PRECISION), &2, &Direction::Long, &0);
+    t.market.open_position(&trader, &btc, &(1_000 * PRECISION), &2, &Direction::Short, &0);
+    let low_rate = t.market.get_borrow_rate(&btc);
+
+    // A large XLM trade raises vault utilization for every asset
+    let whale = t.trader();
+    t.open(&whale, 10_000 * PRECISION, 10, Direction::Long);
+    let high_rate = t.market.get_borrow_rate(&btc);
+    assert!(high_rate > low_rate);
+
+    // BTC accrues at the new rate without any BTC activity; removing
+    // collateral checkpoints the position's fees
+    t.advance(3_600);
+    t.set_price("BTC", 60_000 * PRECISION);
+    t.market.remove_collateral(&trader, &btc_long.id, &PRECISION);
+    let settled = t.market.get_position(&btc_long.id).unwrap();
+    let expected = calculate_accrued_borrow_fee(btc_long.size, 0, calculate_borrow_index_delta(high_rate, 3_600));
+    assert!(expected > 0);
+    assert_eq!(settled.accrued_borrow_fee, expected);
+    assert_eq!(settled.accumulated_funding, 0);
+}
+
+#[test]
+fn test_borrow_fee_is_not_offset_by_funding_and_is_paid_as_fees() {
+    let t = TestMarket::with_liquidity(200_000 * PRECISION);
+    let config = MarketConfig { base_funding_rate_bps: 100, ..MarketConfig::default() };
+    t.market.update_asset_config(&symbol_short!("XLM"), &config);
+
+    // Long-heavy book: the short receives funding but still owes borrow fees
+    let whale = t.trader();
+    t.open(&whale, 10_000 * PRECISION, 10, Direction::Long);
+    let trader = t.trader();
+    let short = t.open(&trader, 1_000 * PRECISION, 5, Direction::Short);
+
+    t.advance(3_600);
+    t.set_price("XLM", PRECISION);
+    let health = t.market.get_position_health(&short.id);
+    assert!(health.accrued_funding < 0);
+    assert!(health.accrued_borrow_fee > 0);
+
+    let fees_before = t.vault.get_fees();
+    t.market.close_position(&trader, &short.id, &0);
+    assert_eq!(t.vault.get_fees() - fees_before, health.accrued_borrow_fee);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Orders
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_expire_orders_refunds_trader_and_credits_expiry_fee() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let keeper = Address::generate(&t.env);
+
+    let order = t.place_long_entry(&trader, 100 * PRECISION, 9_000_000, t.now() + 600);
+    let unexpiring = t.place_long_entry(&trader, 100 * PRECISION, 9_000_000, 0);
+    assert_eq!(t.usdc.balance(&trader), TRADER_BALANCE - 2 * (100 * PRECISION + order.execution_fee));
+
+    // Nothing is expired before the deadline
+    let ids = vec![&t.env, order.id, unexpiring.id, 999];
+    assert!(t.market.expire_orders(&keeper, &ids).is_empty());
+
+    t.advance(601);
+    assert_eq!(t.market.expire_orders(&keeper, &ids), vec![&t.env, order.id]);
+    assert_eq!(t.market.get_order(&order.id).unwrap().status, OrderStatus::Expired);
+    assert_eq!(t.market.get_order(&unexpiring.id).unwrap().status, OrderStatus::Pending);
+
+    // Collateral and escrow come back, less the expiry fee credited to the keeper
+    let expiry_fee = KeeperFeeConfig::default().expiry_fee;
+    assert_eq!(
+        t.usdc.balance(&trader),
+        TRADER_BALANCE - (100 * PRECISION + order.execution_fee) - expiry_fee,
+    );
+    let info = t.market.get_keeper_info(&keeper);
+    assert_eq!(info.orders_expired, 1);
+    assert_eq!(info.accrued_rewards, expiry_fee);
+
+    // Already expired orders are skipped
+    assert!(t.market.expire_orders(&keeper, &ids).is_empty());
+}
+
+#[test]
+fn test_take_profit_ladder_closes_whole_position() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let keeper = Address::generate(&t.env);
+
+    let position = t.open(&trader, 100 * PRECISION, 10, Direction::Long);
+    let mut ladder = Vec::new();
+    for _ in 0..3 {
+        let order = t.market.set_take_profit(&trader, &position.id, &11_000_000, &3_333, &100, &0);
+        assert_eq!(order.close_size, position.size * 3_333 / 10_000);
+        ladder.push(order);
+    }
+
+    // Each rung closes a third of the original size, not of what is left
+    t.set_price("XLM", 11_000_000);
+    t.market.execute_order(&keeper, &ladder[0].id);
+    t.market.execute_order(&keeper, &ladder[1].id);
+    let remaining = t.market.get_position(&position.id).unwrap();
+    assert_eq!(remaining.size, position.size - 2 * ladder[0].close_size);
+
+    // The last rung would leave dust below min collateral, so it closes everything
+    t.market.execute_order(&keeper, &ladder[2].id);
+    assert!(t.market.get_position(&position.id).is_none());
+    assert_eq!(t.vault.get_reserved_usdc(), 0);
+}
+
+/// Order amendment without an expiry.
+fn modify_params(trigger_price: i128, slippage_tolerance_bps: u32, collateral: i128, leverage: u32) -> ModifyOrderParams {
+    ModifyOrderParams { trigger_price, slippage_tolerance_bps, expires_at: 0, collateral, leverage }
+}
+
+#[test]
+fn test_modify_order_reprices_escrow_and_revalidates_triggers() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+
+    // Limit entry: more collateral at lower leverage tops up collateral and fee
+    let order = t.place_long_entry(&trader, 100 * PRECISION, 9_000_000, 0);
+    let balance = t.usdc.balance(&trader);
+    let amended = t.market.modify_order(&trader, &order.id, &modify_params(9_500_000, 200, 200 * PRECISION, 3));
+    assert_eq!(amended.id, order.id);
+    assert_eq!((amended.collateral, amended.leverage, amended.trigger_price), (200 * PRECISION, 3, 9_500_000));
+    let topped_up = (amended.collateral + amended.execution_fee) - (order.collateral + order.execution_fee);
+    assert_eq!(t.usdc.balance(&trader), balance - topped_up);
+
+    // Shrinking it refunds the difference
+    let shrunk = t.market.modify_order(&trader, &order.id, &modify_params(9_500_000, 200, 50 * PRECISION, 0));
+    assert_eq!(t.usdc.balance(&trader), balance + (order.collateral + order.execution_fee) - (shrunk.collateral + shrunk.execution_fee));
+
+    // Stop-loss: the new trigger must still be below entry for a long
+    let position = t.open(&trader, 100 * PRECISION, 5, Direction::Long);
+    let stop = t.market.set_stop_loss(&trader, &position.id, &9_000_000, &10_000, &100, &0);
+    let result = t.market.try_modify_order(&trader, &stop.id, &modify_params(position.entry_price + 1, 100, 0, 0));
+    assert_eq!(result.err(), Some(Ok(NoetherError::InvalidTriggerPrice)));
+    let result = t.market.try_modify_order(&trader, &stop.id, &modify_params(8_000_000, 100, 10 * PRECISION, 0));
+    assert_eq!(result.err(), Some(Ok(NoetherError::InvalidParameter)));
+
+    let moved = t.market.modify_order(&trader, &stop.id, &modify_params(8_000_000, 100, 0, 0));
+    assert_eq!(moved.trigger_price, 8_000_000);
+    assert_eq!(t.market.get_position_orders(&position.id).len(), 1);
+}
+
+#[test]
+fn test_close_order_escrows_fee_only_when_valid() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let position = t.open(&trader, 100 * PRECISION, 5, Direction::Long);
+    let balance = t.usdc.balance(&trader);
+
+    // A take-profit below entry for a long is rejected without taking a fee
+    let result = t.market.try_set_take_profit(&trader, &position.id, &9_000_000, &10_000, &100, &0);
+    assert_eq!(result.err(), Some(Ok(NoetherError::InvalidTriggerPrice)));
+    assert_eq!(t.usdc.balance(&trader), balance);
+
+    // A half-size take-profit escrows the fee on the size it closes
+    let order = t.market.set_take_profit(&trader, &position.id, &11_000_000, &5_000, &100, &0);
+    let config = KeeperFeeConfig::default();
+    let expected_fee = config.base_fee + order.close_size * config.variable_fee_bps as i128 / 10_000;
+    assert_eq!(order.execution_fee, expected_fee);
+    assert_eq!(t.usdc.balance(&trader), balance - expected_fee);
+}
+
+/// Attach a full stop-loss and a half take-profit to a long, so their escrowed fees differ.
+fn attach_stop_and_take_profit(t: &TestMarket, trader: &Address, position: &Position) -> [Order; 2] {
+    [
+        t.market.set_stop_loss(trader, &position.id, &5_000_000, &10_000, &100, &0),
+        t.market.set_take_profit(trader, &position.id, &50_000_000, &5_000, &100, &0),
+    ]
+}
+
+/// Check the last call cancelled `orders` for `reason`, refunded each escrowed
+/// fee and left no trace of them in the order indexes.
+fn assert_orders_cancelled(t: &TestMarket, trader: &Address, position: &Position, orders: &[Order], reason: &str) {
+    let reason = Symbol::new(&t.env, reason);
+    let cancelled: Vec<_> = orders.iter().map(|order| (order.id, reason.clone())).collect();
+    assert_eq!(t.cancelled_orders(), cancelled);
+
+    let refunds = t.usdc_transfers_to(trader);
+    for order in orders {
+        assert_ne!(order.execution_fee, 0);
+        assert!(refunds.contains(&order.execution_fee));
+        assert_eq!(t.market.get_order(&order.id).unwrap().status, OrderStatus::Cancelled);
+    }
+
+    assert!(t.market.get_position_orders(&position.id).is_empty());
+    assert!(t.market.get_orders(trader, &0, &10).is_empty());
+    assert!(t.market.get_all_order_ids(&0, &10).is_empty());
+    assert!(t.market.get_asset_order_ids(&symbol_short!("XLM"), &0, &10).is_empty());
+}
+
+#[test]
+fn test_close_position_cancels_attached_orders() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let position = t.open(&trader, 100 * PRECISION, 5, Direction::Long);
+    let orders = attach_stop_and_take_profit(&t, &trader, &position);
+
+    t.market.close_position(&trader, &position.id, &0);
+    assert_orders_cancelled(&t, &trader, &position, &orders, "position_closed");
+}
+
+#[test]
+fn test_liquidation_cancels_attached_orders() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let keeper = Address::generate(&t.env);
+    let position = t.open(&trader, 100 * PRECISION, 10, Direction::Long);
+    let orders = attach_stop_and_take_profit(&t, &trader, &position);
+
+    // Far enough under water to liquidate in full
+    t.set_price("XLM", 9_000_000);
+    t.market.liquidate(&keeper, &position.id);
+    assert!(t.market.get_position(&position.id).is_none());
+    assert_orders_cancelled(&t, &trader, &position, &orders, "position_liquidated");
+}
+
+#[test]
+fn test_auto_deleverage_full_close_cancels_attached_orders() {
+    let t = TestMarket::with_liquidity(30_000 * PRECISION);
+    let trader = t.trader();
+    let keeper = Address::generate(&t.env);
+    let position = t.open(&trader, 1_000 * PRECISION, 10, Direction::Long);
+    let orders = attach_stop_and_take_profit(&t, &trader, &position);
+
+    // The only winner holds the vault's whole balance in profit, so it is closed in full
+    t.set_price("XLM", 40_000_000);
+    t.market.auto_deleverage(&keeper, &vec![&t.env, position.id]);
+    assert!(t.market.get_position(&position.id).is_none());
+    assert_orders_cancelled(&t, &trader, &position, &orders, "auto_deleveraged");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Health Views
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_position_health_projects_fees_without_settling() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let position = t.open(&trader, 100 * PRECISION, 10, Direction::Long);
+    t.advance(3_600);
+    t.set_price("XLM", 10_500_000);
+
+    let health = t.market.get_position_health(&position.id);
+    assert_eq!(health.current_price, 10_500_000);
+    assert_eq!(health.unrealized_pnl, t.market.get_position_pnl(&position.id));
+    assert!(health.accrued_funding > 0);
+    assert!(health.accrued_borrow_fee > 0);
+    assert_eq!(
+        health.equity,
+        position.collateral + health.unrealized_pnl - health.accrued_funding - health.accrued_borrow_fee
+    );
+    assert!(!health.is_liquidatable);
+    assert!(health.distance_to_liquidation_bps > 0);
+
+    // The view projects fees; it does not settle them
+    assert_eq!(t.market.get_position(&position.id).unwrap().accumulated_funding, 0);
+
+    // Just past the liquidation price the remaining equity pays the keeper
+    t.set_price("XLM", position.liquidation_price - 1_000);
+    let health = t.market.get_position_health(&position.id);
+    assert!(health.is_liquidatable);
+    assert_eq!(health.distance_to_liquidation_bps, 0);
+    assert!(health.estimated_liquidation_payout > 0);
+    assert_eq!(health.estimated_bad_debt, 0);
+
+    // Far past it the loss exceeds the collateral
+    t.set_price("XLM", 8_000_000);
+    let health = t.market.get_position_health(&position.id);
+    assert_eq!(health.estimated_liquidation_payout, 0);
+    assert!(health.estimated_bad_debt > 0);
+}
+
+#[test]
+fn test_account_health_pages_sum_to_account_totals() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let ids: Vec<u64> = [2, 5, 10]
+        .iter()
+        .map(|leverage| t.open(&trader, 100 * PRECISION, *leverage, Direction::Long).id)
+        .collect();
+    t.set_price("XLM", 9_500_000);
+
+    let all = t.market.get_account_health(&trader, &0, &10);
+    let first = t.market.get_account_health(&trader, &0, &2);
+    let second = t.market.get_account_health(&trader, &ids[1], &2);
+
+    let page_ids = |health: &noether_common::AccountHealth| -> Vec<u64> {
+        health.positions.iter().map(|p| p.position_id).collect()
+    };
+    assert_eq!(page_ids(&all), ids);
+    assert_eq!(page_ids(&first), ids[..2]);
+    assert_eq!(page_ids(&second), ids[2..]);
+
+    assert_eq!(first.total_collateral + second.total_collateral, all.total_collateral);
+    assert_eq!(first.total_size + second.total_size, all.total_size);
+    assert_eq!(first.total_unrealized_pnl + second.total_unrealized_pnl, all.total_unrealized_pnl);
+    assert_eq!(first.total_equity + second.total_equity, all.total_equity);
+    assert_eq!(
+        first.min_liquidation_distance_bps.min(second.min_liquidation_distance_bps),
+        all.min_liquidation_distance_bps
+    );
+    assert_eq!(all.margin_ratio_bps, all.total_equity * 10_000 / all.total_size);
+
+    // A trader with no positions reports an empty, fully margined account
+    let empty = t.market.get_account_health(&t.trader(), &0, &10);
+    assert_eq!(empty.positions.len(), 0);
+    assert_eq!(empty.margin_ratio_bps, 10_000);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Liquidations
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_fees_owed_alone_make_position_liquidatable() {
+    let t = TestMarket::new();
+    let config = MarketConfig { base_funding_rate_bps: 100, ..MarketConfig::default() };
+    t.market.update_asset_config(&symbol_short!("XLM"), &config);
+    let keeper = Address::generate(&t.env);
+
+    let trader = t.trader();
+    let position = t.open(&trader, 100 * PRECISION, 10, Direction::Long);
+    assert!(!t.market.is_liquidatable(&position.id));
+
+    // The price never moves; a one-sided book pays funding until the margin
+    // after fees reaches the maintenance margin
+    t.advance(9 * 3_600);
+    t.set_price("XLM", PRECISION);
+    assert!(PRECISION > position.liquidation_price);
+    assert!(t.market.is_liquidatable(&position.id));
+
+    let (liquidatable, _) = t.market.get_liquidatable_positions(&symbol_short!("XLM"), &0, &10);
+    assert_eq!(liquidatable, vec![&t.env, position.id]);
+    assert!(t.market.liquidate(&keeper, &position.id) > 0);
+}
+
+#[test]
+fn test_liquidate_partially_restores_target_margin() {
+    let t = TestMarket::new();
+    let config = MarketConfig::default();
+    let keeper = Address::generate(&t.env);
+
+    let trader = t.trader();
+    let position = t.open(&trader, 1_000 * PRECISION, 10, Direction::Long);
+
+    // Margin between the full-liquidation floor (0.5%) and maintenance (1%)
+    let price = position.entry_price * (position.size - position.collateral + 75 * PRECISION) / position.size;
+    t.set_price("XLM", price);
+    let margin_bps = calculate_margin_ratio_bps(&position, price);
+    assert!(margin_bps > config.full_liquidation_margin_bps as i128);
+    assert!(margin_bps <= config.maintenance_margin_bps as i128);
+
+    let plan = calculate_partial_liquidation(
+        &position,
+        price,
+        config.partial_liquidation_target_bps,
+        config.liquidation_fee_bps,
+    )
+    .unwrap();
+    let keeper_fee = plan.liquidation_fee - plan.liquidation_fee * config.insurance_fee_bps as i128 / 10_000;
+
+    assert_eq!(t.market.liquidate(&keeper, &position.id), keeper_fee);
+    assert!(keeper_fee > 0);
+    assert_eq!(t.market.get_keeper_info(&keeper).accrued_rewards, keeper_fee);
+
+    // The remainder stays open with the planned size and collateral, back at the target margin
+    let remaining = t.market.get_position(&position.id).unwrap();
+    assert_eq!(remaining.size, position.size - plan.close_size);
+    assert_eq!(remaining.collateral, plan.remaining_collateral);
+    assert!(calculate_margin_ratio_bps(&remaining, price) >= config.partial_liquidation_target_bps as i128 - 1);
+    assert!(!t.market.is_liquidatable(&position.id));
+
+    // Its liquidation price is recomputed from the new collateral and size
+    assert_eq!(remaining.liquidation_price, exact_liquidation_price(&remaining));
+    assert!(remaining.liquidation_price < position.liquidation_price);
+
+    // Only the closed size leaves open interest and the vault reservation
+    assert_eq!(t.market.get_market_stats(&symbol_short!("XLM")).total_long_size, remaining.size);
+    assert_eq!(t.vault.get_reserved_usdc(), remaining.size);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Keeper Batches
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_liquidate_batch_skips_unknown_id_mid_batch() {
+    let t = TestMarket::new();
+    let keeper = Address::generate(&t.env);
+    let first = t.open(&t.trader(), 100 * PRECISION, 10, Direction::Long);
+    let second = t.open(&t.trader(), 100 * PRECISION, 10, Direction::Long);
+    t.set_price("XLM", 8_500_000);
+
+    let results = t.market.liquidate_batch(&keeper, &vec![&t.env, first.id, 999, second.id]);
+
+    let outcomes: Vec<(u64, bool, u32)> = results.iter().map(|r| (r.id, r.success, r.error_code)).collect();
+    assert_eq!(
+        outcomes,
+        [
+            (first.id, true, 0),
+            (999, false, NoetherError::PositionNotFound as u32),
+            (second.id, true, 0),
+        ]
+    );
+    assert!(t.market.get_position(&first.id).is_none());
+    assert!(t.market.get_position(&second.id).is_none());
+    assert_eq!(t.market.get_keeper_info(&keeper).liquidations, 2);
+}
+
+#[test]
+fn test_liquidate_batch_skips_failed_vault_call_without_side_effects() {
+    let t = TestMarket::new();
+    let keeper = Address::generate(&t.env);
+    let healthy_loss = t.open(&t.trader(), 100 * PRECISION, 5, Direction::Long);
+    let bad_debt = t.open(&t.trader(), 100 * PRECISION, 10, Direction::Long);
+
+    // Just past the 5x long's liquidation price the 10x long is under water
+    t.set_price("XLM", healthy_loss.liquidation_price - 1_000);
+    t.vault.reject_bad_debt();
+    let reserved = t.vault.get_reserved_usdc();
+
+    let results = t.market.liquidate_batch(&keeper, &vec![&t.env, bad_debt.id, healthy_loss.id]);
+
+    let outcomes: Vec<(u64, bool, u32)> = results.iter().map(|r| (r.id, r.success, r.error_code)).collect();
+    assert_eq!(
+        outcomes,
+        [
+            (bad_debt.id, false, NoetherError::Paused as u32),
+            (healthy_loss.id, true, 0),
+        ]
+    );
+
+    // The failed liquidation left its position and reservation untouched
+    let untouched = t.market.get_position(&bad_debt.id).unwrap();
+    assert_eq!((untouched.size, untouched.collateral), (bad_debt.size, bad_debt.collateral));
+    let released = healthy_loss.size - t.market.get_position(&healthy_loss.id).map_or(0, |p| p.size);
+    assert_eq!(t.vault.get_reserved_usdc(), reserved - released);
+    assert_eq!(t.market.get_keeper_info(&keeper).liquidations, 1);
+}
+
+#[test]
+fn test_execute_orders_batch_skips_failed_execution_without_side_effects() {
+    // Room in the vault for one 500 USDC position but not two
+    let t = TestMarket::with_liquidity(800 * PRECISION);
+    let keeper = Address::generate(&t.env);
+    let first_trader = t.trader();
+    let second_trader = t.trader();
+    let first = t.place_long_entry(&first_trader, 100 * PRECISION, PRECISION, 0);
+    let second = t.place_long_entry(&second_trader, 100 * PRECISION, PRECISION, 0);
+    let second_balance = t.usdc.balance(&second_trader);
+
+    let results = t.market.execute_orders_batch(&keeper, &vec![&t.env, first.id, 999, second.id]);
+
+    let outcomes: Vec<(u64, bool, u32)> = results.iter().map(|r| (r.id, r.success, r.error_code)).collect();
+    assert_eq!(
+        outcomes,
+        [
+            (first.id, true, 0),
+            (999, false, NoetherError::OrderNotFound as u32),
+            (second.id, false, NoetherError::InsufficientLiquidity as u32),
+        ]
+    );
+
+    // The first fill committed; the failed one left its order and escrow untouched
+    assert_eq!(t.market.get_order(&first.id).unwrap().status, OrderStatus::Executed);
+    assert_eq!(t.market.get_order(&second.id).unwrap().status, OrderStatus::Pending);
+    assert_eq!(t.market.get_positions(&second_trader, &0, &10).len(), 0);
+    assert_eq!(t.usdc.balance(&second_trader), second_balance);
+    assert_eq!(t.vault.get_reserved_usdc(), 500 * PRECISION);
+    assert_eq!(t.market.get_keeper_info(&keeper).orders_executed, 1);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Keepers
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_trailing_update_and_pnl_sync_require_registered_keeper() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+    let position = t.open(&trader, 100 * PRECISION, 5, Direction::Long);
+    let order = t.market.set_trailing_stop(&trader, &position.id, &500, &10_000, &100, &0);
+
+    t.market.set_keeper_allowlist(&true);
+    let keeper = Address::generate(&t.env);
+
+    let result = t.market.try_update_trailing_stop(&keeper, &order.id);
+    assert_eq!(result, Err(Ok(NoetherError::KeeperNotRegistered)));
+    let result = t.market.try_sync_pool_pnl(&keeper);
+    assert_eq!(result, Err(Ok(NoetherError::KeeperNotRegistered)));
+
+    t.market.register_keeper(&keeper);
+
+    // An unchanged mark is a no-op and earns no credit
+    t.market.update_trailing_stop(&keeper, &order.id);
+    assert_eq!(t.market.get_keeper_info(&keeper).trailing_updates, 0);
+
+    t.set_price("XLM", 12_000_000);
+    t.market.update_trailing_stop(&keeper, &order.id);
+    t.market.sync_pool_pnl(&keeper);
+
+    let info = t.market.get_keeper_info(&keeper);
+    assert_eq!(info.trailing_updates, 1);
+    assert_eq!(info.pnl_syncs, 1);
+    assert_eq!(info.accrued_rewards, 0);
+    assert!(t.vault.get_unrealized_pnl() > 0);
+}
+
+#[test]
+fn test_get_keepers_lists_only_registered_keepers_by_page() {
+    let t = TestMarket::new();
+    let trader = t.trader();
+
+    // Open mode: running jobs records activity but does not list the keeper
+    let runner = Address::generate(&t.env);
+    let order = t.place_long_entry(&trader, 100 * PRECISION, 9_000_000, t.now() + 60);
+    t.advance(61);
+    t.market.expire_orders(&runner, &vec![&t.env, order.id]);
+    assert_eq!(t.market.get_keeper_info(&runner).orders_expired, 1);
+    assert!(t.market.get_keepers(&None, &10).is_empty());
+
+    let keepers: Vec<Address> = (0..3).map(|_| Address::generate(&t.env)).collect();
+    for keeper in keepers.iter() {
+        t.market.register_keeper(keeper);
+    }
+    // Registering twice does not list a keeper twice
+    t.market.register_keeper(&keepers[0]);
+
+    let first = t.market.get_keepers(&None, &2);
+    assert_eq!(first, vec![&t.env, keepers[0].clone(), keepers[1].clone()]);
+    let rest = t.market.get_keepers(&Some(keepers[1].clone()), &2);
+    assert_eq!(rest, vec![&t.env, keepers[2].clone()]);
+
+    t.market.remove_keeper(&keepers[1]);
+    let listed = t.market.get_keepers(&None, &10);
+    assert_eq!(listed, vec![&t.env, keepers[0].clone(), keepers[2].clone()]);
+}
@@ -5,11 +5,12 @@
 use noether_common::{Direction, Position, PRECISION, BASIS_POINTS};
 
 /// Calculate the effective leverage of a position given current collateral.
+/// Rounded up, so a fractional 6.7x is reported as 7x rather than 6x.
 pub fn calculate_effective_leverage(size: i128, collateral: i128) -> u32 {
     if collateral <= 0 {
         return 0;
     }
-    ((size / collateral) as u32).max(1)
+    (((size + collateral - 1) / collateral) as u32).max(1)
 }
 
 /// Calculate margin ratio (collateral / size).
@@ -66,8 +67,18 @@ pub fn calculate_partial_close(
     position: &Position,
     close_percentage_bps: u32,
 ) -> (i128, i128, i128, i128) {
-    let close_size = position.size * (close_percentage_bps as i128) / (BASIS_POINTS as i128);
-    let close_collateral = position.collateral * (close_percentage_bps as i128) / (BASIS_POINTS as i128);
+    calculate_partial_close_size(position, calculate_close_size(position.size, close_percentage_bps))
+}
+
+/// Calculate partial close amounts for an absolute `close_size`.
+/// Collateral is split in proportion to size.
+/// Returns (close_collateral, close_size, remaining_collateral, remaining_size)
+pub fn calculate_partial_close_size(position: &Position, close_size: i128) -> (i128, i128, i128, i128) {
+    let close_collateral = if position.size > 0 {
+        position.collateral * close_size / position.size
+    } else {
+        0
+    };
 
     let remaining_size = position.size - close_size;
     let remaining_collateral = position.collateral - close_collateral;
@@ -75,10 +86,80 @@ pub fn calculate_partial_close(
     (close_collateral, close_size, remaining_collateral, remaining_size)
 }
 
+/// Calculate the size closed by `close_bps` of a position of `size`.
+pub fn calculate_close_size(size: i128, close_bps: u32) -> i128 {
+    size * (close_bps as i128) / (BASIS_POINTS as i128)
+}
+
+/// Check an exit price against the trader's acceptable price.
+/// Longs sell on exit, so the price must not be below it; shorts buy back,
+/// so it must not be above it. An acceptable price of 0 disables the check.
+pub fn is_exit_price_acceptable(direction: Direction, exit_price: i128, acceptable_price: i128) -> bool {
+    if acceptable_price <= 0 {
+        return true;
+    }
+
+    match direction {
+        Direction::Long => exit_price >= acceptable_price,
+        Direction::Short => exit_price <= acceptable_price,
+    }
+}
+
+/// Check an entry price against the trader's acceptable price.
+/// Longs buy on entry, so the price must not be above it; shorts sell,
+/// so it must not be below it. An acceptable price of 0 disables the check.
+pub fn is_entry_price_acceptable(direction: Direction, entry_price: i128, acceptable_price: i128) -> bool {
+    if acceptable_price <= 0 {
+        return true;
+    }
+
+    match direction {
+        Direction::Long => entry_price <= acceptable_price,
+        Direction::Short => entry_price >= acceptable_price,
+    }
+}
+
+/// Check a stop-loss trigger against the entry price.
+/// Longs stop out below entry (price falling), shorts above entry (price rising).
+pub fn is_stop_loss_trigger_valid(direction: Direction, entry_price: i128, trigger_price: i128) -> bool {
+    match direction {
+        Direction::Long => trigger_price < entry_price,
+        Direction::Short => trigger_price > entry_price,
+    }
+}
+
+/// Check a take-profit trigger against the entry price.
+/// Longs take profit above entry (price rising), shorts below entry (price falling).
+pub fn is_take_profit_trigger_valid(direction: Direction, entry_price: i128, trigger_price: i128) -> bool {
+    match direction {
+        Direction::Long => trigger_price > entry_price,
+        Direction::Short => trigger_price < entry_price,
+    }
+}
+
+/// Move a trailing stop's mark to a new price if it is more favourable.
+/// Longs track the highest price seen, shorts the lowest.
+pub fn ratchet_trailing_mark(direction: Direction, mark: i128, price: i128) -> i128 {
+    match direction {
+        Direction::Long => mark.max(price),
+        Direction::Short => mark.min(price),
+    }
+}
+
+/// Calculate the stop price a trailing distance away from the mark.
+/// Longs stop below the high-water mark, shorts above the low-water mark.
+pub fn calculate_trailing_stop_price(direction: Direction, mark: i128, distance_bps: u32) -> i128 {
+    let offset = mark * (distance_bps as i128) / (BASIS_POINTS as i128);
+    match direction {
+        Direction::Long => mark - offset,
+        Direction::Short => mark + offset,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{Env, Address, Symbol};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
     fn create_test_position(env: &Env) -> Position {
         Position {
@@ -92,8 +173,10 @@ mod tests {
             leverage: 10,
             liquidation_price: PRECISION * 91 / 100,
             timestamp: 1000000,
-            last_funding_time: 1000000,
+            funding_index: 0,
+            borrow_index: 0,
             accumulated_funding: 0,
+            accrued_borrow_fee: 0,
         }
     }
 
@@ -101,6 +184,10 @@ mod tests {
     fn test_effective_leverage() {
         let leverage = calculate_effective_leverage(1000 * PRECISION, 100 * PRECISION);
         assert_eq!(leverage, 10);
+
+        // Fractional leverage rounds up
+        let leverage = calculate_effective_leverage(1000 * PRECISION, 149 * PRECISION);
+        assert_eq!(leverage, 7);
     }
 
     #[test]
@@ -131,4 +218,73 @@ mod tests {
         assert_eq!(rem_coll, 50 * PRECISION);
         assert_eq!(rem_size, 500 * PRECISION);
     }
+
+    #[test]
+    fn test_partial_close_size() {
+        let env = Env::default();
+        let position = create_test_position(&env);
+
+        // Close an absolute 250 of 1000 size; collateral follows proportionally
+        let (close_coll, close_size, rem_coll, rem_size) = calculate_partial_close_size(&position, 250 * PRECISION);
+
+        assert_eq!(close_coll, 25 * PRECISION);
+        assert_eq!(close_size, 250 * PRECISION);
+        assert_eq!(rem_coll, 75 * PRECISION);
+        assert_eq!(rem_size, 750 * PRECISION);
+        assert_eq!(calculate_close_size(position.size, 2500), 250 * PRECISION);
+    }
+
+    #[test]
+    fn test_exit_price_acceptable() {
+        // Long exits need at least the acceptable price
+        assert!(is_exit_price_acceptable(Direction::Long, PRECISION, PRECISION * 99 / 100));
+        assert!(!is_exit_price_acceptable(Direction::Long, PRECISION * 98 / 100, PRECISION * 99 / 100));
+
+        // Short exits need at most the acceptable price
+        assert!(is_exit_price_acceptable(Direction::Short, PRECISION, PRECISION * 101 / 100));
+        assert!(!is_exit_price_acceptable(Direction::Short, PRECISION * 102 / 100, PRECISION * 101 / 100));
+
+        // Zero disables the check
+        assert!(is_exit_price_acceptable(Direction::Long, 1, 0));
+    }
+
+    #[test]
+    fn test_close_order_triggers() {
+        assert!(is_stop_loss_trigger_valid(Direction::Long, PRECISION, PRECISION * 95 / 100));
+        assert!(!is_stop_loss_trigger_valid(Direction::Long, PRECISION, PRECISION));
+        assert!(is_stop_loss_trigger_valid(Direction::Short, PRECISION, PRECISION * 105 / 100));
+
+        assert!(is_take_profit_trigger_valid(Direction::Long, PRECISION, PRECISION * 105 / 100));
+        assert!(!is_take_profit_trigger_valid(Direction::Short, PRECISION, PRECISION));
+        assert!(is_take_profit_trigger_valid(Direction::Short, PRECISION, PRECISION * 95 / 100));
+    }
+
+    #[test]
+    fn test_trailing_stop() {
+        // Long: mark only moves up, stop trails 5% below
+        let mark = ratchet_trailing_mark(Direction::Long, PRECISION, PRECISION * 110 / 100);
+        assert_eq!(mark, PRECISION * 110 / 100);
+        assert_eq!(ratchet_trailing_mark(Direction::Long, mark, PRECISION), mark);
+        assert_eq!(calculate_trailing_stop_price(Direction::Long, mark, 500), PRECISION * 1045 / 1000);
+
+        // Short: mark only moves down, stop trails 5% above
+        let mark = ratchet_trailing_mark(Direction::Short, PRECISION, PRECISION * 90 / 100);
+        assert_eq!(mark, PRECISION * 90 / 100);
+        assert_eq!(ratchet_trailing_mark(Direction::Short, mark, PRECISION), mark);
+        assert_eq!(calculate_trailing_stop_price(Direction::Short, mark, 500), PRECISION * 945 / 1000);
+    }
+
+    #[test]
+    fn test_entry_price_acceptable() {
+        // Long entries need at most the acceptable price
+        assert!(is_entry_price_acceptable(Direction::Long, PRECISION, PRECISION * 101 / 100));
+        assert!(!is_entry_price_acceptable(Direction::Long, PRECISION * 102 / 100, PRECISION * 101 / 100));
+
+        // Short entries need at least the acceptable price
+        assert!(is_entry_price_acceptable(Direction::Short, PRECISION, PRECISION * 99 / 100));
+        assert!(!is_entry_price_acceptable(Direction::Short, PRECISION * 98 / 100, PRECISION * 99 / 100));
+
+        // 0 disables the check
+        assert!(is_entry_price_acceptable(Direction::Short, 1, 0));
+    }
 }
@@ -28,6 +28,8 @@ pub enum NoetherError {
     Overflow = 6,
     /// Division by zero attempted
     DivisionByZero = 7,
+    /// Page cursor points into a shard that has emptied since; restart from 0
+    InvalidCursor = 8,
 
     // ═══════════════════════════════════════════════════════════════
     // Position Errors (20-29)
@@ -45,6 +47,10 @@ pub enum NoetherError {
     NotPositionOwner = 24,
     /// Position has insufficient margin for operation
     InsufficientMargin = 25,
+    /// Asset is not listed for trading
+    AssetNotListed = 26,
+    /// Opening would push the asset's long or short open interest over its cap
+    OpenInterestCapExceeded = 27,
 
     // ═══════════════════════════════════════════════════════════════
     // Oracle Errors (30-39)
@@ -76,6 +82,8 @@ pub enum NoetherError {
     NotLiquidatable = 50,
     /// Liquidation operation failed
     LiquidationFailed = 51,
+    /// Vault coverage is healthy; auto-deleveraging is not allowed
+    AdlNotRequired = 52,
 
     // ═══════════════════════════════════════════════════════════════
     // Funding Rate Errors (55-59)
@@ -102,6 +110,17 @@ pub enum NoetherError {
     InvalidTriggerPrice = 65,
     /// Invalid slippage tolerance (must be > 0 and <= 10000 bps)
     InvalidSlippageTolerance = 66,
-    /// Position already has this type of order attached
+    /// Position already has the maximum number of close orders attached
     OrderAlreadyExists = 67,
+    /// Order has passed its expiry time
+    OrderExpired = 68,
+
+    // ═══════════════════════════════════════════════════════════════
+    // Keeper Errors (76-79)
+    // ═══════════════════════════════════════════════════════════════
+
+    /// Keeper allowlist is enabled and caller is not a registered keeper
+    KeeperNotRegistered = 76,
+    /// Keeper has no accrued rewards to claim
+    NoKeeperRewards = 77,
 }
@@ -3,7 +3,7 @@
 //! Financial calculations for the Noether protocol.
 //! All calculations use 7 decimal precision (PRECISION = 10^7).
 
-use crate::types::{Direction, Position, PRECISION, BASIS_POINTS};
+use crate::types::{Direction, Position, PRECISION, BASIS_POINTS, FUNDING_INDEX_PRECISION};
 use crate::errors::NoetherError;
 
 /// Calculate position size from collateral and leverage.
@@ -94,7 +94,139 @@ pub fn calculate_pnl(position: &Position, current_price: i128) -> Result<i128, N
     Ok(pnl)
 }
 
-/// Calculate the net value of a position (collateral + unrealized PnL).
+/// Calculate the asset quantity a position holds: size / entry_price.
+///
+/// Summing this per side lets unrealized PnL of many positions be
+/// computed from aggregates (see `calculate_aggregate_pnl`).
+///
+/// # Returns
+/// Quantity of the asset (7 decimals)
+pub fn calculate_position_quantity(size: i128, entry_price: i128) -> i128 {
+    if entry_price == 0 {
+        return 0;
+    }
+    size * PRECISION / entry_price
+}
+
+/// Calculate total unrealized PnL of one side of an asset from aggregates.
+///
+/// # Formula
+/// Long:  pnl = price × Σquantity - Σsize
+/// Short: pnl = Σsize - price × Σquantity
+///
+/// # Arguments
+/// * `direction` - Side the aggregates belong to
+/// * `total_size` - Sum of position sizes (7 decimals)
+/// * `total_quantity` - Sum of size / entry_price (7 decimals)
+/// * `current_price` - Current market price (7 decimals)
+///
+/// # Returns
+/// Unrealized PnL of the side (positive = traders winning)
+pub fn calculate_aggregate_pnl(
+    direction: Direction,
+    total_size: i128,
+    total_quantity: i128,
+    current_price: i128,
+) -> i128 {
+    let current_value = total_quantity * current_price / PRECISION;
+
+    match direction {
+        Direction::Long => current_value - total_size,
+        Direction::Short => total_size - current_value,
+    }
+}
+
+/// Calculate skew-based price impact of a trade.
+///
+/// # Formula
+/// impact = (skew_before + skew_after) / (2 × skew_scale)
+///
+/// Skew is long minus short open interest. The impact is the average premium
+/// over the trade, so trades that grow the skew fill at a worse price and
+/// trades that shrink it fill at a better one.
+///
+/// # Arguments
+/// * `skew_before` - Long OI minus short OI before the trade (7 decimals)
+/// * `skew_delta` - Change in skew (+size for buys, -size for sells)
+/// * `skew_scale` - Skew at which the premium reaches 100% (0 disables impact)
+/// * `max_impact_bps` - Cap on the absolute impact
+///
+/// # Returns
+/// Premium over the oracle price in basis points (can be negative)
+pub fn calculate_price_impact_bps(
+    skew_before: i128,
+    skew_delta: i128,
+    skew_scale: i128,
+    max_impact_bps: u32,
+) -> i128 {
+    if skew_scale <= 0 {
+        return 0;
+    }
+
+    let skew_after = skew_before + skew_delta;
+    let impact = (skew_before + skew_after) * (BASIS_POINTS as i128) / (2 * skew_scale);
+    let max_impact = max_impact_bps as i128;
+
+    impact.clamp(-max_impact, max_impact)
+}
+
+/// Calculate the execution price of a trade including price impact.
+///
+/// # Arguments
+/// * `oracle_price` - Oracle price (7 decimals)
+/// * `skew_before` - Long OI minus short OI before the trade (7 decimals)
+/// * `skew_delta` - Change in skew (+size for buys, -size for sells)
+/// * `skew_scale` - Skew at which the premium reaches 100% (0 disables impact)
+/// * `max_impact_bps` - Cap on the absolute impact
+///
+/// # Returns
+/// (execution_price, price_impact) where price_impact is the USD cost to the
+/// trader (positive = worse than oracle, negative = better)
+pub fn calculate_execution_price(
+    oracle_price: i128,
+    skew_before: i128,
+    skew_delta: i128,
+    skew_scale: i128,
+    max_impact_bps: u32,
+) -> (i128, i128) {
+    let impact_bps = calculate_price_impact_bps(skew_before, skew_delta, skew_scale, max_impact_bps);
+    let execution_price = oracle_price + oracle_price * impact_bps / (BASIS_POINTS as i128);
+    let price_impact = skew_delta * impact_bps / (BASIS_POINTS as i128);
+
+    (execution_price, price_impact)
+}
+
+/// Calculate the volume-weighted entry price after adding size to a position.
+///
+/// # Formula
+/// avg_price = (size_a + size_b) / (size_a / price_a + size_b / price_b)
+///
+/// Sizes are USD notional, so this weights each fill by the quantity of the
+/// asset it bought and keeps the PnL of both parts unchanged.
+///
+/// # Arguments
+/// * `size_a` - Existing position size (7 decimals)
+/// * `price_a` - Existing entry price (7 decimals)
+/// * `size_b` - Added size (7 decimals)
+/// * `price_b` - Execution price of the added size (7 decimals)
+///
+/// # Returns
+/// New average entry price (7 decimals)
+pub fn calculate_average_entry_price(
+    size_a: i128,
+    price_a: i128,
+    size_b: i128,
+    price_b: i128,
+) -> Result<i128, NoetherError> {
+    if price_a <= 0 || price_b <= 0 {
+        return Err(NoetherError::InvalidPrice);
+    }
+
+    let quantity = size_a * PRECISION / price_a + size_b * PRECISION / price_b;
+    safe_div((size_a + size_b) * PRECISION, quantity)
+}
+
+/// Calculate the net value of a position (collateral + unrealized PnL - fees owed).
 ///
 /// # Arguments
 /// * `position` - The position
@@ -107,7 +239,7 @@ pub fn calculate_position_value(
     current_price: i128,
 ) -> Result<i128, NoetherError> {
     let pnl = calculate_pnl(position, current_price)?;
-    let value = position.collateral + pnl - position.accumulated_funding;
+    let value = position.collateral + pnl - position.accumulated_funding - position.accrued_borrow_fee;
     Ok(value)
 }
 
@@ -218,6 +350,86 @@ pub fn calculate_funding_payment(
     }
 }
 
+/// Calculate how much the cumulative funding index grows over a period.
+///
+/// # Formula
+/// delta = funding_rate × elapsed_seconds / 3600 / BASIS_POINTS (scaled by FUNDING_INDEX_PRECISION)
+///
+/// # Arguments
+/// * `funding_rate` - Funding rate in basis points per hour (can be negative)
+/// * `elapsed_seconds` - Seconds since the index was last updated
+///
+/// # Returns
+/// Index increment (FUNDING_INDEX_PRECISION), funding owed per unit of long size
+pub fn calculate_funding_index_delta(funding_rate: i128, elapsed_seconds: u64) -> i128 {
+    funding_rate * FUNDING_INDEX_PRECISION * (elapsed_seconds as i128)
+        / (3600 * BASIS_POINTS as i128)
+}
+
+/// Calculate funding accrued by a position between two index values.
+///
+/// # Formula
+/// accrued = size × (index_now - index_entry) / FUNDING_INDEX_PRECISION
+///
+/// # Arguments
+/// * `position_size` - Size of the position (7 decimals)
+/// * `direction` - Position direction
+/// * `entry_index` - Funding index when funding was last settled into the position
+/// * `current_index` - Current cumulative funding index
+///
+/// # Returns
+/// Funding amount to pay (positive) or receive (negative)
+pub fn calculate_accrued_funding(
+    position_size: i128,
+    direction: Direction,
+    entry_index: i128,
+    current_index: i128,
+) -> i128 {
+    let payment = position_size * (current_index - entry_index) / FUNDING_INDEX_PRECISION;
+
+    match direction {
+        Direction::Long => payment,   // Longs pay when the index rises
+        Direction::Short => -payment, // Shorts receive when the index rises
+    }
+}
+
+/// Calculate the hourly borrow rate from vault utilization.
+///
+/// # Formula
+/// rate = max_borrow_rate × utilization
+///
+/// # Arguments
+/// * `utilization_bps` - Reserved / total vault USDC in basis points
+/// * `max_borrow_rate_bps` - Hourly rate at 100% utilization in basis points
+///
+/// # Returns
+/// Hourly borrow rate in basis points with 7 decimals (PRECISION = 1 bps)
+pub fn calculate_borrow_rate(utilization_bps: u32, max_borrow_rate_bps: u32) -> i128 {
+    (max_borrow_rate_bps as i128) * PRECISION * (utilization_bps as i128) / (BASIS_POINTS as i128)
+}
+
+/// Calculate how much the cumulative borrow index grows over a period.
+///
+/// # Formula
+/// delta = borrow_rate × FUNDING_INDEX_PRECISION × elapsed / (3600 × BASIS_POINTS × PRECISION)
+///
+/// # Arguments
+/// * `borrow_rate` - Hourly rate from `calculate_borrow_rate` (PRECISION = 1 bps)
+/// * `elapsed_seconds` - Seconds since the index was last updated
+///
+/// # Returns
+/// Index increment (FUNDING_INDEX_PRECISION), fee owed per unit of size
+pub fn calculate_borrow_index_delta(borrow_rate: i128, elapsed_seconds: u64) -> i128 {
+    borrow_rate * FUNDING_INDEX_PRECISION * (elapsed_seconds as i128)
+        / (3600 * BASIS_POINTS as i128 * PRECISION)
+}
+
+/// Calculate borrow fee accrued by a position between two index values.
+/// Both longs and shorts pay; the fee goes to the vault.
+pub fn calculate_accrued_borrow_fee(position_size: i128, entry_index: i128, current_index: i128) -> i128 {
+    position_size * (current_index - entry_index) / FUNDING_INDEX_PRECISION
+}
+
 /// Calculate GLP tokens to mint for a USDC deposit.
 ///
 /// # Formula
@@ -319,7 +531,7 @@ pub fn safe_sub(a: i128, b: i128) -> Result<i128, NoetherError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{Env, Address, Symbol};
+    use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
 
     fn create_test_position(env: &Env, direction: Direction) -> Position {
         Position {
@@ -333,8 +545,10 @@ mod tests {
             leverage: 10,
             liquidation_price: 0, // Will be calculated
             timestamp: 1000000,
-            last_funding_time: 1000000,
+            funding_index: 0,
+            borrow_index: 0,
             accumulated_funding: 0,
+            accrued_borrow_fee: 0,
         }
     }
 
@@ -426,6 +640,82 @@ mod tests {
         assert_eq!(pnl, 100 * PRECISION);
     }
 
+    #[test]
+    fn test_average_entry_price() {
+        // $1000 at $1.00 plus $1000 at $2.00 = 1500 units for $2000
+        let avg = calculate_average_entry_price(
+            1000 * PRECISION, PRECISION,
+            1000 * PRECISION, 2 * PRECISION,
+        ).unwrap();
+        assert_eq!(avg, PRECISION * 4 / 3);
+
+        // Same price keeps the entry unchanged
+        let same = calculate_average_entry_price(
+            500 * PRECISION, 3 * PRECISION,
+            250 * PRECISION, 3 * PRECISION,
+        ).unwrap();
+        assert_eq!(same, 3 * PRECISION);
+    }
+
+    #[test]
+    fn test_aggregate_pnl_matches_positions() {
+        let env = Env::default();
+        let mut long_a = create_test_position(&env, Direction::Long);
+        long_a.size = 1000 * PRECISION;
+        long_a.entry_price = 50_000 * PRECISION;
+        let mut long_b = create_test_position(&env, Direction::Long);
+        long_b.size = 500 * PRECISION;
+        long_b.entry_price = 40_000 * PRECISION;
+
+        let price = 55_000 * PRECISION;
+        let expected = calculate_pnl(&long_a, price).unwrap() + calculate_pnl(&long_b, price).unwrap();
+
+        let total_size = long_a.size + long_b.size;
+        let total_quantity = calculate_position_quantity(long_a.size, long_a.entry_price)
+            + calculate_position_quantity(long_b.size, long_b.entry_price);
+        let pnl = calculate_aggregate_pnl(Direction::Long, total_size, total_quantity, price);
+        assert_eq!(pnl, expected);
+
+        // The same aggregates held short lose exactly what longs win
+        let short_pnl = calculate_aggregate_pnl(Direction::Short, total_size, total_quantity, price);
+        assert_eq!(short_pnl, -expected);
+    }
+
+    #[test]
+    fn test_price_impact_follows_skew() {
+        let scale = 1_000_000 * PRECISION;
+        let price = 100 * PRECISION;
+
+        // Balanced book: a $10k buy pays (0 + 10k) / (2 × 1M) = 50 bps
+        let (buy_price, buy_impact) =
+            calculate_execution_price(price, 0, 10_000 * PRECISION, scale, 100);
+        assert_eq!(buy_price, price * 10_050 / 10_000);
+        assert_eq!(buy_impact, 50 * PRECISION);
+
+        // Long-heavy book: a sell that reduces skew fills above oracle (better)
+        let (sell_price, sell_impact) =
+            calculate_execution_price(price, 20_000 * PRECISION, -10_000 * PRECISION, scale, 100);
+        assert!(sell_price > price);
+        assert!(sell_impact < 0);
+
+        // Impact is capped and disabled with a zero scale
+        assert_eq!(calculate_price_impact_bps(0, scale, scale, 100), 100);
+        assert_eq!(calculate_price_impact_bps(0, scale, 0, 100), 0);
+    }
+
+    #[test]
+    fn test_borrow_fee_scales_with_utilization() {
+        // 10 bps/hour max at 50% utilization = 5 bps/hour; 2 hours on $1000 = $1
+        let rate = calculate_borrow_rate(5000, 10);
+        assert_eq!(rate, 5 * PRECISION);
+
+        let delta = calculate_borrow_index_delta(rate, 7200);
+        assert_eq!(calculate_accrued_borrow_fee(1000 * PRECISION, 0, delta), PRECISION);
+
+        // No utilization, no fee
+        assert_eq!(calculate_borrow_rate(0, 10), 0);
+    }
+
     #[test]
     fn test_glp_first_deposit() {
         let usdc_amount = 1000 * PRECISION;
@@ -466,4 +756,30 @@ mod tests {
         // 50% imbalance, shorts pay longs (negative rate)
         assert!(rate < 0);
     }
+
+    #[test]
+    fn test_funding_index_accrues_partial_hours() {
+        // 10 bps/hour for 30 minutes = 5 bps on $1000 = $0.50
+        let delta = calculate_funding_index_delta(10, 1800);
+        let long = calculate_accrued_funding(1000 * PRECISION, Direction::Long, 0, delta);
+        let short = calculate_accrued_funding(1000 * PRECISION, Direction::Short, 0, delta);
+
+        assert_eq!(long, PRECISION / 2);
+        assert_eq!(short, -PRECISION / 2);
+    }
+
+    #[test]
+    fn test_funding_index_spans_rate_changes() {
+        // 1 hour at 10 bps then 1 hour at -4 bps = net 6 bps on $1000 = $0.60
+        let index_entry = 0;
+        let index_mid = index_entry + calculate_funding_index_delta(10, 3600);
+        let index_now = index_mid + calculate_funding_index_delta(-4, 3600);
+
+        let paid = calculate_accrued_funding(1000 * PRECISION, Direction::Long, index_entry, index_now);
+        assert_eq!(paid, 6 * PRECISION / 10);
+
+        // A position opened mid-way only pays the second period
+        let received = calculate_accrued_funding(1000 * PRECISION, Direction::Long, index_mid, index_now);
+        assert_eq!(received, -4 * PRECISION / 10);
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! This module defines all shared data structures used across the Noether protocol.
 
-use soroban_sdk::{contracttype, Address, Symbol};
+use soroban_sdk::{contracttype, Address, Symbol, Vec};
 
 /// Decimal precision for prices and amounts.
 /// Stellar uses 7 decimals natively, so we follow the same convention.
@@ -13,6 +13,18 @@ pub const PRECISION: i128 = 10_000_000; // 10^7
 /// 10000 basis points = 100%
 pub const BASIS_POINTS: u32 = 10_000;
 
+/// Precision of the cumulative funding index (10^14).
+/// Funding accrues per second, so the index needs more precision than prices
+/// to avoid truncating small hourly rates to zero.
+pub const FUNDING_INDEX_PRECISION: i128 = PRECISION * PRECISION;
+
+/// Maximum number of close orders (SL/TP/trailing stop) attached to one position.
+/// Bounds the per-position order list walked on close and liquidation.
+pub const MAX_POSITION_ORDERS: u32 = 10;
+
+/// Maximum number of entries returned by one page of a paginated list view.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
 /// Direction of a trading position
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
@@ -60,10 +72,15 @@ pub struct Position {
     pub liquidation_price: i128,
     /// Timestamp when position was opened (Unix seconds)
     pub timestamp: u64,
-    /// Last funding rate applied (for tracking)
-    pub last_funding_time: u64,
+    /// Asset's cumulative funding index when funding was last settled into this position
+    pub funding_index: i128,
+    /// Asset's cumulative borrow index when borrow fees were last settled into this position
+    pub borrow_index: i128,
     /// Accumulated funding payments (positive = paid, negative = received)
     pub accumulated_funding: i128,
+    /// Borrow fees owed to the vault since they were last settled; kept apart
+    /// from funding so funding received never offsets them
+    pub accrued_borrow_fee: i128,
 }
 
 /// Price data from oracles
@@ -107,23 +124,27 @@ pub struct PoolInfo {
     pub unrealized_pnl: i128,
     /// Total fees collected (7 decimals)
     pub total_fees: i128,
+    /// USDC reserved for potential payouts on open positions (7 decimals)
+    pub reserved_usdc: i128,
 }
 
-/// Market statistics
+/// Market statistics for a single asset
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct MarketStats {
-    /// Total value of all long positions (7 decimals)
+    /// Total value of all long positions in the asset (7 decimals)
     pub total_long_size: i128,
-    /// Total value of all short positions (7 decimals)
+    /// Total value of all short positions in the asset (7 decimals)
     pub total_short_size: i128,
-    /// Total number of open positions
+    /// Number of open positions in the asset
     pub open_position_count: u64,
     /// Current funding rate (basis points per hour)
     /// Positive = longs pay shorts
     /// Negative = shorts pay longs
     pub funding_rate: i128,
-    /// Last time funding was applied
+    /// Cumulative funding index (FUNDING_INDEX_PRECISION)
+    pub funding_index: i128,
+    /// Last time the funding index was updated
     pub last_funding_time: u64,
 }
 
@@ -149,6 +170,28 @@ pub struct MarketConfig {
     pub max_price_staleness: u64,
     /// Maximum allowed oracle deviation in basis points
     pub max_oracle_deviation_bps: u32,
+    /// Margin buffer above maintenance required after removing collateral (basis points)
+    pub withdraw_margin_buffer_bps: u32,
+    /// Share of trading and liquidation fees sent to the insurance fund (basis points)
+    pub insurance_fee_bps: u32,
+    /// Margin ratio a partial liquidation restores (basis points, 0 = always full)
+    pub partial_liquidation_target_bps: u32,
+    /// Margin ratio below which positions are fully liquidated (basis points)
+    pub full_liquidation_margin_bps: u32,
+    /// Vault coverage of net trader PnL below which ADL is allowed (basis points)
+    pub adl_trigger_coverage_bps: u32,
+    /// Vault coverage of net trader PnL that ADL restores (basis points)
+    pub adl_target_coverage_bps: u32,
+    /// Long/short skew in USD at which price impact reaches 100% (7 decimals, 0 = no impact)
+    pub price_impact_skew_scale: i128,
+    /// Maximum price impact in basis points
+    pub max_price_impact_bps: u32,
+    /// Hourly borrow rate at 100% vault utilization in basis points
+    pub max_borrow_rate_bps: u32,
+    /// Maximum total long open interest for the asset (7 decimals)
+    pub max_long_open_interest: i128,
+    /// Maximum total short open interest for the asset (7 decimals)
+    pub max_short_open_interest: i128,
 }
 
 impl Default for MarketConfig {
@@ -163,10 +206,31 @@ impl Default for MarketConfig {
             max_position_size: 100_000 * PRECISION,  // 100,000 USDC max position
             max_price_staleness: 60,                  // 60 seconds max staleness
             max_oracle_deviation_bps: 100,            // 1% max oracle deviation
+            withdraw_margin_buffer_bps: 100,          // 1% above maintenance margin
+            insurance_fee_bps: 1000,                  // 10% of fees to insurance fund
+            partial_liquidation_target_bps: 300,      // Restore 3% margin on partial liquidation
+            full_liquidation_margin_bps: 50,          // Full liquidation below 0.5% margin
+            adl_trigger_coverage_bps: 12_000,         // ADL below 120% coverage
+            adl_target_coverage_bps: 15_000,          // ADL back to 150% coverage
+            price_impact_skew_scale: 100_000_000 * PRECISION, // $100M skew = 100% impact
+            max_price_impact_bps: 100,                // 1% max price impact
+            max_borrow_rate_bps: 1,                   // 0.01% per hour at full utilization
+            max_long_open_interest: 5_000_000 * PRECISION,  // 5M USDC total longs
+            max_short_open_interest: 5_000_000 * PRECISION, // 5M USDC total shorts
         }
     }
 }
 
+/// A listed asset and its market parameters
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AssetInfo {
+    /// Trading asset symbol (e.g., "XLM")
+    pub asset: Symbol,
+    /// Market parameters applied to this asset
+    pub config: MarketConfig,
+}
+
 /// Asset type for oracle price queries (SEP-0040 compatible)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -207,6 +271,8 @@ pub enum OrderType {
     StopLoss = 1,
     /// Take-profit order - close position to lock in profits
     TakeProfit = 2,
+    /// Trailing stop - close position when price retraces from its best level
+    TrailingStop = 3,
 }
 
 /// Trigger condition for order execution
@@ -231,7 +297,7 @@ pub enum OrderStatus {
     Cancelled = 2,
     /// Order was cancelled due to slippage exceeded
     CancelledSlippage = 3,
-    /// Order expired (for future use)
+    /// Order passed its expiry time and was expired by a keeper
     Expired = 4,
 }
 
@@ -245,7 +311,7 @@ pub struct Order {
     pub trader: Address,
     /// Trading asset symbol (e.g., "BTC", "ETH", "XLM")
     pub asset: Symbol,
-    /// Type of order (LimitEntry, StopLoss, TakeProfit)
+    /// Type of order (LimitEntry, StopLoss, TakeProfit, TrailingStop)
     pub order_type: OrderType,
     /// Direction for the position (Long or Short) - used for LimitEntry
     pub direction: Direction,
@@ -259,17 +325,45 @@ pub struct Order {
     pub trigger_condition: TriggerCondition,
     /// Maximum allowed slippage in basis points (e.g., 100 = 1%)
     pub slippage_tolerance_bps: u32,
+    /// Fraction of the position to close in basis points (for close orders, 10000 = full close)
+    pub close_bps: u32,
+    /// Size to close, fixed from `close_bps` at placement and capped at the remaining size (0 = full close)
+    pub close_size: i128,
+    /// Trailing distance from the mark in basis points (for TrailingStop orders)
+    pub trailing_distance_bps: u32,
+    /// Best price seen since placement: high-water for longs, low-water for shorts (for TrailingStop orders)
+    pub trailing_mark: i128,
     /// Position ID this order is attached to (for SL/TP orders)
     pub position_id: u64,
     /// Whether this order is attached to a position (0 = no, position_id value if yes)
     pub has_position: bool,
     /// Timestamp when order was created (Unix seconds)
     pub created_at: u64,
+    /// Timestamp after which the order can no longer execute (Unix seconds, 0 = never)
+    pub expires_at: u64,
+    /// Prepaid keeper execution fee held in escrow until execution or cancellation (7 decimals)
+    pub execution_fee: i128,
     /// Current status of the order
     pub status: OrderStatus,
 }
 
-/// Keeper fee configuration for order execution
+/// New values for a pending order (see `modify_order`)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ModifyOrderParams {
+    /// New trigger price (7 decimals); trailing stops must pass their current trigger
+    pub trigger_price: i128,
+    /// New maximum slippage in basis points
+    pub slippage_tolerance_bps: u32,
+    /// New expiry (Unix seconds, 0 = never)
+    pub expires_at: u64,
+    /// New locked collateral, LimitEntry only (0 = unchanged)
+    pub collateral: i128,
+    /// New leverage, LimitEntry only (0 = unchanged)
+    pub leverage: u32,
+}
+
+/// Keeper fee configuration for order execution (set per asset by admin)
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct KeeperFeeConfig {
@@ -277,6 +371,8 @@ pub struct KeeperFeeConfig {
     pub base_fee: i128,
     /// Variable fee in basis points of position size - e.g., 5 = 0.05%
     pub variable_fee_bps: u32,
+    /// Fee paid from the escrowed execution fee to whoever expires an order - e.g., 1_000_000 = 0.10 USDC
+    pub expiry_fee: i128,
 }
 
 impl Default for KeeperFeeConfig {
@@ -284,6 +380,106 @@ impl Default for KeeperFeeConfig {
         Self {
             base_fee: 5_000_000,    // 0.50 USDC
             variable_fee_bps: 5,    // 0.05%
+            expiry_fee: 1_000_000,  // 0.10 USDC
         }
     }
 }
+
+/// Keeper registry entry: allowlist status, activity counters and rewards
+#[contracttype]
+#[derive(Clone, Debug, Default)]
+pub struct KeeperInfo {
+    /// Whether the admin has allowlisted this keeper
+    pub registered: bool,
+    /// Number of liquidations (full or partial) performed
+    pub liquidations: u64,
+    /// Number of positions auto-deleveraged
+    pub deleverages: u64,
+    /// Number of orders executed
+    pub orders_executed: u64,
+    /// Number of orders expired
+    pub orders_expired: u64,
+    /// Number of funding ticks applied
+    pub funding_ticks: u64,
+    /// Number of trailing stop marks ratcheted
+    pub trailing_updates: u64,
+    /// Number of pool PnL syncs pushed to the vault
+    pub pnl_syncs: u64,
+    /// Rewards earned and not yet claimed (7 decimals)
+    pub accrued_rewards: i128,
+    /// Rewards claimed to date (7 decimals)
+    pub total_claimed: i128,
+    /// Timestamp of the keeper's last job (Unix seconds)
+    pub last_active: u64,
+}
+
+/// Per-ID outcome of a keeper batch call
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BatchResult {
+    /// Position or order ID
+    pub id: u64,
+    /// Whether the ID was processed (false = skipped as ineligible or failed)
+    pub success: bool,
+    /// Keeper reward credited for this ID (7 decimals)
+    pub reward: i128,
+    /// NoetherError code explaining why the ID was skipped (0 on success)
+    pub error_code: u32,
+}
+
+/// Health of a position at the current oracle price
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PositionHealth {
+    /// Position ID
+    pub position_id: u64,
+    /// Oracle price the figures are computed at (7 decimals)
+    pub current_price: i128,
+    /// Unrealized PnL at the current price (7 decimals)
+    pub unrealized_pnl: i128,
+    /// Funding owed, including accrual since the last checkpoint (7 decimals)
+    /// Positive = trader pays
+    pub accrued_funding: i128,
+    /// Borrow fees owed, including accrual since the last checkpoint (7 decimals)
+    pub accrued_borrow_fee: i128,
+    /// Collateral + unrealized PnL - accrued funding - accrued borrow fees (7 decimals)
+    pub equity: i128,
+    /// Equity as a share of position size (basis points)
+    pub margin_ratio_bps: i128,
+    /// Price move to the liquidation price as a share of the current price (basis points, 0 = liquidatable)
+    pub distance_to_liquidation_bps: i128,
+    /// Whether the position can be liquidated now
+    pub is_liquidatable: bool,
+    /// Keeper reward a full liquidation would pay at the current price (7 decimals)
+    pub estimated_liquidation_payout: i128,
+    /// Losses beyond collateral a full liquidation would leave as bad debt (7 decimals)
+    pub estimated_bad_debt: i128,
+}
+
+/// Aggregate health of a page of a trader's open positions at current oracle prices
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AccountHealth {
+    /// Total collateral across positions (7 decimals)
+    pub total_collateral: i128,
+    /// Total position size (7 decimals)
+    pub total_size: i128,
+    /// Total unrealized PnL (7 decimals)
+    pub total_unrealized_pnl: i128,
+    /// Total funding owed (7 decimals)
+    pub total_accrued_funding: i128,
+    /// Total borrow fees owed (7 decimals)
+    pub total_accrued_borrow_fee: i128,
+    /// Total equity (7 decimals)
+    pub total_equity: i128,
+    /// Total equity as a share of total size (basis points, 10000 with no positions)
+    pub margin_ratio_bps: i128,
+    /// Smallest distance to liquidation across positions (basis points, 10000 with no positions)
+    pub min_liquidation_distance_bps: i128,
+    /// Number of positions that can be liquidated now
+    pub liquidatable_count: u32,
+    /// Total keeper reward full liquidations would pay at current prices (7 decimals)
+    pub estimated_liquidation_payout: i128,
+    /// Per-position breakdown, ordered by position ID
+    pub positions: Vec<PositionHealth>,
+}
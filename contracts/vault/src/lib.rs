@@ -41,6 +41,18 @@
 //!
 //! This design respects Soroban's authorization model where contracts
 //! can only transfer their OWN tokens, not tokens from other contracts.
+//!
+//! ## Reserved Liquidity
+//!
+//! The Market reserves each position's maximum payout when it is opened or
+//! increased and releases it when the position is closed or liquidated.
+//! LP withdrawals can only draw on `total_usdc - reserved_usdc`.
+//!
+//! ## Insurance Fund
+//!
+//! A sub-account funded by a cut of trading and liquidation fees. It is held
+//! in the vault's USDC balance but excluded from AUM, and absorbs liquidation
+//! bad debt before LPs do.
 
 #![no_std]
 
@@ -110,6 +122,8 @@ impl VaultContract {
         set_total_noe_circulating(&env, 0);
         set_unrealized_pnl(&env, 0);
         set_total_fees(&env, 0);
+        set_reserved_usdc(&env, 0);
+        set_insurance_fund(&env, 0);
         set_initialized(&env, true);
         set_paused(&env, false);
 
@@ -254,6 +268,11 @@ impl VaultContract {
             return Err(NoetherError::InsufficientLiquidity);
         }
 
+        // Liquidity reserved for open positions cannot be withdrawn
+        if gross_usdc > Self::available_liquidity_internal(&env) {
+            return Err(NoetherError::InsufficientLiquidity);
+        }
+
         // Transfer NOE from withdrawer back to vault
         noe::transfer_from_user(&env, &withdrawer, noe_amount);
 
@@ -407,13 +426,13 @@ impl VaultContract {
         Ok(())
     }
 
-    /// Reserve USDC for a position being opened.
-    /// Called when a trader opens a position to ensure liquidity exists.
+    /// Reserve USDC for a position being opened or increased.
+    /// Called by the Market so open positions cannot claim the same liquidity twice.
     ///
     /// # Arguments
-    /// * `amount` - Maximum potential payout needed for this position
+    /// * `amount` - Maximum potential payout needed for the added size
     ///
-    /// This is a check-only function - no actual fund movement.
+    /// No funds move; the amount is tracked in `reserved_usdc` until released.
     pub fn reserve_for_position(env: Env, amount: i128) -> Result<(), NoetherError> {
         require_initialized(&env)?;
 
@@ -424,21 +443,168 @@ impl VaultContract {
         let market_contract = get_market_contract(&env);
         market_contract.require_auth();
 
-        // Check we have enough liquidity to potentially pay out
-        let total_usdc = get_total_usdc(&env);
-        if amount > total_usdc {
+        // Check unreserved liquidity covers the potential payout
+        if amount > Self::available_liquidity_internal(&env) {
             return Err(NoetherError::InsufficientLiquidity);
         }
 
         // Verify actual token balance
+        let reserved = get_reserved_usdc(&env);
         let usdc_token = get_usdc_token(&env);
         let token_client = token::Client::new(&env, &usdc_token);
         let vault_balance = token_client.balance(&env.current_contract_address());
 
-        if amount > vault_balance {
+        if reserved + amount > vault_balance {
             return Err(NoetherError::InsufficientLiquidity);
         }
 
+        let new_reserved = reserved + amount;
+        set_reserved_usdc(&env, new_reserved);
+
+        env.events().publish(
+            (Symbol::new(&env, "liquidity_reserved"),),
+            (amount, new_reserved),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Release USDC reserved for a position being closed, decreased or liquidated.
+    ///
+    /// # Arguments
+    /// * `amount` - Reserved amount to release
+    ///
+    /// Releasing more than is reserved clamps the reserve at zero.
+    pub fn release_reserve(env: Env, amount: i128) -> Result<(), NoetherError> {
+        require_initialized(&env)?;
+
+        if amount <= 0 {
+            return Err(NoetherError::InvalidAmount);
+        }
+
+        let market_contract = get_market_contract(&env);
+        market_contract.require_auth();
+
+        let new_reserved = (get_reserved_usdc(&env) - amount).max(0);
+        set_reserved_usdc(&env, new_reserved);
+
+        env.events().publish(
+            (Symbol::new(&env, "liquidity_released"),),
+            (amount, new_reserved),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Market Contract Interface - Fees and Insurance
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Record trading and liquidation fees transferred by the Market.
+    ///
+    /// # Arguments
+    /// * `amount` - Fees credited to LPs (counted in AUM)
+    /// * `insurance_amount` - Fees credited to the insurance fund
+    ///
+    /// The Market transfers `amount + insurance_amount` before calling.
+    pub fn receive_fees(env: Env, amount: i128, insurance_amount: i128) -> Result<(), NoetherError> {
+        require_initialized(&env)?;
+
+        if amount < 0 || insurance_amount < 0 {
+            return Err(NoetherError::InvalidAmount);
+        }
+
+        let market_contract = get_market_contract(&env);
+        market_contract.require_auth();
+
+        set_total_fees(&env, get_total_fees(&env) + amount);
+
+        let insurance_fund = get_insurance_fund(&env) + insurance_amount;
+        set_insurance_fund(&env, insurance_fund);
+
+        env.events().publish(
+            (Symbol::new(&env, "fees_received"),),
+            (amount, insurance_amount, insurance_fund),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Record bad debt from a liquidation that lost more than its collateral.
+    /// The insurance fund covers as much as it can by moving it into the pool;
+    /// the rest is a realized loss for LPs.
+    ///
+    /// # Arguments
+    /// * `amount` - Loss beyond the position's collateral
+    ///
+    /// # Returns
+    /// Amount covered by the insurance fund
+    pub fn cover_bad_debt(env: Env, amount: i128) -> Result<i128, NoetherError> {
+        require_initialized(&env)?;
+
+        if amount <= 0 {
+            return Err(NoetherError::InvalidAmount);
+        }
+
+        let market_contract = get_market_contract(&env);
+        market_contract.require_auth();
+
+        let insurance_fund = get_insurance_fund(&env);
+        let covered = amount.min(insurance_fund);
+        let uncovered = amount - covered;
+
+        // Covered debt stays in the vault; it just moves from insurance to LPs
+        if covered > 0 {
+            set_insurance_fund(&env, insurance_fund - covered);
+            set_total_usdc(&env, get_total_usdc(&env) + covered);
+        }
+
+        set_total_bad_debt(&env, get_total_bad_debt(&env) + amount);
+        if uncovered > 0 {
+            set_uncovered_bad_debt(&env, get_uncovered_bad_debt(&env) + uncovered);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "bad_debt_absorbed"),),
+            (amount, covered, uncovered, insurance_fund - covered),
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok(covered)
+    }
+
+    /// Top up the insurance fund.
+    /// Anyone can contribute; contributions are not redeemable.
+    pub fn deposit_insurance(env: Env, depositor: Address, amount: i128) -> Result<(), NoetherError> {
+        require_initialized(&env)?;
+
+        if amount <= 0 {
+            return Err(NoetherError::InvalidAmount);
+        }
+
+        depositor.require_auth();
+
+        let usdc_token = get_usdc_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&depositor, &env.current_contract_address(), &amount);
+
+        let insurance_fund = get_insurance_fund(&env) + amount;
+        set_insurance_fund(&env, insurance_fund);
+
+        env.events().publish(
+            (Symbol::new(&env, "insurance_deposited"),),
+            (depositor, amount, insurance_fund),
+        );
+
+        extend_instance_ttl(&env);
+
         Ok(())
     }
 
@@ -456,6 +622,7 @@ impl VaultContract {
             aum: Self::calculate_aum_internal(&env),
             unrealized_pnl: get_unrealized_pnl(&env),
             total_fees: get_total_fees(&env),
+            reserved_usdc: get_reserved_usdc(&env),
         })
     }
 
@@ -491,6 +658,42 @@ impl VaultContract {
         get_total_usdc(&env)
     }
 
+    /// Get USDC reserved for open positions.
+    pub fn get_reserved_usdc(env: Env) -> i128 {
+        get_reserved_usdc(&env)
+    }
+
+    /// Get USDC available for new reservations and withdrawals.
+    pub fn get_available_liquidity(env: Env) -> i128 {
+        Self::available_liquidity_internal(&env)
+    }
+
+    /// Get pool utilization in basis points (reserved / total USDC).
+    pub fn get_utilization(env: Env) -> u32 {
+        let total_usdc = get_total_usdc(&env);
+        if total_usdc <= 0 {
+            return 0;
+        }
+
+        let utilization = get_reserved_usdc(&env) * (BASIS_POINTS as i128) / total_usdc;
+        utilization.clamp(0, BASIS_POINTS as i128) as u32
+    }
+
+    /// Get insurance fund balance.
+    pub fn get_insurance_fund(env: Env) -> i128 {
+        get_insurance_fund(&env)
+    }
+
+    /// Get cumulative bad debt from liquidations.
+    pub fn get_total_bad_debt(env: Env) -> i128 {
+        get_total_bad_debt(&env)
+    }
+
+    /// Get cumulative bad debt not covered by the insurance fund.
+    pub fn get_uncovered_bad_debt(env: Env) -> i128 {
+        get_uncovered_bad_debt(&env)
+    }
+
     /// Get actual USDC token balance held by vault.
     pub fn get_usdc_balance(env: Env) -> Result<i128, NoetherError> {
         require_initialized(&env)?;
@@ -699,6 +902,11 @@ impl VaultContract {
             aum
         }
     }
+
+    /// Calculate USDC not reserved for open positions.
+    fn available_liquidity_internal(env: &Env) -> i128 {
+        (get_total_usdc(env) - get_reserved_usdc(env)).max(0)
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -708,8 +916,200 @@ impl VaultContract {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::testutils::{Address as _, Ledger};
+    use noether_common::PRECISION;
+    use soroban_sdk::testutils::Address as _;
+
+    const NOE_SUPPLY: i128 = 10_000_000 * PRECISION;
+
+    /// A fee-free vault against Stellar asset USDC and NOE tokens, with the NOE
+    /// supply pre-minted to the vault. The market is a plain address; auths are mocked.
+    struct TestVault<'a> {
+        env: Env,
+        vault: VaultContractClient<'a>,
+        usdc: token::Client<'a>,
+        usdc_admin: token::StellarAssetClient<'a>,
+        noe: token::Client<'a>,
+    }
+
+    impl TestVault<'_> {
+        fn new() -> Self {
+            let env = Env::default();
+            env.mock_all_auths();
+
+            let admin = Address::generate(&env);
+            let usdc_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
+            let noe_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
+            let vault_address = env.register_contract(None, VaultContract);
+            let market = Address::generate(&env);
+
+            let vault = VaultContractClient::new(&env, &vault_address);
+            vault.initialize(&admin, &usdc_address, &noe_address, &market, &0, &0);
+            token::StellarAssetClient::new(&env, &noe_address).mint(&vault_address, &NOE_SUPPLY);
+
+            TestVault {
+                usdc: token::Client::new(&env, &usdc_address),
+                usdc_admin: token::StellarAssetClient::new(&env, &usdc_address),
+                noe: token::Client::new(&env, &noe_address),
+                vault,
+                env,
+            }
+        }
+
+        /// Fund a new LP and deposit `amount` USDC for them.
+        fn deposit(&self, amount: i128) -> Address {
+            let lp = Address::generate(&self.env);
+            self.usdc_admin.mint(&lp, &amount);
+            self.vault.deposit(&lp, &amount);
+            lp
+        }
+
+        /// Withdraw `noe_amount` for `lp`, approving the vault to pull the NOE first.
+        fn try_withdraw(&self, lp: &Address, noe_amount: i128) -> Result<i128, NoetherError> {
+            let expiration = self.env.ledger().sequence() + 100;
+            self.noe.approve(lp, &self.vault.address, &noe_amount, &expiration);
+            match self.vault.try_withdraw(lp, &noe_amount) {
+                Ok(Ok(amount)) => Ok(amount),
+                Err(Ok(e)) => Err(e),
+                _ => panic!("unexpected withdraw result"),
+            }
+        }
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Reserved Liquidity
+    // ═══════════════════════════════════════════════════════════════════════
 
-    // Tests will be added in integration test file
-    // as they require token contract setup
+    #[test]
+    fn test_reserve_and_release_track_reserved_usdc() {
+        let t = TestVault::new();
+        assert_eq!(t.vault.get_utilization(), 0);
+        t.deposit(1_000 * PRECISION);
+
+        t.vault.reserve_for_position(&(400 * PRECISION));
+        assert_eq!(t.vault.get_reserved_usdc(), 400 * PRECISION);
+        assert_eq!(t.vault.get_available_liquidity(), 600 * PRECISION);
+        assert_eq!(t.vault.get_utilization(), 4_000);
+
+        t.vault.release_reserve(&(150 * PRECISION));
+        assert_eq!(t.vault.get_reserved_usdc(), 250 * PRECISION);
+        assert_eq!(t.vault.get_available_liquidity(), 750 * PRECISION);
+        assert_eq!(t.vault.get_utilization(), 2_500);
+
+        // Reservations cannot claim more than the unreserved liquidity
+        let result = t.vault.try_reserve_for_position(&(751 * PRECISION));
+        assert_eq!(result, Err(Ok(NoetherError::InsufficientLiquidity)));
+        assert_eq!(t.vault.get_reserved_usdc(), 250 * PRECISION);
+    }
+
+    #[test]
+    fn test_release_reserve_rejects_invalid_amounts_and_clamps_over_release() {
+        let t = TestVault::new();
+        t.deposit(1_000 * PRECISION);
+        t.vault.reserve_for_position(&(300 * PRECISION));
+
+        assert_eq!(t.vault.try_release_reserve(&0), Err(Ok(NoetherError::InvalidAmount)));
+        assert_eq!(t.vault.try_release_reserve(&-PRECISION), Err(Ok(NoetherError::InvalidAmount)));
+        assert_eq!(t.vault.try_reserve_for_position(&0), Err(Ok(NoetherError::InvalidAmount)));
+        assert_eq!(t.vault.get_reserved_usdc(), 300 * PRECISION);
+
+        // Releasing more than is reserved never leaves a negative reserve
+        t.vault.release_reserve(&(500 * PRECISION));
+        assert_eq!(t.vault.get_reserved_usdc(), 0);
+        assert_eq!(t.vault.get_available_liquidity(), 1_000 * PRECISION);
+        assert_eq!(t.vault.get_utilization(), 0);
+    }
+
+    #[test]
+    fn test_withdraw_is_limited_to_unreserved_liquidity() {
+        let t = TestVault::new();
+        let lp = t.deposit(1_000 * PRECISION);
+        let noe_balance = t.noe.balance(&lp);
+        assert_eq!(noe_balance, 1_000 * PRECISION);
+
+        t.vault.reserve_for_position(&(900 * PRECISION));
+
+        // Withdrawing everything would draw on reserved liquidity
+        assert_eq!(t.try_withdraw(&lp, noe_balance), Err(NoetherError::InsufficientLiquidity));
+        assert_eq!(t.noe.balance(&lp), noe_balance);
+        assert_eq!(t.vault.get_total_usdc(), 1_000 * PRECISION);
+
+        // The unreserved part can be withdrawn
+        assert_eq!(t.try_withdraw(&lp, 100 * PRECISION), Ok(100 * PRECISION));
+        assert_eq!(t.usdc.balance(&lp), 100 * PRECISION);
+        assert_eq!(t.vault.get_available_liquidity(), 0);
+        assert_eq!(t.vault.get_utilization(), 10_000);
+
+        // Once the position releases its reserve the rest is withdrawable
+        t.vault.release_reserve(&(900 * PRECISION));
+        assert_eq!(t.try_withdraw(&lp, 900 * PRECISION), Ok(900 * PRECISION));
+        assert_eq!(t.vault.get_total_usdc(), 0);
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // Fees and Insurance
+    // ═══════════════════════════════════════════════════════════════════════
+
+    #[test]
+    fn test_receive_fees_splits_lp_fees_from_insurance() {
+        let t = TestVault::new();
+        t.deposit(1_000 * PRECISION);
+
+        // LP fees count towards AUM; the insurance share is held apart
+        t.vault.receive_fees(&(30 * PRECISION), &(10 * PRECISION));
+        let pool = t.vault.get_pool_info();
+        assert_eq!(pool.total_fees, 30 * PRECISION);
+        assert_eq!(pool.aum, 1_030 * PRECISION);
+        assert_eq!(t.vault.get_insurance_fund(), 10 * PRECISION);
+        assert_eq!(t.vault.get_noe_price(), PRECISION * 1_030 / 1_000);
+
+        let result = t.vault.try_receive_fees(&-PRECISION, &0);
+        assert_eq!(result, Err(Ok(NoetherError::InvalidAmount)));
+        let result = t.vault.try_receive_fees(&0, &-PRECISION);
+        assert_eq!(result, Err(Ok(NoetherError::InvalidAmount)));
+        assert_eq!(t.vault.get_insurance_fund(), 10 * PRECISION);
+    }
+
+    #[test]
+    fn test_cover_bad_debt_draws_insurance_until_exhausted() {
+        let t = TestVault::new();
+        t.deposit(1_000 * PRECISION);
+        t.vault.receive_fees(&0, &(100 * PRECISION));
+
+        // Partly covered: the fund moves what it has into the pool
+        assert_eq!(t.vault.cover_bad_debt(&(150 * PRECISION)), 100 * PRECISION);
+        assert_eq!(t.vault.get_insurance_fund(), 0);
+        assert_eq!(t.vault.get_total_usdc(), 1_100 * PRECISION);
+        assert_eq!(t.vault.get_total_bad_debt(), 150 * PRECISION);
+        assert_eq!(t.vault.get_uncovered_bad_debt(), 50 * PRECISION);
+
+        // Exhausted: further bad debt is a realized LP loss
+        assert_eq!(t.vault.cover_bad_debt(&(30 * PRECISION)), 0);
+        assert_eq!(t.vault.get_total_usdc(), 1_100 * PRECISION);
+        assert_eq!(t.vault.get_total_bad_debt(), 180 * PRECISION);
+        assert_eq!(t.vault.get_uncovered_bad_debt(), 80 * PRECISION);
+
+        assert_eq!(t.vault.try_cover_bad_debt(&0), Err(Ok(NoetherError::InvalidAmount)));
+    }
+
+    #[test]
+    fn test_deposit_insurance_tops_up_fund_outside_aum() {
+        let t = TestVault::new();
+        t.deposit(1_000 * PRECISION);
+        let donor = Address::generate(&t.env);
+        t.usdc_admin.mint(&donor, &(50 * PRECISION));
+
+        t.vault.deposit_insurance(&donor, &(50 * PRECISION));
+        assert_eq!(t.vault.get_insurance_fund(), 50 * PRECISION);
+        assert_eq!(t.usdc.balance(&donor), 0);
+        assert_eq!(t.vault.get_usdc_balance(), 1_050 * PRECISION);
+        assert_eq!(t.vault.get_aum(), 1_000 * PRECISION);
+
+        let result = t.vault.try_deposit_insurance(&donor, &0);
+        assert_eq!(result, Err(Ok(NoetherError::InvalidAmount)));
+
+        // The top-up then absorbs bad debt
+        assert_eq!(t.vault.cover_bad_debt(&(20 * PRECISION)), 20 * PRECISION);
+        assert_eq!(t.vault.get_insurance_fund(), 30 * PRECISION);
+        assert_eq!(t.vault.get_uncovered_bad_debt(), 0);
+    }
 }
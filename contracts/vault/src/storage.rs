@@ -28,6 +28,14 @@ pub enum DataKey {
     UnrealizedPnl,
     /// Total fees collected (7 decimals)
     TotalFees,
+    /// USDC reserved for potential payouts on open positions (7 decimals)
+    ReservedUsdc,
+    /// Insurance fund balance, held by the vault but excluded from AUM (7 decimals)
+    InsuranceFund,
+    /// Cumulative bad debt from liquidations (7 decimals)
+    TotalBadDebt,
+    /// Cumulative bad debt the insurance fund could not cover (7 decimals)
+    UncoveredBadDebt,
     /// Deposit fee in basis points
     DepositFeeBps,
     /// Withdrawal fee in basis points
@@ -146,6 +154,42 @@ pub fn set_total_fees(env: &Env, amount: i128) {
     env.storage().persistent().extend_ttl(&DataKey::TotalFees, 2_592_000, 2_592_000);
 }
 
+pub fn get_insurance_fund(env: &Env) -> i128 {
+    env.storage().persistent().get(&DataKey::InsuranceFund).unwrap_or(0)
+}
+
+pub fn set_insurance_fund(env: &Env, amount: i128) {
+    env.storage().persistent().set(&DataKey::InsuranceFund, &amount);
+    env.storage().persistent().extend_ttl(&DataKey::InsuranceFund, 2_592_000, 2_592_000);
+}
+
+pub fn get_total_bad_debt(env: &Env) -> i128 {
+    env.storage().persistent().get(&DataKey::TotalBadDebt).unwrap_or(0)
+}
+
+pub fn set_total_bad_debt(env: &Env, amount: i128) {
+    env.storage().persistent().set(&DataKey::TotalBadDebt, &amount);
+    env.storage().persistent().extend_ttl(&DataKey::TotalBadDebt, 2_592_000, 2_592_000);
+}
+
+pub fn get_uncovered_bad_debt(env: &Env) -> i128 {
+    env.storage().persistent().get(&DataKey::UncoveredBadDebt).unwrap_or(0)
+}
+
+pub fn set_uncovered_bad_debt(env: &Env, amount: i128) {
+    env.storage().persistent().set(&DataKey::UncoveredBadDebt, &amount);
+    env.storage().persistent().extend_ttl(&DataKey::UncoveredBadDebt, 2_592_000, 2_592_000);
+}
+
+pub fn get_reserved_usdc(env: &Env) -> i128 {
+    env.storage().persistent().get(&DataKey::ReservedUsdc).unwrap_or(0)
+}
+
+pub fn set_reserved_usdc(env: &Env, amount: i128) {
+    env.storage().persistent().set(&DataKey::ReservedUsdc, &amount);
+    env.storage().persistent().extend_ttl(&DataKey::ReservedUsdc, 2_592_000, 2_592_000);
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Authorization Helpers
 // ═══════════════════════════════════════════════════════════════════════════
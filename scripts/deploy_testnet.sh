@@ -211,7 +211,18 @@ CONFIG='{
     "base_funding_rate_bps": 1,
     "max_position_size": 1000000000000,
     "max_price_staleness": 60,
-    "max_oracle_deviation_bps": 100
+    "max_oracle_deviation_bps": 100,
+    "withdraw_margin_buffer_bps": 100,
+    "insurance_fee_bps": 1000,
+    "partial_liquidation_target_bps": 300,
+    "full_liquidation_margin_bps": 50,
+    "adl_trigger_coverage_bps": 12000,
+    "adl_target_coverage_bps": 15000,
+    "price_impact_skew_scale": 1000000000000000,
+    "max_price_impact_bps": 100,
+    "max_borrow_rate_bps": 1,
+    "max_long_open_interest": 50000000000000,
+    "max_short_open_interest": 50000000000000
 }'
 
 $CLI contract invoke \
@@ -225,6 +236,17 @@ $CLI contract invoke \
     --usdc_token "$USDC_TOKEN_ID" \
     --config "$CONFIG"
 echo -e "${GREEN}✓ Market initialized${NC}"
+
+for ASSET in XLM BTC ETH; do
+    $CLI contract invoke \
+        --id "$MARKET_ID" \
+        --source "$IDENTITY" \
+        --network testnet \
+        -- list_asset \
+        --asset "$ASSET" \
+        --config "$CONFIG"
+done
+echo -e "${GREEN}✓ Assets listed (XLM, BTC, ETH)${NC}"
 echo ""
 
 # ═══════════════════════════════════════════════════════════════════════════════
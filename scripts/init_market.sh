@@ -1,7 +1,10 @@
 #!/bin/bash
 
+MARKET_ID=CD4ZEYKAS6OICSECQDTRZU3GDIJYTJYO7UMRP6KULXPHOD6SXGNMHMMO
+CONFIG='{"min_collateral":"100000000","max_leverage":10,"maintenance_margin_bps":100,"liquidation_fee_bps":500,"trading_fee_bps":10,"base_funding_rate_bps":1,"max_position_size":"1000000000000","max_price_staleness":60,"max_oracle_deviation_bps":100,"withdraw_margin_buffer_bps":100,"insurance_fee_bps":1000,"partial_liquidation_target_bps":300,"full_liquidation_margin_bps":50,"adl_trigger_coverage_bps":12000,"adl_target_coverage_bps":15000,"price_impact_skew_scale":"1000000000000000","max_price_impact_bps":100,"max_borrow_rate_bps":1,"max_long_open_interest":"50000000000000","max_short_open_interest":"50000000000000"}'
+
 stellar contract invoke \
-    --id CD4ZEYKAS6OICSECQDTRZU3GDIJYTJYO7UMRP6KULXPHOD6SXGNMHMMO \
+    --id "$MARKET_ID" \
     --source-account noether_admin \
     --network testnet \
     -- \
@@ -10,4 +13,15 @@ stellar contract invoke \
     --oracle_adapter CBDH7R4PBFHMN4AER74O4RG7VHUWUMFI67UKDIY6ISNQP4H5KFKMSBS4 \
     --vault CB2KKOV3DL3KCBIB272ITDUY3LIBD3RLMR3WZ2VAPNUZV3HIVKHT43SG \
     --usdc_token CA63EPM4EEXUVUANF6FQUJEJ37RWRYIXCARWFXYUMPP7RLZWFNLTVNR4 \
-    --config '{"min_collateral":"100000000","max_leverage":10,"maintenance_margin_bps":100,"liquidation_fee_bps":500,"trading_fee_bps":10,"base_funding_rate_bps":1,"max_position_size":"1000000000000","max_price_staleness":60,"max_oracle_deviation_bps":100}'
+    --config "$CONFIG"
+
+for ASSET in XLM BTC ETH; do
+    stellar contract invoke \
+        --id "$MARKET_ID" \
+        --source-account noether_admin \
+        --network testnet \
+        -- \
+        list_asset \
+        --asset "$ASSET" \
+        --config "$CONFIG"
+done
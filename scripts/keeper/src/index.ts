@@ -13,8 +13,8 @@
  */
 
 import { loadConfig } from './config';
-import { StellarClient } from './stellar';
-import { KeeperConfig, KeeperStats, PriceData, AssetConfig } from './types';
+import { StellarClient, PAGE_SIZE } from './stellar';
+import { KeeperConfig, KeeperStats, PriceData, AssetConfig, Order } from './types';
 
 // ASCII art banner
 const BANNER = `
@@ -41,6 +41,7 @@ class KeeperBot {
   private stats: KeeperStats;
   private lastOracleUpdate: number = 0;
   private lastFundingApplication: number = 0;
+  private lastPnlSync: number = 0;
   private currentPrices: Map<string, PriceData> = new Map();
 
   constructor() {
@@ -53,6 +54,7 @@ class KeeperBot {
       ordersExecuted: 0,
       ordersCancelledSlippage: 0,
       ordersSkippedOrphaned: 0,
+      ordersExpired: 0,
       totalRewardsEarned: BigInt(0),
       errors: 0,
     };
@@ -124,6 +126,7 @@ class KeeperBot {
     console.log(`  Orders Executed:       ${this.stats.ordersExecuted}`);
     console.log(`  Orders Cancelled:      ${this.stats.ordersCancelledSlippage} (slippage)`);
     console.log(`  Orders Skipped:        ${this.stats.ordersSkippedOrphaned} (orphaned - position closed)`);
+    console.log(`  Orders Expired:        ${this.stats.ordersExpired}`);
     console.log(`  Total Rewards:         ${this.formatAmount(this.stats.totalRewardsEarned)} USDC`);
     console.log(`  Errors:                ${this.stats.errors}`);
     console.log('═'.repeat(80) + '\n');
@@ -148,16 +151,27 @@ class KeeperBot {
     // 2. Check and execute liquidations
     await this.checkLiquidations();
 
-    // 3. Check and execute orders
+    // 3. Auto-deleverage winners if vault coverage is too low
+    await this.checkAutoDeleverage();
+
+    // 4. Check and execute orders
     await this.checkOrders();
 
-    // 4. Apply funding rate (every hour)
+    // 5. Apply funding rate and claim accrued rewards (every hour)
     const ONE_HOUR = 60 * 60 * 1000;
     if (now - this.lastFundingApplication >= ONE_HOUR) {
-      this.applyFundingRate().catch(e => console.error('Funding rate error:', e));
+      this.applyFundingRate()
+        .then(() => this.claimRewards())
+        .catch(e => console.error('Funding rate error:', e));
       this.lastFundingApplication = now;
     }
 
+    // 6. Sync pool unrealized PnL to the vault (after each oracle update)
+    if (this.lastPnlSync < this.lastOracleUpdate) {
+      this.lastPnlSync = now;
+      this.syncPoolPnl().catch(e => console.error('Pool PnL sync error:', e));
+    }
+
     // Status line
     const priceStr = this.config.assets
       .map(a => {
@@ -250,40 +264,70 @@ class KeeperBot {
 
     if (positionIds.length === 0) return;
 
+    const liquidatableIds: bigint[] = [];
+
     for (const positionId of positionIds) {
       try {
         const isLiquidatable = await this.stellar.isLiquidatable(positionId);
 
         if (isLiquidatable) {
           console.log(`\n⚠️  Position ${positionId} is liquidatable!`);
-          await this.executeLiquidation(positionId);
+          liquidatableIds.push(positionId);
         }
       } catch (error) {
         // Position might have been closed, ignore
       }
     }
+
+    if (liquidatableIds.length > 0) {
+      await this.executeLiquidations(liquidatableIds);
+    }
   }
 
   /**
-   * Execute a liquidation
+   * Trigger auto-deleveraging one page of candidates at a time; the contract
+   * rejects it once coverage is healthy
    */
-  private async executeLiquidation(positionId: bigint): Promise<void> {
-    console.log(`   Executing liquidation for position ${positionId}...`);
+  private async checkAutoDeleverage(): Promise<void> {
+    const positionIds = await this.stellar.getAllPositionIds();
 
-    const result = await this.stellar.liquidate(positionId);
+    for (let i = 0; i < positionIds.length; i += PAGE_SIZE) {
+      const result = await this.stellar.autoDeleverage(positionIds.slice(i, i + PAGE_SIZE));
 
-    if (result.success) {
-      this.stats.liquidationsExecuted++;
-      if (result.reward) {
-        this.stats.totalRewardsEarned += result.reward;
+      if (result.success) {
+        console.log(`\n⚖️  Auto-deleveraging executed (tx: ${result.txHash})`);
+      } else {
+        if (!result.error?.includes('AdlNotRequired') && !result.error?.includes('#52')) {
+          console.log(`\n   ❌ Auto-deleveraging failed: ${result.error}`);
+        }
+        return;
       }
-      console.log(`   ✅ Liquidation successful!`);
-      console.log(`   Transaction: ${result.txHash}`);
-      if (result.reward) {
-        console.log(`   Reward: ${this.formatAmount(result.reward)} USDC`);
+    }
+  }
+
+  /**
+   * Liquidate positions in a single batch transaction
+   */
+  private async executeLiquidations(positionIds: bigint[]): Promise<void> {
+    console.log(`   Executing liquidation batch for position(s) ${positionIds.join(', ')}...`);
+
+    const result = await this.stellar.liquidateBatch(positionIds);
+
+    if (!result.success) {
+      console.log(`   ❌ Liquidation batch failed: ${result.error}`);
+      return;
+    }
+
+    console.log(`   Transaction: ${result.txHash}`);
+    for (const r of result.batchResults ?? []) {
+      if (r.success) {
+        this.stats.liquidationsExecuted++;
+        this.stats.totalRewardsEarned += r.reward;
+        console.log(`   ✅ Position ${r.id} liquidated (reward: ${this.formatAmount(r.reward)} USDC)`);
+      } else {
+        // Already closed or recovered since the check - nothing to do
+        console.log(`   ⚠️  Position ${r.id} skipped (error #${r.error_code})`);
       }
-    } else {
-      console.log(`   ❌ Liquidation failed: ${result.error}`);
     }
   }
 
@@ -299,53 +343,113 @@ class KeeperBot {
 
     if (orderIds.length === 0) return;
 
+    const now = BigInt(Math.floor(Date.now() / 1000));
+    const expiredIds: bigint[] = [];
+    const triggered = new Map<bigint, string>(); // order ID -> order type
+
     for (const orderId of orderIds) {
       try {
+        const order = await this.stellar.getOrder(orderId);
+        if (!order) continue;
+
+        // Expired orders can no longer execute; sweep them in one call below
+        if (order.expires_at > BigInt(0) && order.expires_at <= now) {
+          expiredIds.push(orderId);
+          continue;
+        }
+
+        // Trailing stops follow the best price seen; ratchet before checking the trigger
+        if (order.order_type === 'TrailingStop') {
+          await this.ratchetTrailingStop(orderId, order);
+        }
+
         const shouldExecute = await this.stellar.shouldExecuteOrder(orderId);
 
         if (shouldExecute) {
-          const order = await this.stellar.getOrder(orderId);
-          if (order) {
-            console.log(`\n📋 Order ${orderId} triggered! (${order.order_type} ${order.direction} ${order.asset})`);
-            await this.executeOrder(orderId, order.order_type);
-          }
+          console.log(`\n📋 Order ${orderId} triggered! (${order.order_type} ${order.direction} ${order.asset})`);
+          triggered.set(orderId, order.order_type);
         }
       } catch (error) {
         // Order might have been cancelled or executed, ignore
       }
     }
+
+    if (triggered.size > 0) {
+      await this.executeOrders(triggered);
+    }
+
+    if (expiredIds.length > 0) {
+      await this.expireOrders(expiredIds);
+    }
+  }
+
+  /**
+   * Move a trailing stop's mark if the price has made a new high (long) or low (short)
+   */
+  private async ratchetTrailingStop(orderId: bigint, order: Order): Promise<void> {
+    const price = this.currentPrices.get(order.asset);
+    if (!price) return;
+
+    const improved = order.direction === 'Long'
+      ? price.priceScaled > order.trailing_mark
+      : price.priceScaled < order.trailing_mark;
+    if (!improved) return;
+
+    const result = await this.stellar.updateTrailingStop(orderId);
+    if (result.success) {
+      console.log(`   📈 Trailing stop ${orderId} mark moved to ${this.formatAmount(price.priceScaled)}`);
+    }
   }
 
   /**
-   * Execute a triggered order
+   * Expire stale orders, refunding their locked collateral
    */
-  private async executeOrder(orderId: bigint, orderType: string): Promise<void> {
-    console.log(`   Executing order ${orderId}...`);
+  private async expireOrders(orderIds: bigint[]): Promise<void> {
+    console.log(`\n⌛ Expiring ${orderIds.length} order(s)...`);
 
-    const result = await this.stellar.executeOrder(orderId);
+    const result = await this.stellar.expireOrders(orderIds);
 
     if (result.success) {
-      // Check if order was cancelled due to slippage (reward = 0)
-      if (result.reward === BigInt(0)) {
+      this.stats.ordersExpired += orderIds.length;
+      console.log(`   ✅ Orders expired: ${orderIds.join(', ')}`);
+    } else {
+      console.log(`   ❌ Expiring orders failed: ${result.error}`);
+    }
+  }
+
+  /**
+   * Execute triggered orders in a single batch transaction
+   */
+  private async executeOrders(orders: Map<bigint, string>): Promise<void> {
+    const orderIds = [...orders.keys()];
+    console.log(`   Executing order batch for order(s) ${orderIds.join(', ')}...`);
+
+    const result = await this.stellar.executeOrdersBatch(orderIds);
+
+    if (!result.success) {
+      console.log(`   ❌ Order batch execution failed: ${result.error}`);
+      return;
+    }
+
+    console.log(`   Transaction: ${result.txHash}`);
+    for (const r of result.batchResults ?? []) {
+      if (r.success && r.reward === BigInt(0)) {
+        // Cancelled instead of executed: slippage exceeded, or a limit entry
+        // that would breach the open interest cap
         this.stats.ordersCancelledSlippage++;
-        console.log(`   ⚠️  Order ${orderId} cancelled due to slippage exceeded (collateral refunded)`);
-      } else {
+        console.log(`   ⚠️  Order ${r.id} cancelled (slippage exceeded or open interest cap reached, collateral refunded)`);
+      } else if (r.success) {
         this.stats.ordersExecuted++;
-        this.stats.totalRewardsEarned += result.reward!;
-        console.log(`   ✅ Order executed successfully!`);
-        console.log(`   Transaction: ${result.txHash}`);
-        console.log(`   Keeper fee: ${this.formatAmount(result.reward!)} USDC`);
-      }
-    } else {
-      // Handle PositionNotFound (Error #20) for SL/TP orders
-      // This happens when the position was already closed (manually, liquidated, or by another SL/TP)
-      // The order is orphaned but harmless - just skip it
-      if (result.error?.includes('PositionNotFound') || result.error?.includes('#20')) {
+        this.stats.totalRewardsEarned += r.reward;
+        console.log(`   ✅ Order ${r.id} executed (keeper fee: ${this.formatAmount(r.reward)} USDC)`);
+      } else if (r.error_code === 20) {
+        // PositionNotFound: the market cancels attached orders when a position closes,
+        // so this only affects orders left behind by older contract versions
         this.stats.ordersSkippedOrphaned++;
-        console.log(`   ⚠️  Order ${orderId} skipped: Position already closed (manually or liquidated)`);
-        console.log(`      This ${orderType} order is now orphaned and will be ignored.`);
+        console.log(`   ⚠️  Order ${r.id} skipped: Position already closed (manually or liquidated)`);
+        console.log(`      This ${orders.get(r.id)} order is now orphaned and will be ignored.`);
       } else {
-        console.log(`   ❌ Order execution failed: ${result.error}`);
+        console.log(`   ⚠️  Order ${r.id} skipped (error #${r.error_code})`);
       }
     }
   }
@@ -355,19 +459,47 @@ class KeeperBot {
   // ═══════════════════════════════════════════════════════════════════════
 
   /**
-   * Apply funding rate
+   * Apply funding rate for every monitored asset
    */
   private async applyFundingRate(): Promise<void> {
     console.log('\n⏰ Applying hourly funding rate...');
 
-    const result = await this.stellar.applyFunding();
+    for (const asset of this.config.assets) {
+      const result = await this.stellar.applyFunding(asset.symbol);
+
+      if (result.success) {
+        console.log(`   ✅ Funding rate applied for ${asset.symbol}`);
+      } else if (result.error?.includes('FundingIntervalNotElapsed') || result.error?.includes('#55')) {
+        // Not yet time, ignore silently
+      } else {
+        console.log(`   ❌ Funding rate application failed for ${asset.symbol}: ${result.error}`);
+      }
+    }
+  }
+
+  /**
+   * Withdraw accrued keeper rewards from the market contract
+   */
+  private async claimRewards(): Promise<void> {
+    const result = await this.stellar.claimKeeperRewards();
 
     if (result.success) {
-      console.log('   ✅ Funding rate applied');
-    } else if (result.error?.includes('FundingIntervalNotElapsed') || result.error?.includes('#55')) {
-      // Not yet time, ignore silently
+      console.log('   💰 Keeper rewards claimed');
+    } else if (result.error?.includes('NoKeeperRewards') || result.error?.includes('#77')) {
+      // Nothing accrued yet, ignore silently
     } else {
-      console.log(`   ❌ Funding rate application failed: ${result.error}`);
+      console.log(`   ❌ Keeper reward claim failed: ${result.error}`);
+    }
+  }
+
+  /**
+   * Push aggregate unrealized PnL to the vault so NOE pricing stays current
+   */
+  private async syncPoolPnl(): Promise<void> {
+    const result = await this.stellar.syncPoolPnl();
+
+    if (!result.success) {
+      console.log(`\n   ❌ Pool PnL sync failed: ${result.error}`);
     }
   }
 
@@ -18,11 +18,12 @@ import {
   nativeToScVal,
   Account,
 } from '@stellar/stellar-sdk';
-import { KeeperConfig, Position, Order, ExecutionResult } from './types';
+import { KeeperConfig, Position, Order, ExecutionResult, BatchResult } from './types';
 
 const MAX_RETRIES = 3;
 const RETRY_DELAY_MS = 2000;
 const TX_TIMEOUT_SECONDS = 300;
+export const PAGE_SIZE = 100; // Matches MAX_PAGE_SIZE in the market contract
 
 export class StellarClient {
   private server: rpc.Server;
@@ -90,15 +91,11 @@ export class StellarClient {
   // ═══════════════════════════════════════════════════════════════════════
 
   /**
-   * Get all position IDs
+   * Get all position IDs, reading the contract's index page by page
    */
   async getAllPositionIds(): Promise<bigint[]> {
     try {
-      return await this.invokeContractRead<bigint[]>(
-        this.marketContract,
-        'get_all_position_ids',
-        []
-      );
+      return await this.readAllIds('get_all_position_ids');
     } catch (error) {
       console.error('Error fetching position IDs:', error);
       return [];
@@ -150,32 +147,46 @@ export class StellarClient {
     );
   }
 
+  /**
+   * Liquidate several positions in one transaction; ineligible IDs are skipped
+   */
+  async liquidateBatch(positionIds: bigint[]): Promise<ExecutionResult> {
+    const result = await this.invokeContractWriteWithRetry(
+      this.marketContract,
+      'liquidate_batch',
+      [
+        new Address(this.publicKey).toScVal(),
+        nativeToScVal(positionIds, { type: 'u64' }),
+      ]
+    );
+    return this.withBatchResults(result);
+  }
+
+  /**
+   * Force-reduce winning positions when vault coverage is too low.
+   * The contract ranks the candidates itself and takes at most PAGE_SIZE.
+   */
+  async autoDeleverage(positionIds: bigint[]): Promise<ExecutionResult> {
+    return this.invokeContractWriteWithRetry(
+      this.marketContract,
+      'auto_deleverage',
+      [
+        new Address(this.publicKey).toScVal(),
+        nativeToScVal(positionIds, { type: 'u64' }),
+      ]
+    );
+  }
+
   // ═══════════════════════════════════════════════════════════════════════
   // Order Functions
   // ═══════════════════════════════════════════════════════════════════════
 
   /**
-   * Get all pending order IDs
+   * Get all pending order IDs, reading the contract's index page by page
    */
   async getAllOrderIds(): Promise<bigint[]> {
     try {
-      const result = await this.invokeContractRead<any>(
-        this.marketContract,
-        'get_all_order_ids',
-        []
-      );
-
-      // Convert to bigint array if needed
-      if (Array.isArray(result)) {
-        const converted = result.map(id => {
-          if (typeof id === 'bigint') return id;
-          if (typeof id === 'number') return BigInt(id);
-          if (typeof id === 'string') return BigInt(id);
-          return BigInt(0);
-        });
-        return converted;
-      }
-      return [];
+      return await this.readAllIds('get_all_order_ids');
     } catch (error) {
       return [];
     }
@@ -227,18 +238,94 @@ export class StellarClient {
     );
   }
 
+  /**
+   * Execute several triggered orders in one transaction; ineligible IDs are skipped
+   */
+  async executeOrdersBatch(orderIds: bigint[]): Promise<ExecutionResult> {
+    const result = await this.invokeContractWriteWithRetry(
+      this.marketContract,
+      'execute_orders_batch',
+      [
+        new Address(this.publicKey).toScVal(),
+        nativeToScVal(orderIds, { type: 'u64' }),
+      ]
+    );
+    return this.withBatchResults(result);
+  }
+
+  /**
+   * Ratchet a trailing stop's mark to the current oracle price
+   */
+  async updateTrailingStop(orderId: bigint): Promise<ExecutionResult> {
+    return this.invokeContractWriteWithRetry(
+      this.marketContract,
+      'update_trailing_stop',
+      [
+        new Address(this.publicKey).toScVal(),
+        nativeToScVal(orderId, { type: 'u64' }),
+      ]
+    );
+  }
+
+  /**
+   * Expire pending orders past their expiry time (accrues the expiry fee to keeper rewards)
+   */
+  async expireOrders(orderIds: bigint[]): Promise<ExecutionResult> {
+    return this.invokeContractWriteWithRetry(
+      this.marketContract,
+      'expire_orders',
+      [
+        new Address(this.publicKey).toScVal(),
+        nativeToScVal(orderIds, { type: 'u64' }),
+      ]
+    );
+  }
+
   // ═══════════════════════════════════════════════════════════════════════
   // Funding Rate Functions
   // ═══════════════════════════════════════════════════════════════════════
 
   /**
-   * Apply funding rate (hourly)
+   * Apply funding rate for an asset (hourly)
    */
-  async applyFunding(): Promise<ExecutionResult> {
+  async applyFunding(asset: string): Promise<ExecutionResult> {
     return this.invokeContractWriteWithRetry(
       this.marketContract,
       'apply_funding',
-      []
+      [
+        new Address(this.publicKey).toScVal(),
+        nativeToScVal(asset, { type: 'symbol' }),
+      ]
+    );
+  }
+
+  // ═══════════════════════════════════════════════════════════════════════
+  // Keeper Registry Functions
+  // ═══════════════════════════════════════════════════════════════════════
+
+  /**
+   * Withdraw rewards accrued from liquidations, order executions and expiries
+   */
+  async claimKeeperRewards(): Promise<ExecutionResult> {
+    return this.invokeContractWriteWithRetry(
+      this.marketContract,
+      'claim_keeper_rewards',
+      [new Address(this.publicKey).toScVal()]
+    );
+  }
+
+  // ═══════════════════════════════════════════════════════════════════════
+  // Pool PnL Functions
+  // ═══════════════════════════════════════════════════════════════════════
+
+  /**
+   * Push aggregate unrealized PnL of open positions to the vault
+   */
+  async syncPoolPnl(): Promise<ExecutionResult> {
+    return this.invokeContractWriteWithRetry(
+      this.marketContract,
+      'sync_pool_pnl',
+      [new Address(this.publicKey).toScVal()]
     );
   }
 
@@ -299,6 +386,8 @@ export class StellarClient {
             lastError.includes('OrderNotTriggered') ||
             lastError.includes('NotLiquidatable') ||
             lastError.includes('PositionNotFound') ||
+            lastError.includes('AdlNotRequired') ||
+            lastError.includes('#52') ||
             lastError.includes('#20')) {
           return { success: false, error: lastError };
         }
@@ -365,19 +454,77 @@ export class StellarClient {
     if (getResponse.status === 'SUCCESS') {
       // Try to extract reward from return value
       let reward: bigint | undefined;
+      let returnValue: unknown;
       if (getResponse.returnValue) {
         try {
-          reward = scValToNative(getResponse.returnValue) as bigint;
+          returnValue = scValToNative(getResponse.returnValue);
+          if (typeof returnValue === 'bigint') {
+            reward = returnValue;
+          }
         } catch {
           // Ignore parse errors
         }
       }
-      return { success: true, txHash: sendResponse.hash, reward };
+      return { success: true, txHash: sendResponse.hash, reward, returnValue };
     } else {
       throw new Error(`Transaction failed: ${getResponse.status}`);
     }
   }
 
+  /**
+   * Follow a paginated ID view (start_after, limit) until the last page.
+   * The contract rejects a cursor whose IDs were all removed between pages
+   * (InvalidCursor); the scan then restarts once from the beginning.
+   */
+  private async readAllIds(method: string, restarted = false): Promise<bigint[]> {
+    const ids: bigint[] = [];
+    let startAfter = BigInt(0);
+
+    for (;;) {
+      let page: (bigint | number)[];
+      try {
+        page = await this.invokeContractRead<(bigint | number)[]>(
+          this.marketContract,
+          method,
+          [
+            nativeToScVal(startAfter, { type: 'u64' }),
+            nativeToScVal(PAGE_SIZE, { type: 'u32' }),
+          ]
+        );
+      } catch (error) {
+        const message = String(error);
+        if (!restarted && startAfter > BigInt(0) && (message.includes('InvalidCursor') || message.includes('#8)'))) {
+          return this.readAllIds(method, true);
+        }
+        throw error;
+      }
+      const pageIds = (page ?? []).map(id => BigInt(id));
+      ids.push(...pageIds);
+
+      if (pageIds.length < PAGE_SIZE) break;
+      startAfter = pageIds[pageIds.length - 1];
+    }
+
+    return ids;
+  }
+
+  /**
+   * Decode the per-ID results returned by a batch entry point
+   */
+  private withBatchResults(result: ExecutionResult): ExecutionResult {
+    if (!result.success || !Array.isArray(result.returnValue)) return result;
+
+    const batchResults: BatchResult[] = result.returnValue.map((raw: any) => ({
+      id: BigInt(raw.id),
+      success: Boolean(raw.success),
+      reward: BigInt(raw.reward),
+      error_code: Number(raw.error_code),
+    }));
+    const reward = batchResults.reduce((sum, r) => sum + r.reward, BigInt(0));
+
+    return { ...result, reward, batchResults };
+  }
+
   /**
    * Parse raw position data from contract
    */
@@ -393,8 +540,10 @@ export class StellarClient {
       leverage: Number(raw.leverage),
       liquidation_price: BigInt(raw.liquidation_price),
       timestamp: BigInt(raw.timestamp),
-      last_funding_time: BigInt(raw.last_funding_time),
+      funding_index: BigInt(raw.funding_index),
+      borrow_index: BigInt(raw.borrow_index),
       accumulated_funding: BigInt(raw.accumulated_funding),
+      accrued_borrow_fee: BigInt(raw.accrued_borrow_fee),
     };
   }
 
@@ -406,6 +555,7 @@ export class StellarClient {
       0: 'LimitEntry',
       1: 'StopLoss',
       2: 'TakeProfit',
+      3: 'TrailingStop',
     };
 
     const statusMap: Record<number, Order['status']> = {
@@ -427,9 +577,15 @@ export class StellarClient {
       trigger_price: BigInt(raw.trigger_price),
       trigger_condition: raw.trigger_condition === 0 ? 'Above' : 'Below',
       slippage_tolerance_bps: Number(raw.slippage_tolerance_bps),
+      close_bps: Number(raw.close_bps),
+      close_size: BigInt(raw.close_size),
+      trailing_distance_bps: Number(raw.trailing_distance_bps),
+      trailing_mark: BigInt(raw.trailing_mark),
       position_id: BigInt(raw.position_id),
       has_position: Boolean(raw.has_position),
       created_at: BigInt(raw.created_at),
+      expires_at: BigInt(raw.expires_at),
+      execution_fee: BigInt(raw.execution_fee),
       status: statusMap[raw.status] || 'Pending',
     };
   }
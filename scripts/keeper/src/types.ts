@@ -6,7 +6,7 @@
 export type Direction = 'Long' | 'Short';
 
 // Order type matching contract
-export type OrderType = 'LimitEntry' | 'StopLoss' | 'TakeProfit';
+export type OrderType = 'LimitEntry' | 'StopLoss' | 'TakeProfit' | 'TrailingStop';
 
 // Order status matching contract
 export type OrderStatus = 'Pending' | 'Executed' | 'Cancelled' | 'CancelledSlippage' | 'Expired';
@@ -26,8 +26,10 @@ export interface Position {
   leverage: number;
   liquidation_price: bigint;
   timestamp: bigint;
-  last_funding_time: bigint;
+  funding_index: bigint;
+  borrow_index: bigint;
   accumulated_funding: bigint;
+  accrued_borrow_fee: bigint;
 }
 
 // Order from contract
@@ -42,9 +44,15 @@ export interface Order {
   trigger_price: bigint;
   trigger_condition: TriggerCondition;
   slippage_tolerance_bps: number;
+  close_bps: number; // Fraction of the position a close order closes (10000 = full)
+  close_size: bigint; // Size a close order closes, fixed at placement (0 = full)
+  trailing_distance_bps: number;
+  trailing_mark: bigint; // High-water (long) / low-water (short) price for trailing stops
   position_id: bigint;
   has_position: boolean;
   created_at: bigint;
+  expires_at: bigint; // 0 = never expires
+  execution_fee: bigint; // Prepaid keeper fee held in escrow (7 decimals)
   status: OrderStatus;
 }
 
@@ -64,6 +72,7 @@ export interface KeeperStats {
   ordersExecuted: number;
   ordersCancelledSlippage: number;
   ordersSkippedOrphaned: number;
+  ordersExpired: number;
   totalRewardsEarned: bigint;
   errors: number;
 }
@@ -73,9 +82,19 @@ export interface ExecutionResult {
   success: boolean;
   txHash?: string;
   reward?: bigint;
+  returnValue?: unknown; // Decoded contract return value
+  batchResults?: BatchResult[];
   error?: string;
 }
 
+// Per-ID outcome of liquidate_batch / execute_orders_batch
+export interface BatchResult {
+  id: bigint;
+  success: boolean; // false = skipped as ineligible
+  reward: bigint;
+  error_code: number; // NoetherError code, 0 on success
+}
+
 // Asset configuration
 export interface AssetConfig {
   symbol: string;
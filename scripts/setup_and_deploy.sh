@@ -233,7 +233,7 @@ echo -e "${GREEN}✓${NC}"
 
 # Initialize Market (with config struct)
 echo -n "  Initializing Market... "
-CONFIG='{"min_collateral":100000000,"max_leverage":10,"maintenance_margin_bps":100,"liquidation_fee_bps":500,"trading_fee_bps":10,"base_funding_rate_bps":1,"max_position_size":1000000000000,"max_price_staleness":60,"max_oracle_deviation_bps":100}'
+CONFIG='{"min_collateral":100000000,"max_leverage":10,"maintenance_margin_bps":100,"liquidation_fee_bps":500,"trading_fee_bps":10,"base_funding_rate_bps":1,"max_position_size":1000000000000,"max_price_staleness":60,"max_oracle_deviation_bps":100,"withdraw_margin_buffer_bps":100,"insurance_fee_bps":1000,"partial_liquidation_target_bps":300,"full_liquidation_margin_bps":50,"adl_trigger_coverage_bps":12000,"adl_target_coverage_bps":15000,"price_impact_skew_scale":1000000000000000,"max_price_impact_bps":100,"max_borrow_rate_bps":1,"max_long_open_interest":50000000000000,"max_short_open_interest":50000000000000}'
 $CLI contract invoke --id "$MARKET_ID" $SOURCE_ARG --network testnet \
     -- initialize \
     --admin "$ADMIN_PUBLIC_KEY" \
@@ -243,6 +243,16 @@ $CLI contract invoke --id "$MARKET_ID" $SOURCE_ARG --network testnet \
     --config "$CONFIG" >/dev/null 2>&1
 echo -e "${GREEN}✓${NC}"
 
+# List tradeable assets
+echo -n "  Listing assets... "
+for ASSET in XLM BTC ETH; do
+    $CLI contract invoke --id "$MARKET_ID" $SOURCE_ARG --network testnet \
+        -- list_asset \
+        --asset "$ASSET" \
+        --config "$CONFIG" >/dev/null 2>&1
+done
+echo -e "${GREEN}✓${NC}"
+
 echo ""
 
 # ═══════════════════════════════════════════════════════════════════════════════
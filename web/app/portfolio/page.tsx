@@ -5,14 +5,15 @@ import { Header } from '@/components/layout';
 import { WalletProvider } from '@/components/wallet';
 import { AccountHealth, PnlChart, AssetAllocation, PortfolioHistory } from '@/components/portfolio';
 import { useWallet } from '@/lib/hooks/useWallet';
-import { getPositions, toDisplayPosition, getTradeHistory } from '@/lib/stellar/market';
+import { getPositions, toDisplayPosition, getTradeHistory, getAccountHealth } from '@/lib/stellar/market';
 import { getPrice, priceToDisplay } from '@/lib/stellar/oracle';
-import type { DisplayPosition, Trade } from '@/types';
+import type { DisplayPosition, Trade, AccountHealthInfo } from '@/types';
 
 function PortfolioPage() {
   const { isConnected, publicKey } = useWallet();
 
   const [positions, setPositions] = useState<DisplayPosition[]>([]);
+  const [health, setHealth] = useState<AccountHealthInfo | null>(null);
   const [trades, setTrades] = useState<Trade[]>([]);
   const [isLoadingPositions, setIsLoadingPositions] = useState(true);
   const [isLoadingTrades, setIsLoadingTrades] = useState(true);
@@ -22,14 +23,19 @@ function PortfolioPage() {
   const fetchPositions = useCallback(async () => {
     if (!publicKey) {
       setPositions([]);
+      setHealth(null);
       setIsLoadingPositions(false);
       return;
     }
 
     setIsLoadingPositions(true);
     try {
-      // Fetch positions from contract
-      const contractPositions = await getPositions(publicKey);
+      // Fetch positions and their on-chain health from contract
+      const [contractPositions, accountHealth] = await Promise.all([
+        getPositions(publicKey),
+        getAccountHealth(publicKey),
+      ]);
+      setHealth(accountHealth);
 
       if (contractPositions.length === 0) {
         setPositions([]);
@@ -88,6 +94,7 @@ function PortfolioPage() {
       fetchTrades();
     } else {
       setPositions([]);
+      setHealth(null);
       setTrades([]);
       setIsLoadingPositions(false);
       setIsLoadingTrades(false);
@@ -114,6 +121,7 @@ function PortfolioPage() {
           {/* Row 1 - Account Health */}
           <AccountHealth
             positions={positions}
+            health={health}
             usdcBalance={usdcBalance}
             isConnected={isConnected}
           />
@@ -2,10 +2,11 @@
 
 import { ArrowUpRight, ArrowDownRight, Wallet, TrendingUp, Shield } from 'lucide-react';
 import { cn } from '@/lib/utils/cn';
-import type { DisplayPosition } from '@/types';
+import type { DisplayPosition, AccountHealthInfo } from '@/types';
 
 interface AccountHealthProps {
   positions: DisplayPosition[];
+  health?: AccountHealthInfo | null; // On-chain totals, including accrued funding
   usdcBalance: number;
   isConnected: boolean;
   onDeposit?: () => void;
@@ -14,13 +15,14 @@ interface AccountHealthProps {
 
 export function AccountHealth({
   positions,
+  health,
   usdcBalance,
   isConnected,
 }: AccountHealthProps) {
-  // Calculate totals from positions
-  const totalCollateral = positions.reduce((sum, p) => sum + p.collateral, 0);
-  const totalUnrealizedPnl = positions.reduce((sum, p) => sum + p.pnl, 0);
-  const netWorth = usdcBalance + totalCollateral + totalUnrealizedPnl;
+  // Prefer the contract's totals; fall back to summing display positions
+  const totalCollateral = health?.totalCollateral ?? positions.reduce((sum, p) => sum + p.collateral, 0);
+  const totalUnrealizedPnl = health?.totalUnrealizedPnl ?? positions.reduce((sum, p) => sum + p.pnl, 0);
+  const netWorth = usdcBalance + (health?.totalEquity ?? totalCollateral + totalUnrealizedPnl);
   const buyingPower = usdcBalance;
 
   // Calculate margin usage (collateral used / total value)
@@ -78,6 +78,7 @@ export function OrdersList({
       case 'LimitEntry':
         return <ArrowDownCircle className="w-4 h-4" />;
       case 'StopLoss':
+      case 'TrailingStop':
         return <Shield className="w-4 h-4" />;
       case 'TakeProfit':
         return <Target className="w-4 h-4" />;
@@ -94,6 +95,8 @@ export function OrdersList({
         return 'SL';
       case 'TakeProfit':
         return 'TP';
+      case 'TrailingStop':
+        return 'TSL';
       default:
         return orderType;
     }
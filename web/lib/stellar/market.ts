@@ -1,9 +1,12 @@
 import { marketContract, usdcTokenContract, buildTransaction, submitTransaction, toScVal, rpc as sorobanRpc } from './client';
-import type { Position, DisplayPosition, MarketConfig, Direction, Trade, Order, DisplayOrder, OrderType, TriggerCondition, OrderStatus } from '@/types';
+import type { Position, DisplayPosition, MarketConfig, Direction, Trade, Order, DisplayOrder, OrderType, TriggerCondition, OrderStatus, PositionHealth, AccountHealthInfo } from '@/types';
 import { fromPrecision, calculatePnL } from '@/lib/utils/format';
 import { rpc, scValToNative, xdr, Horizon, TransactionBuilder, BASE_FEE } from '@stellar/stellar-sdk';
 import { CONTRACTS, NETWORK } from '@/lib/utils/constants';
 
+// Page size for the contract's paginated list views (matches MAX_PAGE_SIZE)
+const PAGE_SIZE = 100;
+
 /**
  * Raw position data from contract (before parsing)
  * Contract uses snake_case and enum indices
@@ -135,6 +138,7 @@ export async function openPosition(
     collateral: bigint;
     leverage: number;
     direction: Direction;
+    acceptablePrice?: bigint; // Worst entry price to accept (0 / omitted = no limit)
   }
 ): Promise<Position> {
   // Step 1: Check current allowance
@@ -172,13 +176,14 @@ export async function openPosition(
   console.log('[DEBUG] Step 3: Opening position...');
 
   // Build arguments matching contract signature:
-  // open_position(trader: Address, asset: Symbol, collateral: i128, leverage: u32, direction: Direction)
+  // open_position(trader: Address, asset: Symbol, collateral: i128, leverage: u32, direction: Direction, acceptable_price: i128)
   const args = [
     toScVal(signerPublicKey, 'address'),  // trader: Address
     toScVal(params.asset, 'symbol'),       // asset: Symbol (e.g., "XLM", "BTC")
     toScVal(params.collateral, 'i128'),    // collateral: i128 (7 decimals)
     toScVal(params.leverage, 'u32'),       // leverage: u32 (1-10)
     toScVal(params.direction, 'direction'), // direction: Direction enum (Long=0, Short=1)
+    toScVal(params.acceptablePrice ?? BigInt(0), 'i128'), // acceptable_price: i128 (0 = no limit)
   ];
 
   const xdrStr = await buildTransaction(signerPublicKey, marketContract, 'open_position', args);
@@ -200,7 +205,8 @@ export async function openPosition(
 export async function closePosition(
   signerPublicKey: string,
   signTransaction: (xdr: string) => Promise<string>,
-  positionId: number
+  positionId: number,
+  acceptablePrice: bigint = BigInt(0) // Worst exit price to accept (0 = no limit)
 ): Promise<{ pnl: bigint; fee: bigint }> {
   // Step 1: Check current allowance
   console.log('[DEBUG] Close Position - Step 1: Checking USDC allowance...');
@@ -238,10 +244,11 @@ export async function closePosition(
   // Step 3: Close the position
   console.log('[DEBUG] Step 3: Closing position...');
 
-  // Contract signature: close_position(trader: Address, position_id: u64)
+  // Contract signature: close_position(trader: Address, position_id: u64, acceptable_price: i128)
   const args = [
     toScVal(signerPublicKey, 'address'),  // trader: Address
     toScVal(positionId, 'u64'),            // position_id: u64 (not u32!)
+    toScVal(acceptablePrice, 'i128'),      // acceptable_price: i128 (0 = no limit)
   ];
 
   const xdrStr = await buildTransaction(signerPublicKey, marketContract, 'close_position', args);
@@ -278,23 +285,34 @@ export async function addCollateral(
 }
 
 /**
- * Get all positions for a trader (read-only)
+ * Get all positions for a trader (read-only), following the contract's pages
  */
 export async function getPositions(traderPublicKey: string): Promise<Position[]> {
   try {
-    const args = [toScVal(traderPublicKey, 'address')];
+    const positions: Position[] = [];
+    let startAfter = 0;
 
-    const result = await sorobanRpc.simulateTransaction(
-      await buildSimulateTransaction(traderPublicKey, 'get_positions', args)
-    );
+    for (;;) {
+      const args = [
+        toScVal(traderPublicKey, 'address'),
+        toScVal(startAfter, 'u64'),
+        toScVal(PAGE_SIZE, 'u32'),
+      ];
+
+      const result = await sorobanRpc.simulateTransaction(
+        await buildSimulateTransaction(traderPublicKey, 'get_positions', args)
+      );
+
+      if (!rpc.Api.isSimulationSuccess(result) || !result.result?.retval) break;
 
-    if (rpc.Api.isSimulationSuccess(result) && result.result?.retval) {
       const rawPositions = scValToNative(result.result.retval) as RawPosition[];
-      console.log('[DEBUG] Raw positions from contract:', rawPositions);
-      return rawPositions.map(parsePosition);
+      positions.push(...rawPositions.map(parsePosition));
+
+      if (rawPositions.length < PAGE_SIZE) break;
+      startAfter = Number(rawPositions[rawPositions.length - 1].id);
     }
 
-    return [];
+    return positions;
   } catch (error) {
     console.error('Error fetching positions:', error);
     return [];
@@ -408,7 +426,7 @@ export function toDisplayPosition(
 
 /**
  * Raw event data for position_closed from contract (NEW FORMAT)
- * Contract emits: (position_id, trader, asset, direction, size, entry_price, exit_price, pnl, funding_paid)
+ * Contract emits: (position_id, trader, asset, direction, size, entry_price, exit_price, pnl, funding_paid, price_impact)
  */
 interface RawPositionClosedEvent {
   0: number | bigint;  // position_id
@@ -420,6 +438,7 @@ interface RawPositionClosedEvent {
   6: bigint;           // exit_price
   7: bigint;           // pnl
   8: bigint;           // funding_paid (fee)
+  9?: bigint;          // price_impact (USD cost to trader)
 }
 
 /**
@@ -657,16 +676,22 @@ interface RawOrder {
   id: number | bigint;
   trader: string;
   asset: string;
-  order_type: number | bigint; // 0 = LimitEntry, 1 = StopLoss, 2 = TakeProfit
+  order_type: number | bigint; // 0 = LimitEntry, 1 = StopLoss, 2 = TakeProfit, 3 = TrailingStop
   direction: number | bigint; // 0 = Long, 1 = Short
   collateral: bigint;
   leverage: number | bigint;
   trigger_price: bigint;
   trigger_condition: number | bigint; // 0 = Above, 1 = Below
   slippage_tolerance_bps: number | bigint;
+  close_bps: number | bigint;
+  close_size: bigint;
+  trailing_distance_bps: number | bigint;
+  trailing_mark: bigint;
   position_id: number | bigint;
   has_position: boolean;
   created_at: number | bigint;
+  expires_at: number | bigint; // 0 = never expires
+  execution_fee: bigint;
   status: number | bigint; // 0 = Pending, 1 = Executed, 2 = Cancelled, 3 = CancelledSlippage, 4 = Expired
 }
 
@@ -678,6 +703,7 @@ function parseOrder(raw: RawOrder): Order {
     0: 'LimitEntry',
     1: 'StopLoss',
     2: 'TakeProfit',
+    3: 'TrailingStop',
   };
 
   const statusMap: Record<number, OrderStatus> = {
@@ -699,9 +725,15 @@ function parseOrder(raw: RawOrder): Order {
     triggerPrice: raw.trigger_price,
     triggerCondition: Number(raw.trigger_condition) === 0 ? 'Above' : 'Below',
     slippageToleranceBps: Number(raw.slippage_tolerance_bps),
+    closeBps: Number(raw.close_bps),
+    closeSize: raw.close_size,
+    trailingDistanceBps: Number(raw.trailing_distance_bps),
+    trailingMark: raw.trailing_mark,
     positionId: Number(raw.position_id),
     hasPosition: raw.has_position,
     createdAt: Number(raw.created_at),
+    expiresAt: Number(raw.expires_at),
+    executionFee: raw.execution_fee,
     status: statusMap[Number(raw.status)] || 'Pending',
   };
 }
@@ -725,9 +757,15 @@ export function toDisplayOrder(order: Order): DisplayOrder {
     triggerPrice,
     triggerCondition: order.triggerCondition,
     slippageToleranceBps: order.slippageToleranceBps,
+    closeBps: order.closeBps,
+    closeSize: bigIntToNumber(order.closeSize),
+    trailingDistanceBps: order.trailingDistanceBps,
+    trailingMark: bigIntToNumber(order.trailingMark),
     positionId: order.positionId,
     hasPosition: order.hasPosition,
     createdAt: new Date(order.createdAt * 1000),
+    expiresAt: order.expiresAt > 0 ? new Date(order.expiresAt * 1000) : null,
+    executionFee: bigIntToNumber(order.executionFee),
     status: order.status,
     positionSize,
   };
@@ -739,7 +777,7 @@ export function toDisplayOrder(order: Order): DisplayOrder {
 
 /**
  * Place a limit entry order
- * Locks collateral immediately, executes when trigger price is reached
+ * Locks collateral plus a prepaid keeper execution fee, executes when trigger price is reached
  */
 export async function placeLimitOrder(
   signerPublicKey: string,
@@ -752,6 +790,7 @@ export async function placeLimitOrder(
     triggerPrice: bigint;
     triggerCondition: TriggerCondition;
     slippageToleranceBps: number;
+    expiresAt?: number; // Unix seconds (0 / omitted = never expires)
   }
 ): Promise<Order> {
   // Step 1: Check current allowance
@@ -780,7 +819,7 @@ export async function placeLimitOrder(
   // Step 3: Place the limit order
   console.log('[DEBUG] Step 3: Placing limit order...');
 
-  // Contract signature: place_limit_order(trader, asset, direction, collateral, leverage, trigger_price, trigger_above, slippage_tolerance_bps)
+  // Contract signature: place_limit_order(trader, asset, direction, collateral, leverage, trigger_price, trigger_above, slippage_tolerance_bps, expires_at)
   // trigger_above is a boolean: true = trigger when price >= trigger_price, false = trigger when price <= trigger_price
   const args = [
     toScVal(signerPublicKey, 'address'),
@@ -791,6 +830,7 @@ export async function placeLimitOrder(
     toScVal(params.triggerPrice, 'i128'),
     toScVal(params.triggerCondition === 'Above', 'bool'),  // trigger_above: bool
     toScVal(params.slippageToleranceBps, 'u32'),
+    toScVal(params.expiresAt ?? 0, 'u64'),                  // expires_at: u64 (0 = never)
   ];
 
   const xdrStr = await buildTransaction(signerPublicKey, marketContract, 'place_limit_order', args);
@@ -815,17 +855,21 @@ export async function setStopLoss(
   params: {
     positionId: number;
     triggerPrice: bigint;
+    closeBps?: number; // Fraction of the position to close (omitted = 10000, full close)
     slippageToleranceBps: number;
+    expiresAt?: number; // Unix seconds (0 / omitted = never expires)
   }
 ): Promise<Order> {
   console.log('[DEBUG] Setting stop-loss for position:', params.positionId);
 
-  // Contract signature: set_stop_loss(trader, position_id, trigger_price, slippage_tolerance_bps)
+  // Contract signature: set_stop_loss(trader, position_id, trigger_price, close_bps, slippage_tolerance_bps, expires_at)
   const args = [
     toScVal(signerPublicKey, 'address'),
     toScVal(params.positionId, 'u64'),
     toScVal(params.triggerPrice, 'i128'),
+    toScVal(params.closeBps ?? 10000, 'u32'),
     toScVal(params.slippageToleranceBps, 'u32'),
+    toScVal(params.expiresAt ?? 0, 'u64'),
   ];
 
   const xdrStr = await buildTransaction(signerPublicKey, marketContract, 'set_stop_loss', args);
@@ -850,17 +894,21 @@ export async function setTakeProfit(
   params: {
     positionId: number;
     triggerPrice: bigint;
+    closeBps?: number; // Fraction of the position to close (omitted = 10000, full close)
     slippageToleranceBps: number;
+    expiresAt?: number; // Unix seconds (0 / omitted = never expires)
   }
 ): Promise<Order> {
   console.log('[DEBUG] Setting take-profit for position:', params.positionId);
 
-  // Contract signature: set_take_profit(trader, position_id, trigger_price, slippage_tolerance_bps)
+  // Contract signature: set_take_profit(trader, position_id, trigger_price, close_bps, slippage_tolerance_bps, expires_at)
   const args = [
     toScVal(signerPublicKey, 'address'),
     toScVal(params.positionId, 'u64'),
     toScVal(params.triggerPrice, 'i128'),
+    toScVal(params.closeBps ?? 10000, 'u32'),
     toScVal(params.slippageToleranceBps, 'u32'),
+    toScVal(params.expiresAt ?? 0, 'u64'),
   ];
 
   const xdrStr = await buildTransaction(signerPublicKey, marketContract, 'set_take_profit', args);
@@ -876,6 +924,45 @@ export async function setTakeProfit(
   throw new Error('Failed to set take-profit');
 }
 
+/**
+ * Set a trailing stop for an existing position
+ */
+export async function setTrailingStop(
+  signerPublicKey: string,
+  signTransaction: (xdr: string) => Promise<string>,
+  params: {
+    positionId: number;
+    trailingDistanceBps: number;
+    closeBps?: number; // Fraction of the position to close (omitted = 10000, full close)
+    slippageToleranceBps: number;
+    expiresAt?: number; // Unix seconds (0 / omitted = never expires)
+  }
+): Promise<Order> {
+  console.log('[DEBUG] Setting trailing stop for position:', params.positionId);
+
+  // Contract signature: set_trailing_stop(trader, position_id, trailing_distance_bps, close_bps, slippage_tolerance_bps, expires_at)
+  const args = [
+    toScVal(signerPublicKey, 'address'),
+    toScVal(params.positionId, 'u64'),
+    toScVal(params.trailingDistanceBps, 'u32'),
+    toScVal(params.closeBps ?? 10000, 'u32'),
+    toScVal(params.slippageToleranceBps, 'u32'),
+    toScVal(params.expiresAt ?? 0, 'u64'),
+  ];
+
+  const xdrStr = await buildTransaction(signerPublicKey, marketContract, 'set_trailing_stop', args);
+  const signedXdr = await signTransaction(xdrStr);
+  const result = await submitTransaction(signedXdr);
+
+  if (result.status === 'SUCCESS' && result.returnValue) {
+    console.log('[DEBUG] Trailing stop set successfully!');
+    const rawOrder = scValToNative(result.returnValue) as RawOrder;
+    return parseOrder(rawOrder);
+  }
+
+  throw new Error('Failed to set trailing stop');
+}
+
 /**
  * Cancel a pending order
  */
@@ -905,23 +992,95 @@ export async function cancelOrder(
 }
 
 /**
- * Get all orders for a trader (read-only)
+ * Amend a pending order in place (trigger, slippage, expiry; collateral/leverage for limit entries)
+ * Collateral increases are pulled from the trader, decreases are refunded
+ */
+export async function modifyOrder(
+  signerPublicKey: string,
+  signTransaction: (xdr: string) => Promise<string>,
+  params: {
+    orderId: number;
+    triggerPrice: bigint;
+    slippageToleranceBps: number;
+    expiresAt?: number; // Unix seconds (0 / omitted = never expires)
+    collateral?: bigint; // LimitEntry only (omitted = unchanged)
+    leverage?: number; // LimitEntry only (omitted = unchanged)
+  }
+): Promise<Order> {
+  console.log('[DEBUG] Modifying order:', params.orderId);
+
+  // Contract signature: modify_order(trader, order_id, params: ModifyOrderParams)
+  // Struct fields must be in alphabetical order for Soroban struct matching
+  const modifyParams = xdr.ScVal.scvMap([
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('collateral'),
+      val: toScVal(params.collateral ?? BigInt(0), 'i128'),
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('expires_at'),
+      val: toScVal(params.expiresAt ?? 0, 'u64'),
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('leverage'),
+      val: toScVal(params.leverage ?? 0, 'u32'),
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('slippage_tolerance_bps'),
+      val: toScVal(params.slippageToleranceBps, 'u32'),
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('trigger_price'),
+      val: toScVal(params.triggerPrice, 'i128'),
+    }),
+  ]);
+  const args = [
+    toScVal(signerPublicKey, 'address'),
+    toScVal(params.orderId, 'u64'),
+    modifyParams,
+  ];
+
+  const xdrStr = await buildTransaction(signerPublicKey, marketContract, 'modify_order', args);
+  const signedXdr = await signTransaction(xdrStr);
+  const result = await submitTransaction(signedXdr);
+
+  if (result.status === 'SUCCESS' && result.returnValue) {
+    console.log('[DEBUG] Order modified successfully!');
+    const rawOrder = scValToNative(result.returnValue) as RawOrder;
+    return parseOrder(rawOrder);
+  }
+
+  throw new Error('Failed to modify order');
+}
+
+/**
+ * Get all orders for a trader (read-only), following the contract's pages
  */
 export async function getOrders(traderPublicKey: string): Promise<Order[]> {
   try {
-    const args = [toScVal(traderPublicKey, 'address')];
+    const orders: Order[] = [];
+    let startAfter = 0;
 
-    const result = await sorobanRpc.simulateTransaction(
-      await buildSimulateTransaction(traderPublicKey, 'get_orders', args)
-    );
+    for (;;) {
+      const args = [
+        toScVal(traderPublicKey, 'address'),
+        toScVal(startAfter, 'u64'),
+        toScVal(PAGE_SIZE, 'u32'),
+      ];
+
+      const result = await sorobanRpc.simulateTransaction(
+        await buildSimulateTransaction(traderPublicKey, 'get_orders', args)
+      );
+
+      if (!rpc.Api.isSimulationSuccess(result) || !result.result?.retval) break;
 
-    if (rpc.Api.isSimulationSuccess(result) && result.result?.retval) {
       const rawOrders = scValToNative(result.result.retval) as RawOrder[];
-      console.log('[DEBUG] Raw orders from contract:', rawOrders);
-      return rawOrders.map(parseOrder);
+      orders.push(...rawOrders.map(parseOrder));
+
+      if (rawOrders.length < PAGE_SIZE) break;
+      startAfter = Number(rawOrders[rawOrders.length - 1].id);
     }
 
-    return [];
+    return orders;
   } catch (error) {
     console.error('Error fetching orders:', error);
     return [];
@@ -933,16 +1092,26 @@ export async function getOrders(traderPublicKey: string): Promise<Order[]> {
  */
 export async function getAllOrderIds(publicKey: string): Promise<number[]> {
   try {
-    const result = await sorobanRpc.simulateTransaction(
-      await buildSimulateTransaction(publicKey, 'get_all_order_ids', [])
-    );
+    const ids: number[] = [];
+    let startAfter = 0;
 
-    if (rpc.Api.isSimulationSuccess(result) && result.result?.retval) {
-      const ids = scValToNative(result.result.retval) as (number | bigint)[];
-      return ids.map(id => Number(id));
+    for (;;) {
+      const args = [toScVal(startAfter, 'u64'), toScVal(PAGE_SIZE, 'u32')];
+
+      const result = await sorobanRpc.simulateTransaction(
+        await buildSimulateTransaction(publicKey, 'get_all_order_ids', args)
+      );
+
+      if (!rpc.Api.isSimulationSuccess(result) || !result.result?.retval) break;
+
+      const page = (scValToNative(result.result.retval) as (number | bigint)[]).map(id => Number(id));
+      ids.push(...page);
+
+      if (page.length < PAGE_SIZE) break;
+      startAfter = page[page.length - 1];
     }
 
-    return [];
+    return ids;
   } catch (error) {
     console.error('Error fetching all order IDs:', error);
     return [];
@@ -998,47 +1167,100 @@ export async function getAllPendingOrders(publicKey: string): Promise<Order[]> {
 }
 
 /**
- * Get stop-loss order for a position (read-only)
+ * Get close orders (SL/TP/trailing stop) attached to a position (read-only)
  */
-export async function getPositionStopLoss(
+export async function getPositionOrders(
   publicKey: string,
   positionId: number
-): Promise<Order | null> {
+): Promise<Order[]> {
   try {
     const args = [toScVal(positionId, 'u64')];
 
     const result = await sorobanRpc.simulateTransaction(
-      await buildSimulateTransaction(publicKey, 'get_position_sl', args)
+      await buildSimulateTransaction(publicKey, 'get_position_orders', args)
     );
 
     if (rpc.Api.isSimulationSuccess(result) && result.result?.retval) {
-      const rawOrder = scValToNative(result.result.retval) as RawOrder | null;
-      return rawOrder ? parseOrder(rawOrder) : null;
+      const rawOrders = scValToNative(result.result.retval) as RawOrder[];
+      return rawOrders.map(parseOrder);
     }
 
-    return null;
+    return [];
   } catch {
-    return null;
+    return [];
   }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// Health Functions
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/**
+ * Raw position health from contract (snake_case, 7 decimals)
+ */
+interface RawPositionHealth {
+  position_id: number | bigint;
+  current_price: bigint;
+  unrealized_pnl: bigint;
+  accrued_funding: bigint;
+  accrued_borrow_fee: bigint;
+  equity: bigint;
+  margin_ratio_bps: bigint;
+  distance_to_liquidation_bps: bigint;
+  is_liquidatable: boolean;
+  estimated_liquidation_payout: bigint;
+  estimated_bad_debt: bigint;
+}
+
 /**
- * Get take-profit order for a position (read-only)
+ * Raw account health from contract (snake_case, 7 decimals)
  */
-export async function getPositionTakeProfit(
+interface RawAccountHealth {
+  total_collateral: bigint;
+  total_size: bigint;
+  total_unrealized_pnl: bigint;
+  total_accrued_funding: bigint;
+  total_accrued_borrow_fee: bigint;
+  total_equity: bigint;
+  margin_ratio_bps: bigint;
+  min_liquidation_distance_bps: bigint;
+  liquidatable_count: number | bigint;
+  estimated_liquidation_payout: bigint;
+  positions: RawPositionHealth[];
+}
+
+function parsePositionHealth(raw: RawPositionHealth): PositionHealth {
+  return {
+    positionId: Number(raw.position_id),
+    currentPrice: bigIntToNumber(raw.current_price),
+    unrealizedPnl: bigIntToNumber(raw.unrealized_pnl),
+    accruedFunding: bigIntToNumber(raw.accrued_funding),
+    accruedBorrowFee: bigIntToNumber(raw.accrued_borrow_fee),
+    equity: bigIntToNumber(raw.equity),
+    marginRatioBps: Number(raw.margin_ratio_bps),
+    distanceToLiquidationBps: Number(raw.distance_to_liquidation_bps),
+    isLiquidatable: raw.is_liquidatable,
+    estimatedLiquidationPayout: bigIntToNumber(raw.estimated_liquidation_payout),
+    estimatedBadDebt: bigIntToNumber(raw.estimated_bad_debt),
+  };
+}
+
+/**
+ * Get a position's margin ratio, equity and liquidation distance at the current price (read-only)
+ */
+export async function getPositionHealth(
   publicKey: string,
   positionId: number
-): Promise<Order | null> {
+): Promise<PositionHealth | null> {
   try {
     const args = [toScVal(positionId, 'u64')];
 
     const result = await sorobanRpc.simulateTransaction(
-      await buildSimulateTransaction(publicKey, 'get_position_tp', args)
+      await buildSimulateTransaction(publicKey, 'get_position_health', args)
     );
 
     if (rpc.Api.isSimulationSuccess(result) && result.result?.retval) {
-      const rawOrder = scValToNative(result.result.retval) as RawOrder | null;
-      return rawOrder ? parseOrder(rawOrder) : null;
+      return parsePositionHealth(scValToNative(result.result.retval) as RawPositionHealth);
     }
 
     return null;
@@ -1047,6 +1269,63 @@ export async function getPositionTakeProfit(
   }
 }
 
+/**
+ * Get the aggregate health of a trader's positions at current prices (read-only).
+ * The contract reports one page of positions at a time; totals are summed across pages.
+ */
+export async function getAccountHealth(traderPublicKey: string): Promise<AccountHealthInfo | null> {
+  try {
+    const pages: RawAccountHealth[] = [];
+    let startAfter = 0;
+
+    for (;;) {
+      const args = [
+        toScVal(traderPublicKey, 'address'),
+        toScVal(startAfter, 'u64'),
+        toScVal(PAGE_SIZE, 'u32'),
+      ];
+
+      const result = await sorobanRpc.simulateTransaction(
+        await buildSimulateTransaction(traderPublicKey, 'get_account_health', args)
+      );
+
+      if (!rpc.Api.isSimulationSuccess(result) || !result.result?.retval) return null;
+
+      const raw = scValToNative(result.result.retval) as RawAccountHealth;
+      pages.push(raw);
+
+      if (raw.positions.length < PAGE_SIZE) break;
+      startAfter = Number(raw.positions[raw.positions.length - 1].position_id);
+    }
+
+    const sum = (field: (page: RawAccountHealth) => bigint) =>
+      pages.reduce((total, page) => total + field(page), BigInt(0));
+
+    const totalSize = sum(p => p.total_size);
+    const totalEquity = sum(p => p.total_equity);
+    const marginRatioBps = totalSize > BigInt(0)
+      ? Number((totalEquity * BigInt(10000)) / totalSize)
+      : 10000;
+
+    return {
+      totalCollateral: bigIntToNumber(sum(p => p.total_collateral)),
+      totalSize: bigIntToNumber(totalSize),
+      totalUnrealizedPnl: bigIntToNumber(sum(p => p.total_unrealized_pnl)),
+      totalAccruedFunding: bigIntToNumber(sum(p => p.total_accrued_funding)),
+      totalAccruedBorrowFee: bigIntToNumber(sum(p => p.total_accrued_borrow_fee)),
+      totalEquity: bigIntToNumber(totalEquity),
+      marginRatioBps,
+      minLiquidationDistanceBps: Math.min(...pages.map(p => Number(p.min_liquidation_distance_bps))),
+      liquidatableCount: pages.reduce((count, p) => count + Number(p.liquidatable_count), 0),
+      estimatedLiquidationPayout: bigIntToNumber(sum(p => p.estimated_liquidation_payout)),
+      positions: pages.flatMap(p => p.positions.map(parsePositionHealth)),
+    };
+  } catch (error) {
+    console.error('Error fetching account health:', error);
+    return null;
+  }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // Trade History Functions
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -30,6 +30,9 @@ const USDC_TOKEN_ID = process.env.NEXT_PUBLIC_USDC_TOKEN_ID!;
 const RPC_URL = process.env.RPC_URL || 'https://soroban-testnet.stellar.org';
 const NETWORK_PASSPHRASE = process.env.NETWORK_PASSPHRASE || Networks.TESTNET;
 
+// Assets listed for trading after initialization, all with MARKET_CONFIG
+const ASSETS = ['XLM', 'BTC', 'ETH'];
+
 // Market configuration - matches MarketConfig struct in noether_common/src/types.rs
 const MARKET_CONFIG = {
   min_collateral: BigInt(10) * BigInt(10_000_000), // 10 USDC minimum
@@ -41,15 +44,42 @@ const MARKET_CONFIG = {
   max_position_size: BigInt(100_000) * BigInt(10_000_000), // 100,000 USDC max
   max_price_staleness: 60, // 60 seconds
   max_oracle_deviation_bps: 100, // 1%
+  withdraw_margin_buffer_bps: 100, // 1% above maintenance
+  insurance_fee_bps: 1000, // 10% of fees to insurance fund
+  partial_liquidation_target_bps: 300, // 3% margin after partial liquidation
+  full_liquidation_margin_bps: 50, // Full liquidation below 0.5% margin
+  adl_trigger_coverage_bps: 12000, // ADL when vault covers < 120% of trader PnL
+  adl_target_coverage_bps: 15000, // ADL restores 150% coverage
+  price_impact_skew_scale: BigInt(1000000000000000), // Skew (USD) at which impact reaches 100%
+  max_price_impact_bps: 100, // 1% max price impact
+  max_borrow_rate_bps: 1, // Hourly borrow rate at 100% utilization
+  max_long_open_interest: BigInt(50000000000000), // 5M USDC max total long open interest
+  max_short_open_interest: BigInt(50000000000000), // 5M USDC max total short open interest
 };
 
 function createMarketConfigScVal(config: typeof MARKET_CONFIG): xdr.ScVal {
   // Fields must be in alphabetical order for Soroban struct matching
   return xdr.ScVal.scvMap([
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('adl_target_coverage_bps'),
+      val: nativeToScVal(config.adl_target_coverage_bps, { type: 'u32' }),
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('adl_trigger_coverage_bps'),
+      val: nativeToScVal(config.adl_trigger_coverage_bps, { type: 'u32' }),
+    }),
     new xdr.ScMapEntry({
       key: xdr.ScVal.scvSymbol('base_funding_rate_bps'),
       val: nativeToScVal(config.base_funding_rate_bps, { type: 'u32' }),
     }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('full_liquidation_margin_bps'),
+      val: nativeToScVal(config.full_liquidation_margin_bps, { type: 'u32' }),
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('insurance_fee_bps'),
+      val: nativeToScVal(config.insurance_fee_bps, { type: 'u32' }),
+    }),
     new xdr.ScMapEntry({
       key: xdr.ScVal.scvSymbol('liquidation_fee_bps'),
       val: nativeToScVal(config.liquidation_fee_bps, { type: 'u32' }),
@@ -58,10 +88,18 @@ function createMarketConfigScVal(config: typeof MARKET_CONFIG): xdr.ScVal {
       key: xdr.ScVal.scvSymbol('maintenance_margin_bps'),
       val: nativeToScVal(config.maintenance_margin_bps, { type: 'u32' }),
     }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('max_borrow_rate_bps'),
+      val: nativeToScVal(config.max_borrow_rate_bps, { type: 'u32' }),
+    }),
     new xdr.ScMapEntry({
       key: xdr.ScVal.scvSymbol('max_leverage'),
       val: nativeToScVal(config.max_leverage, { type: 'u32' }),
     }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('max_long_open_interest'),
+      val: nativeToScVal(config.max_long_open_interest, { type: 'i128' }),
+    }),
     new xdr.ScMapEntry({
       key: xdr.ScVal.scvSymbol('max_oracle_deviation_bps'),
       val: nativeToScVal(config.max_oracle_deviation_bps, { type: 'u32' }),
@@ -70,21 +108,100 @@ function createMarketConfigScVal(config: typeof MARKET_CONFIG): xdr.ScVal {
       key: xdr.ScVal.scvSymbol('max_position_size'),
       val: nativeToScVal(config.max_position_size, { type: 'i128' }),
     }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('max_price_impact_bps'),
+      val: nativeToScVal(config.max_price_impact_bps, { type: 'u32' }),
+    }),
     new xdr.ScMapEntry({
       key: xdr.ScVal.scvSymbol('max_price_staleness'),
       val: nativeToScVal(config.max_price_staleness, { type: 'u64' }),
     }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('max_short_open_interest'),
+      val: nativeToScVal(config.max_short_open_interest, { type: 'i128' }),
+    }),
     new xdr.ScMapEntry({
       key: xdr.ScVal.scvSymbol('min_collateral'),
       val: nativeToScVal(config.min_collateral, { type: 'i128' }),
     }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('partial_liquidation_target_bps'),
+      val: nativeToScVal(config.partial_liquidation_target_bps, { type: 'u32' }),
+    }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('price_impact_skew_scale'),
+      val: nativeToScVal(config.price_impact_skew_scale, { type: 'i128' }),
+    }),
     new xdr.ScMapEntry({
       key: xdr.ScVal.scvSymbol('trading_fee_bps'),
       val: nativeToScVal(config.trading_fee_bps, { type: 'u32' }),
     }),
+    new xdr.ScMapEntry({
+      key: xdr.ScVal.scvSymbol('withdraw_margin_buffer_bps'),
+      val: nativeToScVal(config.withdraw_margin_buffer_bps, { type: 'u32' }),
+    }),
   ]);
 }
 
+/**
+ * Simulate, sign and submit a single admin contract call.
+ *
+ * @returns 'success', or 'skipped' when simulation fails with `skipError`
+ */
+async function submitCall(
+  sorobanRpc: rpc.Server,
+  adminKeypair: Keypair,
+  operation: xdr.Operation,
+  skipError: string,
+): Promise<'success' | 'skipped'> {
+  const account = await sorobanRpc.getAccount(adminKeypair.publicKey());
+  const transaction = new TransactionBuilder(account, {
+    fee: BASE_FEE,
+    networkPassphrase: NETWORK_PASSPHRASE,
+  })
+    .addOperation(operation)
+    .setTimeout(30)
+    .build();
+
+  const simulated = await sorobanRpc.simulateTransaction(transaction);
+
+  if (rpc.Api.isSimulationError(simulated)) {
+    if (simulated.error.includes(skipError)) {
+      return 'skipped';
+    }
+    throw new Error(`Simulation failed: ${simulated.error}`);
+  }
+
+  // Prepare and sign
+  const prepared = rpc.assembleTransaction(transaction, simulated).build();
+  prepared.sign(adminKeypair);
+
+  // Submit
+  const response = await sorobanRpc.sendTransaction(prepared);
+  console.log(`  Transaction hash: ${response.hash}`);
+
+  if (response.status === 'ERROR') {
+    throw new Error(`Transaction failed: ${JSON.stringify(response.errorResult)}`);
+  }
+
+  // Wait for confirmation
+  let result = await sorobanRpc.getTransaction(response.hash);
+  let attempts = 0;
+
+  while (result.status === 'NOT_FOUND' && attempts < 30) {
+    await new Promise(resolve => setTimeout(resolve, 1000));
+    result = await sorobanRpc.getTransaction(response.hash);
+    attempts++;
+    process.stdout.write('.');
+  }
+
+  if (result.status !== 'SUCCESS') {
+    throw new Error(`Transaction ${result.status}: ${JSON.stringify(result, null, 2)}`);
+  }
+
+  return 'success';
+}
+
 async function main() {
   console.log('='.repeat(60));
   console.log('Noether: Initialize Market Contract');
@@ -108,6 +225,7 @@ async function main() {
   console.log(`  Vault:          ${VAULT_CONTRACT_ID}`);
   console.log(`  Oracle Adapter: ${ORACLE_ADAPTER_ID}`);
   console.log(`  USDC Token:     ${USDC_TOKEN_ID}`);
+  console.log(`  Assets:         ${ASSETS.join(', ')}`);
   console.log(`  Max Leverage:   ${MARKET_CONFIG.max_leverage}x`);
   console.log(`  Min Collateral: ${Number(MARKET_CONFIG.min_collateral) / 10_000_000} USDC`);
   console.log(`  Maint Margin:   ${MARKET_CONFIG.maintenance_margin_bps / 100}%`);
@@ -115,13 +233,8 @@ async function main() {
   console.log(`  Trading Fee:    ${MARKET_CONFIG.trading_fee_bps / 100}%`);
 
   try {
-    // Step 1: Get account
-    console.log('\n[1/3] Fetching account...');
-    const account = await sorobanRpc.getAccount(adminPublicKey);
-    console.log(`  Account sequence: ${account.sequenceNumber()}`);
-
-    // Step 2: Build initialization transaction
-    console.log('\n[2/3] Building initialization transaction...');
+    // Step 1: Initialize
+    console.log('\n[1/2] Initializing market...');
 
     // initialize(admin, oracle_adapter, vault, usdc_token, config)
     const args = [
@@ -132,75 +245,38 @@ async function main() {
       createMarketConfigScVal(MARKET_CONFIG),     // config
     ];
 
-    const operation = marketContract.call('initialize', ...args);
-
-    const transaction = new TransactionBuilder(account, {
-      fee: BASE_FEE,
-      networkPassphrase: NETWORK_PASSPHRASE,
-    })
-      .addOperation(operation)
-      .setTimeout(30)
-      .build();
-
-    // Step 3: Simulate and submit
-    console.log('\n[3/3] Simulating and submitting...');
-    const simulated = await sorobanRpc.simulateTransaction(transaction);
-
-    if (rpc.Api.isSimulationError(simulated)) {
-      console.error('Simulation failed:', simulated.error);
-
-      // Check if already initialized
-      if (simulated.error.includes('AlreadyInitialized') || simulated.error.includes('#2')) {
-        console.log('\n  Market is already initialized! Skipping...');
-        return;
-      }
-      process.exit(1);
-    }
-
-    console.log('  Simulation successful!');
+    // AlreadyInitialized = #2
+    const initialized = await submitCall(
+      sorobanRpc,
+      adminKeypair,
+      marketContract.call('initialize', ...args),
+      'Error(Contract, #2)',
+    );
+    console.log(initialized === 'skipped' ? '  Market is already initialized! Skipping...' : '  Market initialized.');
 
-    // Prepare and sign
-    const prepared = rpc.assembleTransaction(transaction, simulated).build();
-    prepared.sign(adminKeypair);
-
-    // Submit
-    const response = await sorobanRpc.sendTransaction(prepared);
-    console.log(`  Transaction hash: ${response.hash}`);
-
-    if (response.status === 'ERROR') {
-      console.error('Transaction failed:', response.errorResult);
-      process.exit(1);
-    }
-
-    // Wait for confirmation
-    console.log('  Waiting for confirmation...');
-    let result = await sorobanRpc.getTransaction(response.hash);
-    let attempts = 0;
-
-    while (result.status === 'NOT_FOUND' && attempts < 30) {
-      await new Promise(resolve => setTimeout(resolve, 1000));
-      result = await sorobanRpc.getTransaction(response.hash);
-      attempts++;
-      process.stdout.write('.');
-    }
-    console.log();
-
-    if (result.status === 'SUCCESS') {
-      console.log('\n' + '='.repeat(60));
-      console.log('SUCCESS! Market contract initialized.');
-      console.log('='.repeat(60));
-      console.log(`\nMarket ${MARKET_CONTRACT_ID.slice(0, 8)}... is now ready!`);
-      console.log(`Vault ${VAULT_CONTRACT_ID.slice(0, 8)}... is connected.`);
-      console.log(`Oracle ${ORACLE_ADAPTER_ID.slice(0, 8)}... is connected.`);
-    } else if (result.status === 'FAILED') {
-      console.error('\nTransaction FAILED on-chain.');
-      console.error('Result:', JSON.stringify(result, null, 2));
-      process.exit(1);
-    } else {
-      console.error('\nTransaction status unknown:', result.status);
-      process.exit(1);
+    // Step 2: List assets - trading requires each asset to be listed
+    console.log('\n[2/2] Listing assets...');
+    for (const asset of ASSETS) {
+      // list_asset(asset, config); an already listed asset fails with InvalidParameter = #5
+      const listed = await submitCall(
+        sorobanRpc,
+        adminKeypair,
+        marketContract.call(
+          'list_asset',
+          nativeToScVal(asset, { type: 'symbol' }),
+          createMarketConfigScVal(MARKET_CONFIG),
+        ),
+        'Error(Contract, #5)',
+      );
+      console.log(`  ${asset}: ${listed === 'skipped' ? 'already listed' : 'listed'}`);
     }
 
+    console.log('\n' + '='.repeat(60));
+    console.log('SUCCESS! Market contract initialized.');
+    console.log('='.repeat(60));
+    console.log(`\nMarket ${MARKET_CONTRACT_ID.slice(0, 8)}... is now ready!`);
+    console.log(`Vault ${VAULT_CONTRACT_ID.slice(0, 8)}... is connected.`);
+    console.log(`Oracle ${ORACLE_ADAPTER_ID.slice(0, 8)}... is connected.`);
   } catch (error) {
     console.error('\nError:', error);
     process.exit(1);
@@ -2,7 +2,7 @@
 export type Direction = 'Long' | 'Short';
 
 // Order type
-export type OrderType = 'LimitEntry' | 'StopLoss' | 'TakeProfit';
+export type OrderType = 'LimitEntry' | 'StopLoss' | 'TakeProfit' | 'TrailingStop';
 
 // Order status
 export type OrderStatus = 'Pending' | 'Executed' | 'Cancelled' | 'CancelledSlippage' | 'Expired';
@@ -37,9 +37,15 @@ export interface Order {
   triggerPrice: bigint;
   triggerCondition: TriggerCondition;
   slippageToleranceBps: number;
+  closeBps: number; // Close orders: fraction of the position to close (10000 = full)
+  closeSize: bigint; // Close orders: size to close, fixed at placement (0 = full)
+  trailingDistanceBps: number; // TrailingStop only
+  trailingMark: bigint; // TrailingStop only: best price seen
   positionId: number;
   hasPosition: boolean;
   createdAt: number;
+  expiresAt: number; // 0 = never expires
+  executionFee: bigint; // Prepaid keeper fee held in escrow
   status: OrderStatus;
 }
 
@@ -55,9 +61,15 @@ export interface DisplayOrder {
   triggerPrice: number;
   triggerCondition: TriggerCondition;
   slippageToleranceBps: number;
+  closeBps: number;
+  closeSize: number;
+  trailingDistanceBps: number;
+  trailingMark: number;
   positionId: number;
   hasPosition: boolean;
   createdAt: Date;
+  expiresAt: Date | null;
+  executionFee: number;
   status: OrderStatus;
   // Calculated fields
   positionSize: number;
@@ -74,6 +86,17 @@ export interface MarketConfig {
   maxPositionSize: bigint;
   maxPriceStaleness: number;
   maxOracleDeviationBps: number;
+  withdrawMarginBufferBps: number;
+  insuranceFeeBps: number;
+  partialLiquidationTargetBps: number;
+  fullLiquidationMarginBps: number;
+  adlTriggerCoverageBps: number;
+  adlTargetCoverageBps: number;
+  priceImpactSkewScale: bigint;
+  maxPriceImpactBps: number;
+  maxBorrowRateBps: number;
+  maxLongOpenInterest: bigint;
+  maxShortOpenInterest: bigint;
 }
 
 // Pool/Vault information
@@ -82,6 +105,7 @@ export interface PoolInfo {
   totalNoe: bigint;
   unrealizedPnl: bigint;
   totalFees: bigint;
+  reservedUsdc: bigint;
   noePrice: bigint;
 }
 
@@ -108,6 +132,36 @@ export interface DisplayPosition {
   openedAt: Date;
 }
 
+// Position health at the current oracle price (from get_position_health)
+export interface PositionHealth {
+  positionId: number;
+  currentPrice: number;
+  unrealizedPnl: number;
+  accruedFunding: number; // Funding owed (positive = trader pays)
+  accruedBorrowFee: number; // Borrow fees owed to the vault
+  equity: number; // Collateral + PnL - accrued funding - accrued borrow fees
+  marginRatioBps: number;
+  distanceToLiquidationBps: number; // 0 = liquidatable
+  isLiquidatable: boolean;
+  estimatedLiquidationPayout: number; // Keeper reward a liquidation would pay now
+  estimatedBadDebt: number;
+}
+
+// Aggregate health of a trader's positions (from get_account_health)
+export interface AccountHealthInfo {
+  totalCollateral: number;
+  totalSize: number;
+  totalUnrealizedPnl: number;
+  totalAccruedFunding: number;
+  totalAccruedBorrowFee: number;
+  totalEquity: number;
+  marginRatioBps: number;
+  minLiquidationDistanceBps: number;
+  liquidatableCount: number;
+  estimatedLiquidationPayout: number;
+  positions: PositionHealth[];
+}
+
 // Trade for history
 export interface Trade {
   id: string;